@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use axum::{
     Json, Router,
-    extract::{Path as AxumPath, State},
+    extract::{FromRequest, Path as AxumPath, State, rejection::JsonRejection},
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
@@ -16,6 +16,7 @@ use serde_json::json;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::Mutex;
 use tower_http::{
     cors::{Any, CorsLayer},
@@ -40,6 +41,8 @@ struct AppState {
 struct ApiError {
     status: StatusCode,
     message: String,
+    /// Full error chain for server logs; never sent to the client.
+    detail: Option<String>,
 }
 
 impl ApiError {
@@ -47,6 +50,7 @@ impl ApiError {
         Self {
             status: StatusCode::BAD_REQUEST,
             message: message.into(),
+            detail: None,
         }
     }
 
@@ -54,6 +58,7 @@ impl ApiError {
         Self {
             status: StatusCode::NOT_FOUND,
             message: message.into(),
+            detail: None,
         }
     }
 
@@ -61,30 +66,76 @@ impl ApiError {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
             message: message.into(),
+            detail: None,
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        (
-            self.status,
-            Json(json!({
-                "error": self.message,
-                "status": self.status.as_u16()
-            })),
-        )
-            .into_response()
+        let mut body = json!({
+            "error": self.message,
+            "status": self.status.as_u16()
+        });
+        if self.status.is_server_error() {
+            let correlation_id = next_correlation_id();
+            error!(
+                correlation_id = %correlation_id,
+                status = self.status.as_u16(),
+                detail = self.detail.as_deref().unwrap_or(&self.message),
+                "request failed"
+            );
+            body["correlation_id"] = json!(correlation_id);
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Ties a sanitized 5xx body to the server log line carrying its detail.
+fn next_correlation_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "{:x}-{:x}",
+        Utc::now().timestamp_millis(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn error_chain(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut chain = format!("{err:?}");
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push_str(&format!(": caused by {cause:?}"));
+        source = cause.source();
+    }
+    chain
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self {
+            status: rejection.status(),
+            message: format!("invalid JSON body: {}", rejection.body_text()),
+            detail: None,
+        }
     }
 }
 
+/// `Json` extractor whose rejections use the same error body as every other handler.
+#[derive(FromRequest)]
+#[from_request(via(Json), rejection(ApiError))]
+struct ApiJson<T>(T);
+
 impl From<DbError> for ApiError {
     fn from(value: DbError) -> Self {
         let message = value.to_string();
         if message.to_ascii_lowercase().contains("todo not found") {
             return ApiError::not_found(message);
         }
-        ApiError::internal(message)
+        ApiError {
+            detail: Some(error_chain(&value)),
+            ..ApiError::internal("internal server error")
+        }
     }
 }
 
@@ -284,7 +335,16 @@ pub async fn bootstrap(config: &AppConfig) -> Result<TodoBootstrap> {
     };
 
     let persist_app = PersistApp::open_auto_with(config.data_dir.clone(), policy).await?;
-    let todos = Arc::new(Mutex::new(persist_app.open_vec::<TodoVec>("todo_api").await?));
+    let todos = persist_app.open_vec::<TodoVec>("todo_api").await?;
+    let recovery = todos.recovery_report();
+    info!(
+        snapshot_loaded = recovery.snapshot_loaded,
+        ops_replayed = recovery.ops_replayed,
+        duration_ms = recovery.duration.as_millis() as u64,
+        last_version = recovery.last_version,
+        "recovered todo collection"
+    );
+    let todos = Arc::new(Mutex::new(todos));
 
     let router = build_router(todos.clone(), config.durability_mode.clone());
 
@@ -323,7 +383,7 @@ async fn health() -> Json<HealthResponse> {
 
 async fn create_todo(
     State(state): State<AppState>,
-    Json(payload): Json<CreateTodoRequest>,
+    ApiJson(payload): ApiJson<CreateTodoRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     let title = payload.title.trim();
     if title.is_empty() {
@@ -380,7 +440,7 @@ async fn get_todo(
 async fn patch_todo(
     State(state): State<AppState>,
     AxumPath(id): AxumPath<String>,
-    Json(payload): Json<PatchTodoRequest>,
+    ApiJson(payload): ApiJson<PatchTodoRequest>,
 ) -> Result<Json<TodoResponse>, ApiError> {
     if payload.title.is_none() && payload.completed.is_none() && payload.priority.is_none() {
         return Err(ApiError::bad_request(
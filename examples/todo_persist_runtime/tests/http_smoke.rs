@@ -130,6 +130,46 @@ async fn http_smoke_crud_snapshot_and_recovery_without_socket() {
     boot2.shutdown().await.expect("shutdown #2");
 }
 
+#[tokio::test]
+async fn http_malformed_json_body_returns_json_error() {
+    let data = tempdir().expect("temp dir");
+    let config = AppConfig::for_testing(data.path().join("primary"));
+    let boot = bootstrap(&config).await.expect("bootstrap");
+
+    let (status, body) = request_json(
+        &boot.router,
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/todos")
+            .header("content-type", "application/json")
+            .body(Body::from("{\"title\": "))
+            .expect("valid request"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body.get("status").and_then(|v| v.as_u64()), Some(400));
+    assert!(
+        body.get("error")
+            .and_then(|v| v.as_str())
+            .is_some_and(|message| message.starts_with("invalid JSON body"))
+    );
+
+    let (status, body) = request_json(
+        &boot.router,
+        Request::builder()
+            .method("POST")
+            .uri("/api/v1/todos")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({"title": 42}).to_string()))
+            .expect("valid request"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body.get("status").and_then(|v| v.as_u64()), Some(422));
+
+    boot.shutdown().await.expect("shutdown");
+}
+
 async fn request_json(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
     let response = app
         .clone()
@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
+use std::collections::HashMap;
 use syn::parse::Parser;
 use syn::punctuated::Punctuated;
 use syn::{
@@ -9,7 +10,7 @@ use syn::{
     spanned::Spanned,
 };
 
-#[proc_macro_derive(PersistModel, attributes(persist_model, sql))]
+#[proc_macro_derive(PersistModel, attributes(persist_model, persist, sql, aggregate))]
 pub fn derive_persist_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     match expand_persist_model(input) {
@@ -18,6 +19,33 @@ pub fn derive_persist_model(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(PersistEnumInt)]
+pub fn derive_persist_enum_int(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_persist_enum_int(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(PersistEnumText)]
+pub fn derive_persist_enum_text(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_persist_enum_text(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(PersistFlatten)]
+pub fn derive_persist_flatten(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_persist_flatten(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_attribute]
 pub fn persistent(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemStruct);
@@ -34,11 +62,7 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
         Err(err) => return err.to_compile_error().into(),
     };
 
-    let marker_value = marker
-        .name
-        .as_ref()
-        .map(|name| format!("__rustmemodb_command:{name}"))
-        .unwrap_or_else(|| "__rustmemodb_command".to_string());
+    let marker_value = command_doc_marker(&marker);
 
     if let Ok(mut method) = syn::parse::<ImplItemFn>(item.clone()) {
         method
@@ -60,6 +84,33 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn computed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[computed] does not accept arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    match syn::parse::<ImplItemFn>(item) {
+        Ok(mut method) => {
+            method
+                .attrs
+                .push(syn::parse_quote!(#[doc = "__rustmemodb_computed"]));
+            quote!(#method).into()
+        }
+        Err(_) => syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[computed] can only be applied to impl methods",
+        )
+        .to_compile_error()
+        .into(),
+    }
+}
+
 #[proc_macro_attribute]
 pub fn persistent_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
     if !attr.is_empty() {
@@ -86,7 +137,18 @@ fn expand_persistent_attr(attr: TokenStream2, item_struct: ItemStruct) -> syn::R
         .iter()
         .any(|attr| attr.path().is_ident("persist_model"));
 
-    if has_persist_model_attr && (options.table_name.is_some() || options.schema_version.is_some()) {
+    let mut persist_model_parts = Vec::new();
+    if let Some(table) = &options.table_name {
+        persist_model_parts.push(quote!(table = #table));
+    }
+    if let Some(version) = options.schema_version {
+        persist_model_parts.push(quote!(schema_version = #version));
+    }
+    if let Some(migrations) = &options.migrations {
+        persist_model_parts.push(quote!(migrations = #migrations));
+    }
+
+    if has_persist_model_attr && !persist_model_parts.is_empty() {
         return Err(syn::Error::new(
             item_struct.span(),
             "#[persistent(...)] options conflict with existing #[persist_model(...)] attribute",
@@ -98,24 +160,8 @@ fn expand_persistent_attr(attr: TokenStream2, item_struct: ItemStruct) -> syn::R
         injected.push(quote!(#[derive(::rustmemodb::PersistModel)]));
     }
 
-    if !has_persist_model_attr && (options.table_name.is_some() || options.schema_version.is_some())
-    {
-        let table_part = options.table_name.as_ref().map(|table| {
-            quote!(table = #table)
-        });
-        let schema_part = options.schema_version.map(|version| {
-            quote!(schema_version = #version)
-        });
-
-        let persist_model_attr = match (table_part, schema_part) {
-            (Some(table), Some(schema)) => quote!(#[persist_model(#table, #schema)]),
-            (Some(table), None) => quote!(#[persist_model(#table)]),
-            (None, Some(schema)) => quote!(#[persist_model(#schema)]),
-            (None, None) => quote!(),
-        };
-        if !persist_model_attr.is_empty() {
-            injected.push(persist_model_attr);
-        }
+    if !persist_model_parts.is_empty() {
+        injected.push(quote!(#[persist_model(#(#persist_model_parts),*)]));
     }
 
     Ok(quote! {
@@ -137,21 +183,98 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
     let command_enum_ident = format_ident!("{}PersistentCommand", model_ident);
 
     let mut commands = Vec::<PersistentCommandMethod>::new();
+    let mut computed_methods = Vec::<Ident>::new();
     for item in &mut item_impl.items {
         let ImplItem::Fn(method) = item else {
             continue;
         };
 
+        if extract_computed_marker(&mut method.attrs)? {
+            validate_computed_method(method)?;
+            computed_methods.push(method.sig.ident.clone());
+            continue;
+        }
+
         let marker = extract_command_marker(&mut method.attrs)?;
         let Some(marker) = marker else {
             continue;
         };
 
-        commands.push(PersistentCommandMethod::from_impl_method(method, marker)?);
+        let command = PersistentCommandMethod::from_impl_method(method, marker)?;
+        // Only other commands collide: a read-only #[computed] value may share a command's name.
+        if let Some(name) = command.all_names().find(|name| {
+            commands
+                .iter()
+                .any(|existing| existing.all_names().any(|existing| existing == *name))
+        }) {
+            return Err(syn::Error::new(
+                method.sig.span(),
+                format!("Duplicate #[command] name '{}'", name),
+            ));
+        }
+        commands.push(command);
     }
 
+    let computed_impl = if computed_methods.is_empty() {
+        quote!()
+    } else {
+        let computed_names = computed_methods
+            .iter()
+            .map(|method| method.to_string())
+            .collect::<Vec<_>>();
+        quote! {
+            impl #persisted_ident {
+                pub fn computed_field_names() -> &'static [&'static str] {
+                    &[#(#computed_names),*]
+                }
+
+                pub fn computed_fields_json(
+                    &self,
+                ) -> ::rustmemodb::Result<serde_json::Map<String, serde_json::Value>> {
+                    let mut computed = serde_json::Map::new();
+                    #(
+                        computed.insert(
+                            #computed_names.to_string(),
+                            serde_json::to_value(&self.data.#computed_methods()).map_err(|err| {
+                                ::rustmemodb::persist::serde_to_db_error(
+                                    &format!("serialize computed field '{}'", #computed_names),
+                                    err,
+                                )
+                            })?,
+                        );
+                    )*
+                    Ok(computed)
+                }
+
+                /// `state_json()` plus every `#[computed]` value; computed fields are never persisted.
+                pub fn state_json_with_computed(&self) -> ::rustmemodb::Result<serde_json::Value> {
+                    let mut json = self.state_json();
+                    let object = json.as_object_mut().ok_or_else(|| {
+                        ::rustmemodb::DbError::ExecutionError(
+                            "Persist state must be a JSON object".to_string(),
+                        )
+                    })?;
+                    for (name, value) in self.computed_fields_json()? {
+                        if object.contains_key(&name) {
+                            return Err(::rustmemodb::DbError::ExecutionError(format!(
+                                "Computed field '{}' collides with a stored field of {}",
+                                name,
+                                stringify!(#model_ident),
+                            )));
+                        }
+                        object.insert(name, value);
+                    }
+                    Ok(json)
+                }
+            }
+        }
+    };
+
     if commands.is_empty() {
-        return Ok(quote!(#item_impl));
+        return Ok(quote! {
+            #item_impl
+            #computed_impl
+        });
     }
 
     let enum_variants = commands.iter().map(|cmd| {
@@ -178,6 +301,16 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
         }
     });
 
+    let enum_describe_arms = commands.iter().map(|cmd| {
+        let variant = &cmd.variant_ident;
+        let description = cmd.description.as_str();
+        if cmd.args.is_empty() {
+            quote!(Self::#variant => #description)
+        } else {
+            quote!(Self::#variant { .. } => #description)
+        }
+    });
+
     let payload_arms = commands.iter().map(|cmd| {
         let variant = &cmd.variant_ident;
         let arg_idents = cmd
@@ -229,10 +362,10 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
     });
 
     let schema_by_name_arms = commands.iter().map(|cmd| {
-        let command_name = cmd.command_name.as_str();
+        let names = cmd.all_names();
         let schema_expr = build_runtime_payload_schema_expr(&cmd.args);
         quote! {
-            #command_name => Some(#schema_expr)
+            #(#names)|* => Some(#schema_expr)
         }
     });
 
@@ -248,15 +381,33 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
 
         let method_call = quote!(self.data.#method_ident(#(#args),*));
         let body = cmd.return_kind.build_command_body(method_call);
+        let command_name = cmd.command_name.as_str();
+        let arg_names = args.iter().map(|arg| arg.to_string());
         quote! {
             #pattern => {
+                {
+                    use ::rustmemodb::persist::{
+                        PersistValidateFallback as _, PersistValidateViaTrait as _,
+                    };
+                    #(
+                        (&::rustmemodb::persist::PersistValidateProbe(&#args))
+                            .persist_validate()
+                            .map_err(|message| {
+                                ::rustmemodb::persist::command_input_error(
+                                    stringify!(#model_ident),
+                                    #command_name,
+                                    #arg_names,
+                                    &message,
+                                )
+                            })?;
+                    )*
+                }
                 #body
             }
         }
     });
 
-    let command_contract_entries = commands.iter().map(|cmd| {
-        let command_name = cmd.command_name.as_str();
+    let command_contract_entries = commands.iter().flat_map(|cmd| {
         let field_entries = cmd.args.iter().map(|arg| {
             let field_name = arg.ident.to_string();
             let ty = &arg.ty;
@@ -269,17 +420,52 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
             }
         });
 
-        quote! {
-            ::rustmemodb::persist::PersistCommandContract {
-                name: #command_name.to_string(),
-                fields: vec![#(#field_entries),*],
-                mutates_state: true,
-            }
-        }
+        let field_entries = field_entries.collect::<Vec<_>>();
+
+        cmd.all_names()
+            .map(|command_name| {
+                let deprecated = match cmd.deprecation_for(command_name) {
+                    Some(note) => quote!(Some(#note.to_string())),
+                    None => quote!(None),
+                };
+                quote! {
+                    ::rustmemodb::persist::PersistCommandContract {
+                        name: #command_name.to_string(),
+                        fields: vec![#(#field_entries),*],
+                        mutates_state: true,
+                        deprecated: #deprecated,
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let min_interval_arms = commands.iter().filter_map(|cmd| {
+        let names = cmd.all_names();
+        cmd.min_interval_ms
+            .map(|interval| quote!(#(#names)|* => Some(#interval)))
+    });
+
+    let serialized_arms = commands
+        .iter()
+        .filter(|cmd| cmd.serialize_per_entity)
+        .map(|cmd| {
+            let names = cmd.all_names();
+            quote!(#(#names)|* => true)
+        });
+
+    let deprecation_arms = commands.iter().flat_map(|cmd| {
+        cmd.all_names()
+            .filter_map(|command_name| {
+                cmd.deprecation_for(command_name)
+                    .map(|note| quote!(#command_name => Some(#note)))
+            })
+            .collect::<Vec<_>>()
     });
 
     let runtime_registration_entries = commands.iter().map(|cmd| {
         let command_name = cmd.command_name.as_str();
+        let registered_names = cmd.all_names();
         let variant = &cmd.variant_ident;
         let schema_expr = build_runtime_payload_schema_expr(&cmd.args);
 
@@ -317,10 +503,22 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
             quote!(#command_enum_ident::#variant { #(#fields),* })
         };
 
+        let min_interval_registration = cmd.min_interval_ms.map(|interval| {
+            quote! {
+                runtime.register_command_min_interval(
+                    stringify!(#model_ident),
+                    registered_name,
+                    #interval,
+                );
+            }
+        });
+
         quote! {
+            for registered_name in [#(#registered_names),*] {
+            #min_interval_registration
             runtime.register_deterministic_context_command_with_schema(
                 stringify!(#model_ident),
-                #command_name,
+                registered_name,
                 #schema_expr,
                 ::std::sync::Arc::new(|state, payload, _ctx| {
                     let payload_obj = payload.as_object().ok_or_else(|| {
@@ -337,11 +535,13 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
                     Ok(Vec::new())
                 }),
             );
+            }
         }
     });
 
     Ok(quote! {
         #item_impl
+        #computed_impl
 
         pub enum #command_enum_ident {
             #(#enum_variants),*
@@ -354,6 +554,13 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
                 }
             }
 
+            /// First doc-comment line of the command method, or its name when undocumented.
+            pub fn describe(&self) -> &'static str {
+                match self {
+                    #(#enum_describe_arms),*
+                }
+            }
+
             pub fn payload_json(&self) -> ::rustmemodb::Result<serde_json::Value> {
                 match self {
                     #(#payload_arms),*
@@ -398,13 +605,73 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
                 }
             }
 
+            pub fn domain_command_min_interval_ms(command_name: &str) -> Option<u64> {
+                match command_name {
+                    #(#min_interval_arms,)*
+                    _ => None,
+                }
+            }
+
+            pub fn domain_command_deprecation(command_name: &str) -> Option<&'static str> {
+                match command_name {
+                    #(#deprecation_arms,)*
+                    _ => None,
+                }
+            }
+
+            pub fn domain_command_serialized_per_entity(command_name: &str) -> bool {
+                match command_name {
+                    #(#serialized_arms,)*
+                    _ => false,
+                }
+            }
+
             pub async fn apply_domain_command_persisted(
                 &mut self,
                 command: #command_enum_ident,
             ) -> ::rustmemodb::Result<serde_json::Value> {
-                let result = self.apply_domain_command(command)?;
-                self.save_bound().await?;
-                Ok(result)
+                let command_name = command.name();
+                // Held until the command is saved; a copy that went stale while
+                // waiting is reloaded so it does not hit an optimistic conflict.
+                let _entity_lock = if Self::domain_command_serialized_per_entity(command_name) {
+                    let guard = ::rustmemodb::persist::lock_entity_commands(
+                        stringify!(#model_ident),
+                        self.persist_id(),
+                    )
+                    .await;
+                    if self.has_bound_session() {
+                        self.reload_bound_if_stale().await?;
+                    }
+                    Some(guard)
+                } else {
+                    None
+                };
+                let limits = match Self::domain_command_min_interval_ms(command_name) {
+                    Some(min_interval_ms) => {
+                        let session = self.bound_session().cloned().ok_or_else(|| {
+                            ::rustmemodb::DbError::ExecutionError(
+                                "No bound PersistSession for apply_domain_command_persisted"
+                                    .to_string(),
+                            )
+                        })?;
+                        let reservation = session.command_limits().try_acquire(
+                            stringify!(#model_ident),
+                            command_name,
+                            self.persist_id(),
+                            min_interval_ms,
+                        )?;
+                        Some((session, reservation))
+                    }
+                    None => None,
+                };
+                let result = match self.apply_domain_command(command) {
+                    Ok(result) => self.save_bound().await.map(|()| result),
+                    Err(err) => Err(err),
+                };
+                if let (Err(_), Some((session, reservation))) = (&result, limits) {
+                    session.command_limits().release(reservation);
+                }
+                result
             }
 
             pub fn domain_command_envelope(
@@ -443,116 +710,593 @@ fn expand_persistent_impl_attr(mut item_impl: ItemImpl) -> syn::Result<TokenStre
                 #(#runtime_registration_entries)*
                 Ok(())
             }
+
+            pub fn runtime_model_registration() -> ::rustmemodb::RuntimeModelRegistration {
+                ::rustmemodb::RuntimeModelRegistration::new(
+                    stringify!(#model_ident),
+                    Self::default_table_name(),
+                    Self::try_register_domain_commands_in_runtime,
+                )
+            }
         }
     })
 }
 
-fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
-    let struct_name = input.ident;
-    let vis = input.vis;
+fn expand_persist_enum_int(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let enum_name = input.ident;
 
     if !input.generics.params.is_empty() {
         return Err(syn::Error::new_spanned(
             input.generics,
-            "PersistModel does not support generic structs yet",
+            "PersistEnumInt does not support generic enums",
         ));
     }
 
-    let model_options = parse_persist_model_options(&input.attrs)?;
-
-    let data_struct = match input.data {
-        Data::Struct(data) => data,
+    let data_enum = match input.data {
+        Data::Enum(data) => data,
         _ => {
             return Err(syn::Error::new(
-                struct_name.span(),
-                "PersistModel can only be derived for structs",
+                enum_name.span(),
+                "PersistEnumInt can only be derived for enums",
             ));
         }
     };
 
-    let named_fields = match data_struct.fields {
-        Fields::Named(fields) => fields,
-        _ => {
+    if data_enum.variants.is_empty() {
+        return Err(syn::Error::new(
+            enum_name.span(),
+            "PersistEnumInt requires at least one variant",
+        ));
+    }
+
+    let mut variant_idents = Vec::<Ident>::new();
+    for variant in data_enum.variants {
+        if !matches!(variant.fields, Fields::Unit) {
             return Err(syn::Error::new(
-                struct_name.span(),
-                "PersistModel requires named fields",
+                variant.span(),
+                "PersistEnumInt requires unit variants without fields",
             ));
         }
-    };
+        variant_idents.push(variant.ident);
+    }
 
-    let mut field_idents = Vec::<Ident>::new();
-    let mut field_types = Vec::<Type>::new();
-    let mut field_sql_options = Vec::<Option<SqlFieldOptions>>::new();
+    Ok(quote! {
+        impl #enum_name {
+            pub fn to_persist_int(&self) -> i64 {
+                match self {
+                    #( Self::#variant_idents => Self::#variant_idents as i64, )*
+                }
+            }
+        }
 
-    for field in named_fields.named {
-        let ident = field.ident.clone().ok_or_else(|| {
-            syn::Error::new(field.span(), "PersistModel requires named fields")
-        })?;
-        let sql_options = parse_sql_field_options(&field.attrs)?;
-        field_idents.push(ident);
-        field_types.push(field.ty);
-        field_sql_options.push(sql_options);
+        impl TryFrom<i64> for #enum_name {
+            type Error = ::rustmemodb::DbError;
+
+            fn try_from(value: i64) -> ::rustmemodb::Result<Self> {
+                #(
+                    if value == Self::#variant_idents as i64 {
+                        return Ok(Self::#variant_idents);
+                    }
+                )*
+                Err(::rustmemodb::DbError::TypeMismatch(format!(
+                    "Invalid discriminant {} for enum {}",
+                    value,
+                    stringify!(#enum_name)
+                )))
+            }
+        }
+
+        impl serde::Serialize for #enum_name {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_i64(self.to_persist_int())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #enum_name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = <i64 as serde::Deserialize>::deserialize(deserializer)?;
+                Self::try_from(value).map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl ::rustmemodb::PersistValue for #enum_name {
+            fn sql_type() -> &'static str {
+                "INTEGER"
+            }
+
+            fn to_sql_literal(&self) -> String {
+                self.to_persist_int().to_string()
+            }
+        }
+    })
+}
+
+fn expand_persist_enum_text(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let enum_name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input.generics,
+            "PersistEnumText does not support generic enums",
+        ));
     }
 
-    if field_idents.is_empty() {
+    let data_enum = match input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                enum_name.span(),
+                "PersistEnumText can only be derived for enums",
+            ));
+        }
+    };
+
+    if data_enum.variants.is_empty() {
         return Err(syn::Error::new(
-            struct_name.span(),
-            "PersistModel requires at least one field",
+            enum_name.span(),
+            "PersistEnumText requires at least one variant",
         ));
     }
 
-    let persisted_name = format_ident!("{}Persisted", struct_name);
-    let draft_name = format_ident!("{}Draft", persisted_name);
-    let patch_name = format_ident!("{}Patch", persisted_name);
-    let command_name = format_ident!("{}Command", persisted_name);
-    let command_variant_idents = field_idents
+    let mut variant_idents = Vec::<Ident>::new();
+    for variant in data_enum.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "PersistEnumText requires unit variants without fields",
+            ));
+        }
+        variant_idents.push(variant.ident);
+    }
+
+    let variant_names = variant_idents
         .iter()
-        .map(|field| format_ident!("Set{}", to_pascal_case(&field.to_string())))
+        .map(|ident| ident.to_string())
         .collect::<Vec<_>>();
+    let sql_type = format!(
+        "ENUM({})",
+        variant_names
+            .iter()
+            .map(|name| format!("'{}'", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
 
-    let default_table_expr = match model_options.table_name {
-        Some(table_name) => quote! { #table_name.to_string() },
-        None => quote! { ::rustmemodb::persist::default_table_name_stable(stringify!(#struct_name)) },
-    };
-    let schema_version_literal = model_options
-        .schema_version
-        .unwrap_or(1u32);
-    let has_explicit_projection_attrs = field_sql_options.iter().any(|options| options.is_some());
+    Ok(quote! {
+        impl #enum_name {
+            pub fn to_persist_text(&self) -> &'static str {
+                match self {
+                    #( Self::#variant_idents => #variant_names, )*
+                }
+            }
+        }
 
-    let mut projection_contract_fields = Vec::<TokenStream2>::new();
-    let mut projection_index_helpers = Vec::<TokenStream2>::new();
-    for ((field_ident, field_ty), field_sql) in field_idents
-        .iter()
-        .zip(field_types.iter())
-        .zip(field_sql_options.iter())
-    {
-        let include = match field_sql {
-            Some(options) => options.include,
-            None => !has_explicit_projection_attrs,
-        };
-        if !include {
-            continue;
+        impl TryFrom<&str> for #enum_name {
+            type Error = ::rustmemodb::DbError;
+
+            fn try_from(value: &str) -> ::rustmemodb::Result<Self> {
+                match value {
+                    #( #variant_names => Ok(Self::#variant_idents), )*
+                    other => Err(::rustmemodb::DbError::TypeMismatch(format!(
+                        "Invalid value '{}' for enum {}",
+                        other,
+                        stringify!(#enum_name)
+                    ))),
+                }
+            }
         }
 
-        let indexed = field_sql.as_ref().map(|options| options.indexed).unwrap_or(false);
-        let state_field_name = field_ident.to_string();
-        let column_name = field_sql
-            .as_ref()
-            .and_then(|options| options.column_name.clone())
-            .unwrap_or_else(|| state_field_name.clone());
-        let payload_type = runtime_payload_type_tokens(field_ty);
-        projection_contract_fields.push(quote! {
+        impl serde::Serialize for #enum_name {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.to_persist_text())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #enum_name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+                Self::try_from(value.as_str()).map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl ::rustmemodb::PersistValue for #enum_name {
+            fn sql_type() -> &'static str {
+                #sql_type
+            }
+
+            fn to_sql_literal(&self) -> String {
+                format!("'{}'", self.to_persist_text())
+            }
+        }
+    })
+}
+
+fn expand_persist_flatten(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input.generics,
+            "PersistFlatten does not support generic structs",
+        ));
+    }
+
+    let named_fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    struct_name.span(),
+                    "PersistFlatten requires named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                struct_name.span(),
+                "PersistFlatten can only be derived for structs",
+            ));
+        }
+    };
+
+    if named_fields.is_empty() {
+        return Err(syn::Error::new(
+            struct_name.span(),
+            "PersistFlatten requires at least one field",
+        ));
+    }
+
+    let field_idents = named_fields
+        .iter()
+        .filter_map(|field| field.ident.clone())
+        .collect::<Vec<_>>();
+    let field_types = named_fields
+        .iter()
+        .map(|field| field.ty.clone())
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl ::rustmemodb::PersistFlatten for #struct_name {
+            fn flatten_fields() -> Vec<(&'static str, &'static str)> {
+                vec![
+                    #( (
+                        stringify!(#field_idents),
+                        <#field_types as ::rustmemodb::PersistValue>::sql_type(),
+                    ), )*
+                ]
+            }
+
+            fn flatten_sql_literals(&self) -> Vec<String> {
+                vec![
+                    #( <#field_types as ::rustmemodb::PersistValue>::to_sql_literal(&self.#field_idents), )*
+                ]
+            }
+        }
+    })
+}
+
+fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = input.ident;
+    let vis = input.vis;
+
+    // Generated types repeat the model's type parameters; each one has to be a
+    // storable value.
+    let mut generics = input.generics.clone();
+    let mut type_params = Vec::<Ident>::new();
+    for param in &input.generics.params {
+        match param {
+            syn::GenericParam::Type(param) => type_params.push(param.ident.clone()),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "PersistModel only supports type parameters",
+                ));
+            }
+        }
+    }
+    for param in &type_params {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote!(#param: ::rustmemodb::PersistValue));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let model_options = parse_persist_model_options(&input.attrs)?;
+    let aggregates = parse_aggregate_options(&input.attrs)?;
+
+    let data_struct = match input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                struct_name.span(),
+                "PersistModel can only be derived for structs",
+            ));
+        }
+    };
+
+    let named_fields = match data_struct.fields {
+        Fields::Named(fields) => fields,
+        _ => {
+            return Err(syn::Error::new(
+                struct_name.span(),
+                "PersistModel requires named fields",
+            ));
+        }
+    };
+
+    let mut field_idents = Vec::<Ident>::new();
+    let mut field_types = Vec::<Type>::new();
+    let mut field_sql_options = Vec::<Option<SqlFieldOptions>>::new();
+    let mut unique_index_fields = Vec::<Ident>::new();
+
+    for field in named_fields.named {
+        let ident = field.ident.clone().ok_or_else(|| {
+            syn::Error::new(field.span(), "PersistModel requires named fields")
+        })?;
+        let sql_options = parse_sql_field_options(&field.attrs)?;
+        let persist_options = parse_persist_field_options(&field.attrs)?;
+        if persist_options.unique_index {
+            unique_index_fields.push(ident.clone());
+        }
+        field_idents.push(ident);
+        field_types.push(field.ty);
+        field_sql_options.push(sql_options);
+    }
+
+    if field_idents.is_empty() {
+        return Err(syn::Error::new(
+            struct_name.span(),
+            "PersistModel requires at least one field",
+        ));
+    }
+
+    let persisted_name = format_ident!("{}Persisted", struct_name);
+    let draft_name = format_ident!("{}Draft", persisted_name);
+    let patch_name = format_ident!("{}Patch", persisted_name);
+    let command_name = format_ident!("{}Command", persisted_name);
+    let command_variant_idents = field_idents
+        .iter()
+        .map(|field| format_ident!("Set{}", to_pascal_case(&field.to_string())))
+        .collect::<Vec<_>>();
+
+    let struct_ty = quote! { #struct_name #ty_generics };
+    let persisted_ty = quote! { #persisted_name #ty_generics };
+    let draft_ty = quote! { #draft_name #ty_generics };
+    let patch_ty = quote! { #patch_name #ty_generics };
+    let command_ty = quote! { #command_name #ty_generics };
+
+    // Each instantiation of a generic model gets its own type name, such as
+    // `MoneyModel<i64>`, so registries and snapshots keep them apart.
+    let type_name_expr = if type_params.is_empty() {
+        quote! { stringify!(#struct_name) }
+    } else {
+        quote! {
+            ::rustmemodb::persist::generic_type_name(
+                stringify!(#struct_name),
+                &[#( std::any::type_name::<#type_params>() ),*],
+            )
+        }
+    };
+
+    let default_table_expr = match model_options.table_name {
+        Some(table_name) => quote! { #table_name.to_string() },
+        None if !type_params.is_empty() => quote! {
+            ::rustmemodb::persist::default_table_name_generic(
+                stringify!(#struct_name),
+                &[#( std::any::type_name::<#type_params>() ),*],
+            )
+        },
+        None => quote! { ::rustmemodb::persist::default_table_name_stable(stringify!(#struct_name)) },
+    };
+    let schema_version_literal = model_options
+        .schema_version
+        .unwrap_or(1u32);
+    let strict_fields = model_options.strict_fields;
+    let auto_touch = model_options.auto_touch;
+    let migration_plan_method = match &model_options.migrations {
+        Some(path) => quote! {
+            fn migration_plan() -> ::rustmemodb::PersistMigrationPlan {
+                #path()
+            }
+        },
+        None => quote!(),
+    };
+    let flatten_flags = field_sql_options
+        .iter()
+        .map(|options| options.as_ref().is_some_and(|options| options.flatten))
+        .collect::<Vec<_>>();
+    let bool_as_int_flags = field_sql_options
+        .iter()
+        .map(|options| options.as_ref().is_some_and(|options| options.bool_as_int))
+        .collect::<Vec<_>>();
+    for (field_ty, field_sql) in field_types.iter().zip(field_sql_options.iter()) {
+        if field_sql.as_ref().is_some_and(|options| options.max_len.is_some())
+            && !is_string_field_type(field_ty)
+        {
+            return Err(syn::Error::new(
+                field_ty.span(),
+                "#[sql(max_len = ...)] requires a String or Option<String> field",
+            ));
+        }
+    }
+    for (field_ty, bool_as_int) in field_types.iter().zip(bool_as_int_flags.iter()) {
+        if *bool_as_int && !is_bool_field_type(field_ty) {
+            return Err(syn::Error::new(
+                field_ty.span(),
+                "#[sql(bool_as = \"int\")] requires a bool or Option<bool> field",
+            ));
+        }
+    }
+    let redacted_field_names = field_idents
+        .iter()
+        .zip(field_sql_options.iter())
+        .filter(|(_, options)| options.as_ref().is_some_and(|options| options.redact))
+        .map(|(field, _)| field.to_string())
+        .collect::<Vec<_>>();
+    let known_columns = field_idents
+        .iter()
+        .zip(flatten_flags.iter())
+        .map(|(field, flatten)| (field.to_string(), *flatten))
+        .collect::<Vec<_>>();
+    for (field, field_sql) in field_idents.iter().zip(field_sql_options.iter()) {
+        if let Some(check) = field_sql.as_ref().and_then(|options| options.check.as_ref()) {
+            validate_check_columns(check, &known_columns).map_err(|message| {
+                syn::Error::new(field.span(), format!("#[sql(check)] on '{}': {}", field, message))
+            })?;
+        }
+    }
+    for (index, generated) in model_options.generated.iter().enumerate() {
+        let is_ident = generated
+            .name
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_')
+            && generated
+                .name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        if !is_ident || generated.name.starts_with("__") {
+            return Err(syn::Error::new(
+                generated.span,
+                format!("generated column name '{}' is not a valid column name", generated.name),
+            ));
+        }
+        let taken = known_columns
+            .iter()
+            .any(|(column, _)| *column == generated.name)
+            || model_options.generated[..index]
+                .iter()
+                .any(|other| other.name == generated.name);
+        if taken {
+            return Err(syn::Error::new(
+                generated.span,
+                format!("generated column '{}' is already defined", generated.name),
+            ));
+        }
+        validate_check_columns(&generated.expr, &known_columns).map_err(|message| {
+            syn::Error::new(
+                generated.span,
+                format!("generated column '{}': {}", generated.name, message),
+            )
+        })?;
+    }
+    let generated_column_defs = model_options
+        .generated
+        .iter()
+        .map(|generated| format!("{} {}", generated.name, generated.sql_type))
+        .collect::<Vec<_>>();
+    // Written by a follow-up UPDATE after every save because the engine has no
+    // GENERATED ALWAYS AS columns; the expressions see the saved row.
+    let generated_assignments = model_options
+        .generated
+        .iter()
+        .map(|generated| format!("{} = ({})", generated.name, generated.expr))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // `#[sql(bool_as = "int")]` columns store 1/0 instead of TRUE/FALSE.
+    let sql_value_literal = |field: &Ident, ty: &Type, bool_as_int: bool| {
+        let literal = quote! {
+            <#ty as ::rustmemodb::PersistValue>::to_sql_literal(&self.data.#field)
+        };
+        if bool_as_int {
+            quote! { ::rustmemodb::persist::bool_as_int_sql_literal(#literal) }
+        } else {
+            literal
+        }
+    };
+    let value_field_types = field_types
+        .iter()
+        .zip(flatten_flags.iter())
+        .filter(|(_, flatten)| !**flatten)
+        .map(|(ty, _)| ty)
+        .collect::<Vec<_>>();
+    let flatten_field_types = field_types
+        .iter()
+        .zip(flatten_flags.iter())
+        .filter(|(_, flatten)| **flatten)
+        .map(|(ty, _)| ty)
+        .collect::<Vec<_>>();
+    let has_explicit_projection_attrs = field_sql_options
+        .iter()
+        .any(|options| matches!(options, Some(options) if !options.flatten));
+
+    let mut projection_contract_fields = Vec::<TokenStream2>::new();
+    let mut projection_index_helpers = Vec::<TokenStream2>::new();
+    let mut projected_columns = HashMap::<String, (String, bool)>::new();
+    for ((field_ident, field_ty), field_sql) in field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(field_sql_options.iter())
+    {
+        let include = match field_sql {
+            Some(options) if !options.flatten => options.include,
+            _ => !has_explicit_projection_attrs,
+        };
+        if !include {
+            continue;
+        }
+
+        let unique = field_sql.as_ref().is_some_and(|options| options.unique);
+        let indexed = unique || field_sql.as_ref().is_some_and(|options| options.indexed);
+        let state_field_name = field_ident.to_string();
+        let column_name = field_sql
+            .as_ref()
+            .and_then(|options| options.column_name.clone())
+            .unwrap_or_else(|| state_field_name.clone());
+        let payload_type = runtime_payload_type_tokens(field_ty);
+        projected_columns.insert(
+            state_field_name.clone(),
+            (column_name.clone(), is_numeric_field_type(field_ty)),
+        );
+        projection_contract_fields.push(quote! {
             ::rustmemodb::RuntimeProjectionField::new(
                 #state_field_name,
                 #column_name,
                 #payload_type,
-            ).indexed(#indexed)
+            ).indexed(#indexed).unique(#unique)
         });
 
+        if unique {
+            let helper_suffix = state_field_name.trim_start_matches("r#").to_string();
+            let id_helper_name = format_ident!("find_projection_id_by_{}", helper_suffix);
+            projection_index_helpers.push(quote! {
+                pub fn #id_helper_name(
+                    runtime: &::rustmemodb::PersistEntityRuntime,
+                    value: #field_ty,
+                ) -> ::rustmemodb::Result<Option<String>> {
+                    let json_value = serde_json::to_value(value)
+                        .map_err(|err| ::rustmemodb::persist::serde_to_db_error("serialize projection index value", err))?;
+                    Ok(runtime
+                        .find_projection_entity_ids_by_index(
+                            #type_name_expr,
+                            #column_name,
+                            &json_value,
+                        )?
+                        .into_iter()
+                        .next())
+                }
+            });
+        }
+
         if indexed {
             let helper_suffix = state_field_name.trim_start_matches("r#").to_string();
             let rows_helper_name = format_ident!("find_projection_rows_by_{}", helper_suffix);
             let ids_helper_name = format_ident!("find_projection_ids_by_{}", helper_suffix);
+            let range_helper_name =
+                format_ident!("find_projection_rows_by_{}_between", helper_suffix);
             projection_index_helpers.push(quote! {
                 pub fn #rows_helper_name(
                     runtime: &::rustmemodb::PersistEntityRuntime,
@@ -561,7 +1305,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     let json_value = serde_json::to_value(value)
                         .map_err(|err| ::rustmemodb::persist::serde_to_db_error("serialize projection index value", err))?;
                     runtime.find_projection_rows_by_index(
-                        stringify!(#struct_name),
+                        #type_name_expr,
                         #column_name,
                         &json_value,
                     )
@@ -574,15 +1318,96 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     let json_value = serde_json::to_value(value)
                         .map_err(|err| ::rustmemodb::persist::serde_to_db_error("serialize projection index value", err))?;
                     runtime.find_projection_entity_ids_by_index(
-                        stringify!(#struct_name),
+                        #type_name_expr,
                         #column_name,
                         &json_value,
                     )
                 }
+
+                /// Rows with the field in `lower..=upper`, ordered by the field.
+                pub fn #range_helper_name(
+                    runtime: &::rustmemodb::PersistEntityRuntime,
+                    lower: #field_ty,
+                    upper: #field_ty,
+                ) -> ::rustmemodb::Result<Vec<::rustmemodb::RuntimeProjectionRow>> {
+                    let lower = serde_json::to_value(lower)
+                        .map_err(|err| ::rustmemodb::persist::serde_to_db_error("serialize projection range bound", err))?;
+                    let upper = serde_json::to_value(upper)
+                        .map_err(|err| ::rustmemodb::persist::serde_to_db_error("serialize projection range bound", err))?;
+                    runtime.find_projection_rows_by_range(
+                        #type_name_expr,
+                        #column_name,
+                        Some(&lower),
+                        Some(&upper),
+                        true,
+                    )
+                }
             });
         }
     }
 
+    let mut aggregate_helpers = Vec::<TokenStream2>::new();
+    for aggregate in &aggregates {
+        if projected_columns.is_empty() {
+            return Err(syn::Error::new(
+                aggregate.span,
+                "#[aggregate(...)] requires at least one projected field",
+            ));
+        }
+        match &aggregate.kind {
+            AggregateKind::Count => aggregate_helpers.push(quote! {
+                pub fn projection_count(
+                    runtime: &::rustmemodb::PersistEntityRuntime,
+                ) -> ::rustmemodb::Result<usize> {
+                    runtime.count_projection_rows(#type_name_expr)
+                }
+            }),
+            AggregateKind::Exists => aggregate_helpers.push(quote! {
+                pub fn projection_exists(
+                    runtime: &::rustmemodb::PersistEntityRuntime,
+                ) -> ::rustmemodb::Result<bool> {
+                    Ok(runtime.count_projection_rows(#type_name_expr)? > 0)
+                }
+            }),
+            AggregateKind::Sum(field) | AggregateKind::Avg(field) => {
+                let (column_name, numeric) = projected_columns.get(field).ok_or_else(|| {
+                    syn::Error::new(
+                        aggregate.span,
+                        format!("#[aggregate] field '{}' is not a projected field", field),
+                    )
+                })?;
+                if !numeric {
+                    return Err(syn::Error::new(
+                        aggregate.span,
+                        format!("#[aggregate] field '{}' must be numeric", field),
+                    ));
+                }
+                aggregate_helpers.push(match &aggregate.kind {
+                    AggregateKind::Sum(_) => {
+                        let helper_name = format_ident!("projection_sum_{}", field);
+                        quote! {
+                            pub fn #helper_name(
+                                runtime: &::rustmemodb::PersistEntityRuntime,
+                            ) -> ::rustmemodb::Result<f64> {
+                                runtime.sum_projection_column(#type_name_expr, #column_name)
+                            }
+                        }
+                    }
+                    _ => {
+                        let helper_name = format_ident!("projection_avg_{}", field);
+                        quote! {
+                            pub fn #helper_name(
+                                runtime: &::rustmemodb::PersistEntityRuntime,
+                            ) -> ::rustmemodb::Result<Option<f64>> {
+                                runtime.avg_projection_column(#type_name_expr, #column_name)
+                            }
+                        }
+                    }
+                });
+            }
+        }
+    }
+
     let projection_methods = if projection_contract_fields.is_empty() {
         quote! {
             pub fn projection_contract() -> Option<::rustmemodb::RuntimeProjectionContract> {
@@ -590,9 +1415,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
 
             pub fn register_projection_in_runtime(
-                _runtime: &mut ::rustmemodb::PersistEntityRuntime,
+                runtime: &mut ::rustmemodb::PersistEntityRuntime,
             ) -> ::rustmemodb::Result<()> {
-                Ok(())
+                runtime.claim_model_table(#type_name_expr, Self::default_table_name())
             }
         }
     } else {
@@ -600,7 +1425,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             pub fn projection_contract() -> Option<::rustmemodb::RuntimeProjectionContract> {
                 let table_name = format!("{}_projection", Self::default_table_name());
                 let mut contract = ::rustmemodb::RuntimeProjectionContract::new(
-                    stringify!(#struct_name),
+                    #type_name_expr,
                     table_name,
                 )
                 .with_schema_version(#schema_version_literal);
@@ -611,6 +1436,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             pub fn register_projection_in_runtime(
                 runtime: &mut ::rustmemodb::PersistEntityRuntime,
             ) -> ::rustmemodb::Result<()> {
+                runtime.claim_model_table(#type_name_expr, Self::default_table_name())?;
                 if let Some(contract) = Self::projection_contract() {
                     runtime.register_projection_contract(contract)?;
                 }
@@ -618,6 +1444,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
 
             #( #projection_index_helpers )*
+            #( #aggregate_helpers )*
         }
     };
 
@@ -658,41 +1485,114 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
         }
     });
 
-    let sql_columns = field_idents.iter().zip(field_types.iter()).map(|(field, ty)| {
-        quote! {
-            columns.push(format!(
-                "{} {}",
-                stringify!(#field),
-                <#ty as ::rustmemodb::PersistValue>::sql_type()
-            ));
-        }
-    });
+    let sql_columns = field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(field_sql_options.iter())
+        .map(|((field, ty), field_sql)| {
+            if field_sql.as_ref().is_some_and(|options| options.flatten) {
+                return quote! {
+                    for (name, sql_type) in <#ty as ::rustmemodb::PersistFlatten>::flatten_fields() {
+                        columns.push(format!(
+                            "{} {}",
+                            ::rustmemodb::persist::flatten_column_name(stringify!(#field), name),
+                            sql_type
+                        ));
+                    }
+                };
+            }
+            let check = field_sql
+                .as_ref()
+                .and_then(|options| options.check.as_ref())
+                .map(|check| format!(" CHECK ({})", check))
+                .unwrap_or_default();
+            let max_len = field_sql.as_ref().and_then(|options| options.max_len);
+            match field_sql.as_ref().and_then(|options| options.column_type.as_ref()) {
+                Some(column_type) => quote! {
+                    columns.push(format!("{} {}{}", stringify!(#field), #column_type, #check));
+                },
+                None if field_sql.as_ref().is_some_and(|options| options.bool_as_int) => quote! {
+                    columns.push(format!("{} INTEGER{}", stringify!(#field), #check));
+                },
+                None if max_len.is_some() => quote! {
+                    columns.push(format!("{} VARCHAR({}){}", stringify!(#field), #max_len, #check));
+                },
+                None => quote! {
+                    columns.push(format!(
+                        "{} {}{}",
+                        stringify!(#field),
+                        <#ty as ::rustmemodb::PersistValue>::sql_type(),
+                        #check
+                    ));
+                },
+            }
+        });
 
-    let insert_columns = field_idents.iter().map(|field| {
-        quote! {
-            columns.push(stringify!(#field).to_string());
-        }
-    });
+    let insert_columns = field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(flatten_flags.iter())
+        .map(|((field, ty), flatten)| {
+            if *flatten {
+                return quote! {
+                    for (name, _) in <#ty as ::rustmemodb::PersistFlatten>::flatten_fields() {
+                        columns.push(::rustmemodb::persist::flatten_column_name(stringify!(#field), name));
+                    }
+                };
+            }
+            quote! {
+                columns.push(stringify!(#field).to_string());
+            }
+        });
 
-    let insert_values = field_idents.iter().zip(field_types.iter()).map(|(field, ty)| {
-        quote! {
-            values.push(
-                <#ty as ::rustmemodb::PersistValue>::to_sql_literal(&self.data.#field)
-            );
-        }
-    });
+    let insert_values = field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(flatten_flags.iter().zip(bool_as_int_flags.iter()))
+        .map(|((field, ty), (flatten, bool_as_int))| {
+            if *flatten {
+                return quote! {
+                    values.extend(
+                        <#ty as ::rustmemodb::PersistFlatten>::flatten_sql_literals(&self.data.#field)
+                    );
+                };
+            }
+            let literal = sql_value_literal(field, ty, *bool_as_int);
+            quote! {
+                values.push(#literal);
+            }
+        });
 
-    let update_assignments = field_idents.iter().zip(field_types.iter()).map(|(field, ty)| {
-        quote! {
-            if self.__dirty_fields.contains(stringify!(#field)) {
-                set_clauses.push(format!(
-                    "{} = {}",
-                    stringify!(#field),
-                    <#ty as ::rustmemodb::PersistValue>::to_sql_literal(&self.data.#field)
-                ));
+    let update_assignments = field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(flatten_flags.iter().zip(bool_as_int_flags.iter()))
+        .map(|((field, ty), (flatten, bool_as_int))| {
+            if *flatten {
+                return quote! {
+                    if self.__dirty_fields.contains(stringify!(#field)) {
+                        let literals =
+                            <#ty as ::rustmemodb::PersistFlatten>::flatten_sql_literals(&self.data.#field);
+                        for ((name, _), literal) in <#ty as ::rustmemodb::PersistFlatten>::flatten_fields()
+                            .into_iter()
+                            .zip(literals)
+                        {
+                            set_clauses.push(format!(
+                                "{} = {}",
+                                ::rustmemodb::persist::flatten_column_name(stringify!(#field), name),
+                                literal
+                            ));
+                        }
+                    }
+                };
             }
-        }
-    });
+            let literal = sql_value_literal(field, ty, *bool_as_int);
+            quote! {
+                if self.__dirty_fields.contains(stringify!(#field)) {
+                    set_clauses.push(format!("{} = {}", stringify!(#field), #literal));
+                }
+            }
+        });
 
     let state_json_fields = field_idents.iter().map(|field| {
         quote! {
@@ -700,24 +1600,100 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
         }
     });
 
-    let from_state_fields = field_idents.iter().zip(field_types.iter()).map(|(field, ty)| {
-        quote! {
-            let #field: #ty = serde_json::from_value(
-                fields
+    let from_state_fields = field_idents
+        .iter()
+        .zip(field_types.iter())
+        .zip(flatten_flags.iter().zip(bool_as_int_flags.iter()))
+        .map(|((field, ty), (flatten, bool_as_int))| {
+            // Flattened fields loaded straight from the table arrive as prefixed columns.
+            let unflatten = if *flatten {
+                quote! {
+                    .or_else(|| {
+                        ::rustmemodb::persist::unflatten_state_field::<#ty>(stringify!(#field), fields)
+                    })
+                }
+            } else {
+                quote!()
+            };
+            let coerce = if *flatten {
+                quote!()
+            } else if *bool_as_int {
+                quote! { .map(::rustmemodb::persist::coerce_bool_state_value) }
+            } else {
+                quote! { .map(<#ty as ::rustmemodb::PersistValue>::coerce_state_value) }
+            };
+            // Lenient models read a missing key as null, so fields added as `Option<_>` load as `None`.
+            let missing = if strict_fields {
+                quote! {
+                    return Err(::rustmemodb::DbError::ExecutionError(
+                        format!("Field '{}' missing in persisted state", stringify!(#field))
+                    ))
+                }
+            } else {
+                quote! {
+                    serde_json::from_value(serde_json::Value::Null).map_err(|_| {
+                        ::rustmemodb::DbError::ExecutionError(format!(
+                            "Field '{}' missing in persisted state",
+                            stringify!(#field)
+                        ))
+                    })?
+                }
+            };
+            quote! {
+                let #field: #ty = match fields
                     .get(stringify!(#field))
                     .cloned()
-                    .ok_or_else(|| ::rustmemodb::DbError::ExecutionError(
-                        format!("Field '{}' missing in persisted state", stringify!(#field))
-                    ))?
+                    #unflatten
+                    #coerce
+                {
+                    Some(value) => serde_json::from_value(value).map_err(|err| {
+                        ::rustmemodb::persist::serde_to_db_error(
+                            &format!("deserialize field '{}'", stringify!(#field)),
+                            err,
+                        )
+                    })?,
+                    None => #missing,
+                };
+            }
+        });
+    let unknown_fields_check = if strict_fields {
+        let value_field_names = field_idents
+            .iter()
+            .zip(flatten_flags.iter())
+            .filter(|(_, flatten)| !**flatten)
+            .map(|(field, _)| field.to_string())
+            // Generated columns are read back with the row but never set by the model.
+            .chain(
+                model_options
+                    .generated
+                    .iter()
+                    .map(|generated| generated.name.clone()),
             )
-            .map_err(|err| {
-                ::rustmemodb::persist::serde_to_db_error(
-                    &format!("deserialize field '{}'", stringify!(#field)),
-                    err,
-                )
-            })?;
+            .collect::<Vec<_>>();
+        let flatten_prefixes = field_idents
+            .iter()
+            .zip(flatten_flags.iter())
+            .filter(|(_, flatten)| **flatten)
+            .map(|(field, _)| field.to_string())
+            .collect::<Vec<_>>();
+        quote! {
+            for key in fields.keys() {
+                let known = [#(#value_field_names),*].contains(&key.as_str())
+                    || [#(#flatten_prefixes),*].iter().any(|prefix: &&str| {
+                        key == prefix || key.starts_with(&format!("{}_", prefix))
+                    });
+                if !known {
+                    return Err(::rustmemodb::DbError::ExecutionError(format!(
+                        "Unknown field '{}' in persisted state of {}",
+                        key,
+                        #type_name_expr,
+                    )));
+                }
+            }
         }
-    });
+    } else {
+        quote!()
+    };
 
     let from_parts_args = field_idents
         .iter()
@@ -759,11 +1735,11 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
         });
 
     Ok(quote! {
-        #vis struct #draft_name {
+        #vis struct #draft_name #generics #where_clause {
             #( pub #field_idents: #field_types, )*
         }
 
-        impl #draft_name {
+        impl #impl_generics #draft_ty #where_clause {
             pub fn new(#(#from_parts_args),*) -> Self {
                 Self {
                     #(#from_parts_struct_fields,)*
@@ -771,11 +1747,19 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
-        #vis struct #patch_name {
+        impl #impl_generics From<#struct_ty> for #draft_ty #where_clause {
+            fn from(value: #struct_ty) -> Self {
+                Self {
+                    #( #field_idents: value.#field_idents, )*
+                }
+            }
+        }
+
+        #vis struct #patch_name #generics #where_clause {
             #( pub #field_idents: Option<#field_types>, )*
         }
 
-        impl Default for #patch_name {
+        impl #impl_generics Default for #patch_ty #where_clause {
             fn default() -> Self {
                 Self {
                     #(#field_idents: None,)*
@@ -783,7 +1767,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
-        impl #patch_name {
+        impl #impl_generics #patch_ty #where_clause {
             pub fn is_empty(&self) -> bool {
                 true #(&& self.#field_idents.is_none())*
             }
@@ -798,12 +1782,12 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
-        #vis enum #command_name {
+        #vis enum #command_name #generics #where_clause {
             #( #command_variant_idents(#field_types), )*
             Touch,
         }
 
-        impl #command_name {
+        impl #impl_generics #command_ty #where_clause {
             pub fn name(&self) -> &'static str {
                 match self {
                     #( Self::#command_variant_idents(_) => stringify!(#command_variant_idents), )*
@@ -812,38 +1796,38 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
-        impl #struct_name {
-            pub fn into_persisted(self) -> #persisted_name {
+        impl #impl_generics #struct_ty #where_clause {
+            pub fn into_persisted(self) -> #persisted_ty {
                 #persisted_name::new(self)
             }
 
-            pub fn into_persisted_with_table(self, table_name: impl Into<String>) -> #persisted_name {
+            pub fn into_persisted_with_table(self, table_name: impl Into<String>) -> #persisted_ty {
                 #persisted_name::with_table_name(table_name, self)
             }
         }
 
-        impl ::rustmemodb::persist::PersistModelExt for #struct_name {
-            type Persisted = #persisted_name;
+        impl #impl_generics ::rustmemodb::persist::PersistModelExt for #struct_ty #where_clause {
+            type Persisted = #persisted_ty;
 
             fn into_persisted(self) -> Self::Persisted {
                 #persisted_name::new(self)
             }
         }
 
-        impl From<#struct_name> for #persisted_name {
-            fn from(value: #struct_name) -> Self {
+        impl #impl_generics From<#struct_ty> for #persisted_ty #where_clause {
+            fn from(value: #struct_ty) -> Self {
                 Self::new(value)
             }
         }
 
-        impl From<#persisted_name> for #struct_name {
-            fn from(value: #persisted_name) -> Self {
+        impl #impl_generics From<#persisted_ty> for #struct_ty #where_clause {
+            fn from(value: #persisted_ty) -> Self {
                 value.into_inner()
             }
         }
 
-        #vis struct #persisted_name {
-            data: #struct_name,
+        #vis struct #persisted_name #generics #where_clause {
+            data: #struct_ty,
             __persist_id: String,
             __table_name: String,
             __metadata: ::rustmemodb::PersistMetadata,
@@ -864,10 +1848,11 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             >,
         }
 
-        impl #persisted_name {
+        impl #impl_generics #persisted_ty #where_clause {
             fn __type_checks()
             where
-                #( #field_types: ::rustmemodb::PersistValue, )*
+                #( #value_field_types: ::rustmemodb::PersistValue, )*
+                #( #flatten_field_types: ::rustmemodb::PersistFlatten, )*
             {}
 
             pub fn default_table_name() -> String {
@@ -884,16 +1869,20 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     "__created_at TIMESTAMP NOT NULL".to_string(),
                     "__updated_at TIMESTAMP NOT NULL".to_string(),
                     "__last_touch_at TIMESTAMP NOT NULL".to_string(),
+                    "__labels JSON".to_string(),
+                    "__created_by TEXT".to_string(),
+                    "__updated_by TEXT".to_string(),
                 ];
 
                 #( #sql_columns )*
+                #( columns.push(#generated_column_defs.to_string()); )*
 
                 format!("CREATE TABLE IF NOT EXISTS {} ({})", table_name, columns.join(", "))
             }
 
             #projection_methods
 
-            pub fn new(data: #struct_name) -> Self {
+            pub fn new(data: #struct_ty) -> Self {
                 Self::__type_checks();
                 let now = chrono::Utc::now();
                 Self {
@@ -909,7 +1898,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 }
             }
 
-            pub fn with_table_name(table_name: impl Into<String>, data: #struct_name) -> Self {
+            pub fn with_table_name(table_name: impl Into<String>, data: #struct_ty) -> Self {
                 let mut this = Self::new(data);
                 this.__table_name = table_name.into();
                 this
@@ -921,24 +1910,26 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 })
             }
 
-            pub fn data(&self) -> &#struct_name {
+            pub fn data(&self) -> &#struct_ty {
                 &self.data
             }
 
-            pub fn data_mut(&mut self) -> &mut #struct_name {
+            pub fn data_mut(&mut self) -> &mut #struct_ty {
                 &mut self.data
             }
 
             pub fn mark_all_dirty(&mut self) {
                 #( #dirty_all_fields )*
-                self.touch();
+                if Self::__AUTO_TOUCH {
+                    self.touch();
+                }
             }
 
             pub fn clear_dirty(&mut self) {
                 self.__dirty_fields.clear();
             }
 
-            pub fn into_inner(self) -> #struct_name {
+            pub fn into_inner(self) -> #struct_ty {
                 self.data
             }
 
@@ -967,6 +1958,10 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 self.__bound_session.is_some()
             }
 
+            pub fn bound_session(&self) -> Option<&::rustmemodb::PersistSession> {
+                self.__bound_session.as_ref()
+            }
+
             pub fn auto_persist_enabled(&self) -> bool {
                 self.__auto_persist
             }
@@ -991,6 +1986,36 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 <Self as ::rustmemodb::PersistEntity>::save(self, &session).await
             }
 
+            /// Replaces data and metadata with the stored row when its version
+            /// differs from ours. Returns whether anything was reloaded.
+            pub async fn reload_bound_if_stale(&mut self) -> ::rustmemodb::Result<bool> {
+                let session = self.__bound_session.clone().ok_or_else(|| {
+                    ::rustmemodb::DbError::ExecutionError(
+                        "No bound PersistSession for reload_bound_if_stale".to_string(),
+                    )
+                })?;
+                let Some(state) = session
+                    .load_persist_state(
+                        &self.__table_name,
+                        &self.__persist_id,
+                        #type_name_expr,
+                    )
+                    .await?
+                else {
+                    return Ok(false);
+                };
+                if state.metadata.version == self.__metadata.version {
+                    return Ok(false);
+                }
+
+                let fresh = <Self as ::rustmemodb::PersistEntityFactory>::from_state(&state)?;
+                self.data = fresh.data;
+                self.__metadata = fresh.__metadata;
+                self.__metadata.persisted = true;
+                self.__dirty_fields.clear();
+                Ok(true)
+            }
+
             pub async fn delete_bound(&mut self) -> ::rustmemodb::Result<()> {
                 let session = self.__bound_session.clone().ok_or_else(|| {
                     ::rustmemodb::DbError::ExecutionError(
@@ -1021,18 +2046,18 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 self.__auto_persist_if_enabled().await
             }
 
-            pub fn from_draft(draft: #draft_name) -> Self {
+            pub fn from_draft(draft: #draft_ty) -> Self {
                 Self::from_parts(#(draft.#field_idents),*)
             }
 
-            pub fn patch(&mut self, patch: #patch_name) -> ::rustmemodb::Result<bool> {
+            pub fn patch(&mut self, patch: #patch_ty) -> ::rustmemodb::Result<bool> {
                 patch.validate()?;
                 let mut changed = false;
                 #( #patch_apply_steps )*
                 Ok(changed)
             }
 
-            pub fn apply(&mut self, command: #command_name) -> ::rustmemodb::Result<bool> {
+            pub fn apply(&mut self, command: #command_ty) -> ::rustmemodb::Result<bool> {
                 match command {
                     #( #command_apply_arms, )*
                     #command_name::Touch => {
@@ -1042,7 +2067,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 }
             }
 
-            pub async fn patch_persisted(&mut self, patch: #patch_name) -> ::rustmemodb::Result<bool> {
+            pub async fn patch_persisted(&mut self, patch: #patch_ty) -> ::rustmemodb::Result<bool> {
                 let changed = self.patch(patch)?;
                 self.__auto_persist_if_enabled().await?;
                 Ok(changed)
@@ -1050,21 +2075,48 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
 
             pub async fn apply_persisted(
                 &mut self,
-                command: #command_name,
+                command: #command_ty,
             ) -> ::rustmemodb::Result<bool> {
                 let changed = self.apply(command)?;
                 self.__auto_persist_if_enabled().await?;
                 Ok(changed)
             }
 
+            const __AUTO_TOUCH: bool = #auto_touch;
+            const __GENERATED_ASSIGNMENTS: &'static str = #generated_assignments;
+
+            /// Recomputes the `generated(...)` columns of the row at `version`.
+            fn __generated_refresh_sql(&self, version: i64) -> Option<String> {
+                if Self::__GENERATED_ASSIGNMENTS.is_empty() {
+                    return None;
+                }
+                Some(format!(
+                    "UPDATE {} SET {} WHERE __persist_id = '{}' AND __version = {}",
+                    self.__table_name,
+                    Self::__GENERATED_ASSIGNMENTS,
+                    ::rustmemodb::persist::sql_escape_string(&self.__persist_id),
+                    version,
+                ))
+            }
+
             pub fn touch(&mut self) {
                 self.__metadata.touch_count = self.__metadata.touch_count.saturating_add(1);
                 self.__metadata.last_touch_at = chrono::Utc::now();
+                if !Self::__AUTO_TOUCH {
+                    // Without auto-touch only an explicit touch is written back.
+                    self.__dirty_fields.insert("__touch");
+                }
             }
 
             fn __mark_dirty(&mut self, field: &'static str) {
                 self.__dirty_fields.insert(field);
-                self.touch();
+                if Self::__AUTO_TOUCH {
+                    self.touch();
+                }
+            }
+
+            fn __touch_pending(&self) -> bool {
+                Self::__AUTO_TOUCH || self.__dirty_fields.contains("__touch")
             }
 
             pub fn register_function<F>(&mut self, name: impl Into<String>, handler: F)
@@ -1107,6 +2159,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     "__created_at".to_string(),
                     "__updated_at".to_string(),
                     "__last_touch_at".to_string(),
+                    "__labels".to_string(),
+                    "__created_by".to_string(),
+                    "__updated_by".to_string(),
                 ];
                 let mut values = vec![
                     format!(
@@ -1119,7 +2174,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     format!("'{}'", self.__metadata.created_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.updated_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.last_touch_at.to_rfc3339()),
+                    ::rustmemodb::persist::persist_labels_sql_literal(&self.__metadata.labels),
                 ];
+                values.extend(self.__metadata.actor_sql_literals());
 
                 #( #insert_columns )*
                 #( #insert_values )*
@@ -1150,11 +2207,19 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     "__updated_at = '{}'",
                     self.__metadata.updated_at.to_rfc3339()
                 ));
+                if self.__touch_pending() {
+                    set_clauses.push(format!(
+                        "__last_touch_at = '{}'",
+                        self.__metadata.last_touch_at.to_rfc3339()
+                    ));
+                    set_clauses.push(format!("__touch_count = {}", self.__metadata.touch_count));
+                }
                 set_clauses.push(format!(
-                    "__last_touch_at = '{}'",
-                    self.__metadata.last_touch_at.to_rfc3339()
+                    "__labels = {}",
+                    ::rustmemodb::persist::persist_labels_sql_literal(&self.__metadata.labels)
                 ));
-                set_clauses.push(format!("__touch_count = {}", self.__metadata.touch_count));
+                let [_, updated_by] = self.__metadata.actor_sql_literals();
+                set_clauses.push(format!("__updated_by = {}", updated_by));
 
                 Some(format!(
                     "UPDATE {} SET {} WHERE __persist_id = '{}' AND __version = {}",
@@ -1181,9 +2246,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
         }
 
         #[async_trait::async_trait]
-        impl ::rustmemodb::PersistEntity for #persisted_name {
+        impl #impl_generics ::rustmemodb::PersistEntity for #persisted_ty #where_clause {
             fn type_name(&self) -> &'static str {
-                stringify!(#struct_name)
+                #type_name_expr
             }
 
             fn table_name(&self) -> &str {
@@ -1194,6 +2259,15 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 &self.__persist_id
             }
 
+            fn set_persist_id(&mut self, persist_id: String) -> ::rustmemodb::Result<()> {
+                self.__persist_id = persist_id;
+                Ok(())
+            }
+
+            fn unique_index_fields(&self) -> Vec<&'static str> {
+                vec![#( stringify!(#unique_index_fields), )*]
+            }
+
             fn metadata(&self) -> &::rustmemodb::PersistMetadata {
                 &self.__metadata
             }
@@ -1202,9 +2276,18 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 &mut self.__metadata
             }
 
+            fn set_label(&mut self, key: &str, value: &str) {
+                if self.__metadata.labels.get(key).map(String::as_str) != Some(value) {
+                    self.__metadata
+                        .labels
+                        .insert(key.to_string(), value.to_string());
+                    self.__mark_dirty("__labels");
+                }
+            }
+
             fn descriptor(&self) -> ::rustmemodb::ObjectDescriptor {
                 ::rustmemodb::ObjectDescriptor {
-                    type_name: stringify!(#struct_name).to_string(),
+                    type_name: #type_name_expr.to_string(),
                     table_name: self.__table_name.clone(),
                     functions: self.available_functions(),
                 }
@@ -1213,7 +2296,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             fn state(&self) -> ::rustmemodb::PersistState {
                 ::rustmemodb::PersistState {
                     persist_id: self.__persist_id.clone(),
-                    type_name: stringify!(#struct_name).to_string(),
+                    type_name: #type_name_expr.to_string(),
                     table_name: self.__table_name.clone(),
                     metadata: self.__metadata.clone(),
                     fields: self.state_json(),
@@ -1298,7 +2381,10 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 if self.__table_ready {
                     return Ok(());
                 }
-                session.execute(&self.__create_table_sql()).await?;
+                session
+                    .ensure_entity_table(&self.__table_name, &self.__create_table_sql())
+                    .await?;
+                session.ensure_persist_metadata_columns(&self.__table_name).await?;
                 let migration_plan = <Self as ::rustmemodb::PersistEntityFactory>::migration_plan();
                 migration_plan
                     .ensure_table_schema_version(session, &self.__table_name)
@@ -1307,6 +2393,14 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 Ok(())
             }
 
+            fn has_unsaved_changes(&self) -> bool {
+                !self.__metadata.persisted || !self.__dirty_fields.is_empty()
+            }
+
+            fn mark_all_dirty(&mut self) {
+                #( self.__dirty_fields.insert(stringify!(#field_idents)); )*
+            }
+
             async fn save(
                 &mut self,
                 session: &::rustmemodb::PersistSession,
@@ -1316,6 +2410,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     .__metadata
                     .schema_version
                     .max(<Self as ::rustmemodb::PersistEntityFactory>::schema_version());
+                self.__metadata.record_actor(session);
                 let now = chrono::Utc::now();
 
                 if !self.__metadata.persisted {
@@ -1328,8 +2423,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     self.__metadata.updated_at = now;
                     self.__metadata.last_touch_at = now;
 
-                    let sql = self.__insert_sql();
-                    session.execute(&sql).await?;
+                    let mut statements = vec![self.__insert_sql()];
+                    statements.extend(self.__generated_refresh_sql(self.__metadata.version));
+                    session.execute_in_transaction(&statements).await?;
                     self.__metadata.persisted = true;
                     self.__dirty_fields.clear();
                     return Ok(());
@@ -1339,11 +2435,13 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     return Ok(());
                 }
 
-                if self.__metadata.touch_count == 0 {
-                    self.__metadata.touch_count = 1;
+                if self.__touch_pending() {
+                    if self.__metadata.touch_count == 0 {
+                        self.__metadata.touch_count = 1;
+                    }
+                    self.__metadata.last_touch_at = now;
                 }
                 self.__metadata.updated_at = now;
-                self.__metadata.last_touch_at = now;
 
                 let expected_version = self.__metadata.version.max(1);
                 let new_version = expected_version + 1;
@@ -1353,8 +2451,10 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                         "No changed fields to update".to_string(),
                     ))?;
 
-                let result = session.execute(&sql).await?;
-                if matches!(result.affected_rows(), Some(0)) {
+                let mut statements = vec![sql];
+                statements.extend(self.__generated_refresh_sql(new_version));
+                let results = session.execute_in_transaction(&statements).await?;
+                if matches!(results[0].affected_rows(), Some(0)) {
                     return Err(::rustmemodb::DbError::ExecutionError(format!(
                         "Optimistic lock conflict for {}:{}",
                         self.__table_name,
@@ -1450,9 +2550,9 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
         }
 
         #[async_trait::async_trait]
-        impl ::rustmemodb::PersistEntityFactory for #persisted_name {
+        impl #impl_generics ::rustmemodb::PersistEntityFactory for #persisted_ty #where_clause {
             fn entity_type_name() -> &'static str {
-                stringify!(#struct_name)
+                #type_name_expr
             }
 
             fn default_table_name() -> String {
@@ -1467,7 +2567,15 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                 #schema_version_literal
             }
 
+            fn redacted_fields() -> &'static [&'static str] {
+                &[#(#redacted_field_names),*]
+            }
+
+            #migration_plan_method
+
             fn from_state(state: &::rustmemodb::PersistState) -> ::rustmemodb::Result<Self> {
+                let state = <Self as ::rustmemodb::PersistEntityFactory>::upcast_state(state)?;
+                let state = state.as_ref();
                 let fields = state
                     .fields
                     .as_object()
@@ -1476,6 +2584,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     ))?;
 
                 Self::__type_checks();
+                #unknown_fields_check
 
                 #( #from_state_fields )*
 
@@ -1500,10 +2609,10 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
-        impl ::rustmemodb::persist::PersistCommandModel for #persisted_name {
-            type Draft = #draft_name;
-            type Patch = #patch_name;
-            type Command = #command_name;
+        impl #impl_generics ::rustmemodb::persist::PersistCommandModel for #persisted_ty #where_clause {
+            type Draft = #draft_ty;
+            type Patch = #patch_ty;
+            type Command = #command_ty;
 
             fn from_draft(draft: Self::Draft) -> Self {
                 Self::from_parts(#(draft.#field_idents),*)
@@ -1549,6 +2658,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                                 },
                             ],
                             mutates_state: true,
+                            deprecated: None,
                         },
                     )*
                 ];
@@ -1557,6 +2667,7 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
                     name: "Touch".to_string(),
                     fields: Vec::new(),
                     mutates_state: true,
+                    deprecated: None,
                 });
 
                 contracts
@@ -1565,16 +2676,46 @@ fn expand_persist_model(input: DeriveInput) -> syn::Result<TokenStream2> {
     })
 }
 
+enum AggregateKind {
+    Count,
+    Exists,
+    Sum(String),
+    Avg(String),
+}
+
+struct AggregateOption {
+    kind: AggregateKind,
+    span: proc_macro2::Span,
+}
+
 struct PersistModelOptions {
     table_name: Option<String>,
     schema_version: Option<u32>,
+    migrations: Option<syn::Path>,
+    strict_fields: bool,
+    auto_touch: bool,
+    generated: Vec<GeneratedColumnOption>,
+}
+
+struct GeneratedColumnOption {
+    name: String,
+    expr: String,
+    sql_type: String,
+    span: proc_macro2::Span,
 }
 
 #[derive(Clone)]
 struct SqlFieldOptions {
     include: bool,
     indexed: bool,
+    unique: bool,
     column_name: Option<String>,
+    column_type: Option<String>,
+    flatten: bool,
+    bool_as_int: bool,
+    check: Option<String>,
+    redact: bool,
+    max_len: Option<usize>,
 }
 
 impl Default for SqlFieldOptions {
@@ -1582,19 +2723,36 @@ impl Default for SqlFieldOptions {
         Self {
             include: true,
             indexed: false,
+            unique: false,
             column_name: None,
+            column_type: None,
+            flatten: false,
+            bool_as_int: false,
+            check: None,
+            redact: false,
+            max_len: None,
         }
     }
 }
 
+#[derive(Clone, Default)]
+struct PersistFieldOptions {
+    unique_index: bool,
+}
+
 struct PersistentAttrOptions {
     table_name: Option<LitStr>,
     schema_version: Option<u32>,
+    migrations: Option<syn::Path>,
 }
 
 #[derive(Clone)]
 struct CommandAttrOptions {
     name: Option<String>,
+    aliases: Vec<String>,
+    min_interval_ms: Option<u64>,
+    deprecated: Option<String>,
+    serialize_per_entity: bool,
 }
 
 struct PersistentCommandArg {
@@ -1609,14 +2767,24 @@ enum PersistentMethodReturnKind {
 }
 
 impl PersistentMethodReturnKind {
-    fn from_signature(signature: &syn::Signature) -> Self {
+    fn from_signature(signature: &syn::Signature) -> syn::Result<Self> {
         match &signature.output {
-            ReturnType::Default => Self::Unit,
+            ReturnType::Default => Ok(Self::Unit),
             ReturnType::Type(_, ty) => {
+                let output_ty = extract_result_ok_type(ty).unwrap_or_else(|| (**ty).clone());
+                if type_borrows(&output_ty) {
+                    return Err(syn::Error::new(
+                        output_ty.span(),
+                        format!(
+                            "#[command] method `{}` returns borrowed data; command output is serialized after the entity is marked dirty, so return an owned value instead (e.g. `.clone()` or `.to_owned()` the field)",
+                            signature.ident
+                        ),
+                    ));
+                }
                 if let Some(ok_ty) = extract_result_ok_type(ty) {
-                    return Self::RustResult(ok_ty);
+                    return Ok(Self::RustResult(ok_ty));
                 }
-                Self::Plain((**ty).clone())
+                Ok(Self::Plain((**ty).clone()))
             }
         }
     }
@@ -1650,6 +2818,11 @@ struct PersistentCommandMethod {
     method_ident: Ident,
     variant_ident: Ident,
     command_name: String,
+    aliases: Vec<String>,
+    description: String,
+    min_interval_ms: Option<u64>,
+    deprecated: Option<String>,
+    serialize_per_entity: bool,
     args: Vec<PersistentCommandArg>,
     return_kind: PersistentMethodReturnKind,
 }
@@ -1712,15 +2885,54 @@ impl PersistentCommandMethod {
         let method_name = method.sig.ident.to_string();
         let command_name = marker.name.unwrap_or_else(|| method_name.clone());
         let variant_ident = format_ident!("{}", to_pascal_case(&method_name));
+        let description = first_doc_line(&method.attrs).unwrap_or_else(|| command_name.clone());
 
         Ok(Self {
             method_ident: method.sig.ident.clone(),
             variant_ident,
             command_name,
+            aliases: marker.aliases,
+            description,
+            min_interval_ms: marker.min_interval_ms,
+            deprecated: marker.deprecated,
+            serialize_per_entity: marker.serialize_per_entity,
             args,
-            return_kind: PersistentMethodReturnKind::from_signature(&method.sig),
+            return_kind: PersistentMethodReturnKind::from_signature(&method.sig)?,
         })
     }
+
+    /// Primary command name followed by its aliases.
+    fn all_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.command_name.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+
+    /// Aliases are always reported as deprecated in favor of the primary name.
+    fn deprecation_for(&self, name: &str) -> Option<String> {
+        if name == self.command_name {
+            self.deprecated.clone()
+        } else {
+            Some(format!("alias of '{}'", self.command_name))
+        }
+    }
+}
+
+fn first_doc_line(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            return None;
+        };
+        if !meta.path.is_ident("doc") {
+            return None;
+        }
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit), ..
+        }) = &meta.value
+        else {
+            return None;
+        };
+        let line = lit.value().trim().to_string();
+        (!line.is_empty()).then_some(line)
+    })
 }
 
 fn to_pascal_case(value: &str) -> String {
@@ -1763,6 +2975,7 @@ fn parse_persistent_attr_options(attr: TokenStream2) -> syn::Result<PersistentAt
     let mut options = PersistentAttrOptions {
         table_name: None,
         schema_version: None,
+        migrations: None,
     };
 
     let parser = syn::meta::parser(|meta| {
@@ -1772,16 +2985,98 @@ fn parse_persistent_attr_options(attr: TokenStream2) -> syn::Result<PersistentAt
             options.table_name = Some(lit);
             return Ok(());
         }
-
-        if meta.path.is_ident("schema_version") {
+
+        if meta.path.is_ident("schema_version") {
+            let value = meta.value()?;
+            let lit: syn::LitInt = value.parse()?;
+            options.schema_version = Some(lit.base10_parse::<u32>()?);
+            return Ok(());
+        }
+
+        if meta.path.is_ident("migrations") {
+            options.migrations = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+
+        Err(meta.error(
+            "Unsupported #[persistent(...)] option. Supported: table = \"...\", schema_version = <u32>, migrations = <fn path>",
+        ))
+    });
+
+    parser.parse2(attr)?;
+    Ok(options)
+}
+
+fn parse_command_attr_tokens(attr: TokenStream2) -> syn::Result<CommandAttrOptions> {
+    let mut options = CommandAttrOptions {
+        name: None,
+        aliases: Vec::new(),
+        min_interval_ms: None,
+        deprecated: None,
+        serialize_per_entity: false,
+    };
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            let value = meta.value()?;
+            if !value.peek(syn::token::Bracket) {
+                let lit: LitStr = value.parse()?;
+                options.name = Some(command_name_value(&lit)?);
+                return Ok(());
+            }
+            let content;
+            let bracket = syn::bracketed!(content in value);
+            let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+            let mut names = names
+                .iter()
+                .map(command_name_value)
+                .collect::<syn::Result<Vec<_>>>()?;
+            if names.is_empty() {
+                return Err(syn::Error::new(
+                    bracket.span.join(),
+                    "#[command(name = [...])] requires at least one name",
+                ));
+            }
+            for (index, name) in names.iter().enumerate() {
+                if names[..index].contains(name) {
+                    return Err(syn::Error::new(
+                        bracket.span.join(),
+                        format!("Duplicate #[command] name '{}'", name),
+                    ));
+                }
+            }
+            options.aliases = names.split_off(1);
+            options.name = names.pop();
+            return Ok(());
+        }
+        if meta.path.is_ident("min_interval_ms") {
             let value = meta.value()?;
             let lit: syn::LitInt = value.parse()?;
-            options.schema_version = Some(lit.base10_parse::<u32>()?);
+            let interval = lit.base10_parse::<u64>()?;
+            if interval == 0 {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "#[command(min_interval_ms = ...)] must be greater than zero",
+                ));
+            }
+            options.min_interval_ms = Some(interval);
+            return Ok(());
+        }
+        if meta.path.is_ident("deprecated") {
+            let note = if meta.input.peek(Token![=]) {
+                let lit: LitStr = meta.value()?.parse()?;
+                lit.value()
+            } else {
+                String::new()
+            };
+            options.deprecated = Some(note);
+            return Ok(());
+        }
+        if meta.path.is_ident("serialize_per_entity") {
+            options.serialize_per_entity = true;
             return Ok(());
         }
-
         Err(meta.error(
-            "Unsupported #[persistent(...)] option. Supported: table = \"...\", schema_version = <u32>",
+            "Unsupported #[command(...)] option. Supported: name = \"...\" | [\"...\", ...], min_interval_ms = <u64>, deprecated [= \"...\"], serialize_per_entity",
         ))
     });
 
@@ -1789,36 +3084,158 @@ fn parse_persistent_attr_options(attr: TokenStream2) -> syn::Result<PersistentAt
     Ok(options)
 }
 
-fn parse_command_attr_tokens(attr: TokenStream2) -> syn::Result<CommandAttrOptions> {
-    let mut options = CommandAttrOptions { name: None };
-    let parser = syn::meta::parser(|meta| {
-        if meta.path.is_ident("name") {
-            let value = meta.value()?;
-            let lit: LitStr = value.parse()?;
-            options.name = Some(lit.value());
-            return Ok(());
-        }
-        Err(meta.error("Unsupported #[command(...)] option. Supported: name = \"...\""))
-    });
+/// Command names are written unescaped into the doc marker, so the marker's
+/// separators cannot appear in them.
+fn command_name_value(lit: &LitStr) -> syn::Result<String> {
+    let name = lit.value();
+    if name.contains([',', ';']) {
+        return Err(syn::Error::new(
+            lit.span(),
+            format!("#[command] name '{}' must not contain ',' or ';'", name),
+        ));
+    }
+    Ok(name)
+}
 
-    parser.parse2(attr)?;
-    Ok(options)
+fn command_doc_marker(options: &CommandAttrOptions) -> String {
+    let mut marker = "__rustmemodb_command".to_string();
+    if options.name.is_some()
+        || options.min_interval_ms.is_some()
+        || options.deprecated.is_some()
+        || options.serialize_per_entity
+    {
+        marker.push(':');
+        marker.push_str(options.name.as_deref().unwrap_or_default());
+    }
+    if !options.aliases.is_empty() {
+        marker.push_str(&format!(";aliases={}", options.aliases.join(",")));
+    }
+    if options.serialize_per_entity {
+        marker.push_str(";serialize_per_entity");
+    }
+    if let Some(interval) = options.min_interval_ms {
+        marker.push_str(&format!(";min_interval_ms={interval}"));
+    }
+    // Kept last: the note is free text and may itself contain ';'.
+    if let Some(note) = &options.deprecated {
+        marker.push_str(&format!(";deprecated={note}"));
+    }
+    marker
 }
 
 fn parse_command_doc_marker(value: &str) -> Option<CommandAttrOptions> {
     const MARKER: &str = "__rustmemodb_command";
     if value == MARKER {
-        return Some(CommandAttrOptions { name: None });
+        return Some(CommandAttrOptions {
+            name: None,
+            aliases: Vec::new(),
+            min_interval_ms: None,
+            deprecated: None,
+            serialize_per_entity: false,
+        });
     }
-    value
-        .strip_prefix("__rustmemodb_command:")
-        .map(|name| CommandAttrOptions {
-            name: if name.trim().is_empty() {
-                None
-            } else {
-                Some(name.to_string())
-            },
-        })
+    let rest = value.strip_prefix("__rustmemodb_command:")?;
+    let (rest, deprecated) = match rest.split_once(";deprecated=") {
+        Some((rest, note)) => (rest, Some(note.to_string())),
+        None => (rest, None),
+    };
+    let (name, min_interval_ms) = match rest.split_once(";min_interval_ms=") {
+        Some((name, interval)) => (name, interval.parse::<u64>().ok()),
+        None => (rest, None),
+    };
+    let (name, serialize_per_entity) = match name.strip_suffix(";serialize_per_entity") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+    let (name, aliases) = match name.split_once(";aliases=") {
+        Some((name, aliases)) => (name, aliases.split(',').map(str::to_string).collect()),
+        None => (name, Vec::new()),
+    };
+    Some(CommandAttrOptions {
+        name: if name.trim().is_empty() {
+            None
+        } else {
+            Some(name.to_string())
+        },
+        aliases,
+        min_interval_ms,
+        deprecated,
+        serialize_per_entity,
+    })
+}
+
+fn extract_computed_marker(attrs: &mut Vec<syn::Attribute>) -> syn::Result<bool> {
+    let mut found = false;
+    let mut kept = Vec::with_capacity(attrs.len());
+
+    for attr in attrs.drain(..) {
+        let is_marker = path_ends_with_ident(attr.path(), "computed")
+            || (attr.path().is_ident("doc")
+                && attr
+                    .parse_args::<LitStr>()
+                    .map(|marker| marker.value() == "__rustmemodb_computed")
+                    .unwrap_or(false));
+        if !is_marker {
+            kept.push(attr);
+            continue;
+        }
+        if found {
+            return Err(syn::Error::new(
+                attr.span(),
+                "Duplicate #[computed] marker on method",
+            ));
+        }
+        if path_ends_with_ident(attr.path(), "computed") && !matches!(attr.meta, syn::Meta::Path(_)) {
+            return Err(syn::Error::new(
+                attr.span(),
+                "#[computed] does not accept arguments",
+            ));
+        }
+        found = true;
+    }
+
+    *attrs = kept;
+    Ok(found)
+}
+
+fn validate_computed_method(method: &ImplItemFn) -> syn::Result<()> {
+    if method.sig.asyncness.is_some() {
+        return Err(syn::Error::new(
+            method.sig.span(),
+            "#[computed] methods must be synchronous",
+        ));
+    }
+    if !method.sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            method.sig.generics.span(),
+            "#[computed] methods cannot have generic parameters",
+        ));
+    }
+
+    let mut inputs = method.sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => {
+            return Err(syn::Error::new(
+                method.sig.span(),
+                "#[computed] method receiver must be `&self`",
+            ));
+        }
+    }
+    if let Some(extra) = inputs.next() {
+        return Err(syn::Error::new(
+            extra.span(),
+            "#[computed] methods cannot take arguments besides `&self`",
+        ));
+    }
+    if matches!(method.sig.output, ReturnType::Default) {
+        return Err(syn::Error::new(
+            method.sig.span(),
+            "#[computed] method must return a Serialize value",
+        ));
+    }
+    Ok(())
 }
 
 fn extract_command_marker(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Option<CommandAttrOptions>> {
@@ -1900,6 +3317,101 @@ fn build_runtime_payload_schema_expr(args: &[PersistentCommandArg]) -> TokenStre
     quote!(#expr.allow_extra_fields(false))
 }
 
+/// Best-effort check that every identifier in a `#[sql(check)]` expression names a
+/// column of the model. Quoted literals, keywords and function names are skipped.
+fn validate_check_columns(check: &str, known_columns: &[(String, bool)]) -> Result<(), String> {
+    const KEYWORDS: &[&str] = &[
+        "AND", "OR", "NOT", "NULL", "IS", "IN", "BETWEEN", "LIKE", "ILIKE", "TRUE", "FALSE",
+        "CASE", "WHEN", "THEN", "ELSE", "END",
+    ];
+
+    let mut chars = check.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if ch == '\'' {
+            for (_, next) in chars.by_ref() {
+                if next == '\'' {
+                    break;
+                }
+            }
+            continue;
+        }
+        if ch.is_ascii_digit() {
+            while chars
+                .peek()
+                .is_some_and(|(_, next)| next.is_ascii_alphanumeric() || *next == '.')
+            {
+                chars.next();
+            }
+            continue;
+        }
+        if !(ch.is_ascii_alphabetic() || ch == '_') {
+            continue;
+        }
+
+        let mut end = start + ch.len_utf8();
+        while let Some((index, next)) = chars.peek().copied() {
+            if !(next.is_ascii_alphanumeric() || next == '_') {
+                break;
+            }
+            end = index + next.len_utf8();
+            chars.next();
+        }
+        let ident = &check[start..end];
+        let is_function = check[end..].trim_start().starts_with('(');
+        if is_function || KEYWORDS.contains(&ident.to_ascii_uppercase().as_str()) {
+            continue;
+        }
+        let known = known_columns.iter().any(|(column, flatten)| {
+            if *flatten {
+                ident.starts_with(&format!("{}_", column))
+            } else {
+                column == ident
+            }
+        });
+        if !known {
+            return Err(format!("unknown column '{}'", ident));
+        }
+    }
+    Ok(())
+}
+
+fn is_bool_field_type(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|segment| match segment.ident.to_string().as_str() {
+        "bool" => true,
+        "Option" => first_generic_type(segment).is_some_and(|inner| is_bool_field_type(&inner)),
+        _ => false,
+    })
+}
+
+fn is_string_field_type(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|segment| match segment.ident.to_string().as_str() {
+        "String" => true,
+        "Option" => first_generic_type(segment).is_some_and(|inner| is_string_field_type(&inner)),
+        _ => false,
+    })
+}
+
+fn is_numeric_field_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_numeric_field_type(reference.elem.as_ref()),
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            match segment.ident.to_string().as_str() {
+                "f32" | "f64" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+                | "u32" | "u64" | "u128" | "usize" => true,
+                "Option" => first_generic_type(segment).is_some_and(|inner| is_numeric_field_type(&inner)),
+                _ => false,
+            }
+        }),
+        _ => false,
+    }
+}
+
 fn runtime_payload_type_tokens(ty: &Type) -> TokenStream2 {
     match ty {
         Type::Reference(reference) => {
@@ -1970,6 +3482,35 @@ fn extract_result_ok_type(ty: &Type) -> Option<Type> {
     None
 }
 
+fn type_borrows(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => {
+            let is_static = reference
+                .lifetime
+                .as_ref()
+                .map(|lifetime| lifetime.ident == "static")
+                .unwrap_or(false);
+            !is_static || type_borrows(&reference.elem)
+        }
+        Type::Path(type_path) => type_path.path.segments.iter().any(|segment| {
+            let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                return false;
+            };
+            arguments.args.iter().any(|arg| match arg {
+                syn::GenericArgument::Lifetime(lifetime) => lifetime.ident != "static",
+                syn::GenericArgument::Type(inner) => type_borrows(inner),
+                _ => false,
+            })
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().any(type_borrows),
+        Type::Array(array) => type_borrows(&array.elem),
+        Type::Slice(slice) => type_borrows(&slice.elem),
+        Type::Paren(paren) => type_borrows(&paren.elem),
+        Type::Group(group) => type_borrows(&group.elem),
+        _ => false,
+    }
+}
+
 fn parse_sql_field_options(attrs: &[syn::Attribute]) -> syn::Result<Option<SqlFieldOptions>> {
     let mut options: Option<SqlFieldOptions> = None;
 
@@ -1995,11 +3536,63 @@ fn parse_sql_field_options(attrs: &[syn::Attribute]) -> syn::Result<Option<SqlFi
                         return Ok(());
                     }
 
+                    if meta.path.is_ident("unique") {
+                        parsed.unique = true;
+                        return Ok(());
+                    }
+
                     if meta.path.is_ident("skip") {
                         parsed.include = false;
                         return Ok(());
                     }
 
+                    if meta.path.is_ident("flatten") {
+                        parsed.flatten = true;
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("redact") {
+                        parsed.redact = true;
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("max_len") {
+                        let value = meta.value()?;
+                        let lit: syn::LitInt = value.parse()?;
+                        let max_len = lit.base10_parse::<usize>()?;
+                        if max_len == 0 {
+                            return Err(meta.error("#[sql(max_len = ...)] must be greater than zero"));
+                        }
+                        parsed.max_len = Some(max_len);
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("check") {
+                        let value = meta.value()?;
+                        let lit: LitStr = value.parse()?;
+                        let check = lit.value();
+                        if check.trim().is_empty() {
+                            return Err(meta.error("#[sql(check = \"...\")] must not be empty"));
+                        }
+                        parsed.check = Some(check.trim().to_string());
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("bool_as") {
+                        let value = meta.value()?;
+                        let lit: LitStr = value.parse()?;
+                        match lit.value().as_str() {
+                            "int" => parsed.bool_as_int = true,
+                            "bool" => parsed.bool_as_int = false,
+                            _ => {
+                                return Err(meta.error(
+                                    "#[sql(bool_as = \"...\")] must be \"int\" or \"bool\"",
+                                ));
+                            }
+                        }
+                        return Ok(());
+                    }
+
                     if meta.path.is_ident("name") || meta.path.is_ident("column") {
                         let value = meta.value()?;
                         let lit: LitStr = value.parse()?;
@@ -2007,8 +3600,19 @@ fn parse_sql_field_options(attrs: &[syn::Attribute]) -> syn::Result<Option<SqlFi
                         return Ok(());
                     }
 
+                    if meta.path.is_ident("column_type") {
+                        let value = meta.value()?;
+                        let lit: LitStr = value.parse()?;
+                        let column_type = lit.value();
+                        if column_type.trim().is_empty() {
+                            return Err(meta.error("#[sql(column_type = \"...\")] must not be empty"));
+                        }
+                        parsed.column_type = Some(column_type.trim().to_string());
+                        return Ok(());
+                    }
+
                     Err(meta.error(
-                        "Unsupported #[sql(...)] option. Supported: index, skip, name = \"...\", column = \"...\"",
+                        "Unsupported #[sql(...)] option. Supported: index, unique, skip, flatten, redact, max_len = <usize>, bool_as = \"int\", check = \"...\", name = \"...\", column = \"...\", column_type = \"...\"",
                     ))
                 })?;
             }
@@ -2027,6 +3631,13 @@ fn parse_sql_field_options(attrs: &[syn::Attribute]) -> syn::Result<Option<SqlFi
             ));
         }
 
+        if !parsed.include && parsed.unique {
+            return Err(syn::Error::new(
+                attr.span(),
+                "#[sql(skip)] cannot be combined with #[sql(unique)]",
+            ));
+        }
+
         if !parsed.include && parsed.column_name.is_some() {
             return Err(syn::Error::new(
                 attr.span(),
@@ -2034,16 +3645,104 @@ fn parse_sql_field_options(attrs: &[syn::Attribute]) -> syn::Result<Option<SqlFi
             ));
         }
 
+        if !parsed.include && parsed.column_type.is_some() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "#[sql(skip)] cannot be combined with #[sql(column_type = \"...\")]",
+            ));
+        }
+
+        if parsed.max_len.is_some() && parsed.column_type.is_some() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "#[sql(max_len = ...)] cannot be combined with #[sql(column_type = \"...\")]",
+            ));
+        }
+
+        if parsed.flatten
+            && (!parsed.include
+                || parsed.indexed
+                || parsed.unique
+                || parsed.column_name.is_some()
+                || parsed.column_type.is_some()
+                || parsed.bool_as_int
+                || parsed.max_len.is_some()
+                || parsed.check.is_some())
+        {
+            return Err(syn::Error::new(
+                attr.span(),
+                "#[sql(flatten)] cannot be combined with other #[sql(...)] options",
+            ));
+        }
+
         options = Some(parsed);
     }
 
     Ok(options)
 }
 
+fn parse_persist_field_options(attrs: &[syn::Attribute]) -> syn::Result<PersistFieldOptions> {
+    let mut options = PersistFieldOptions::default();
+
+    for attr in attrs {
+        if !path_ends_with_ident(attr.path(), "persist") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unique_index") {
+                options.unique_index = true;
+                return Ok(());
+            }
+
+            Err(meta.error("Unsupported #[persist(...)] option. Supported: unique_index"))
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn parse_aggregate_options(attrs: &[syn::Attribute]) -> syn::Result<Vec<AggregateOption>> {
+    let mut aggregates = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("aggregate") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            let span = meta.path.span();
+            let kind = if meta.path.is_ident("count") {
+                AggregateKind::Count
+            } else if meta.path.is_ident("exists") {
+                AggregateKind::Exists
+            } else if meta.path.is_ident("sum") {
+                let lit: LitStr = meta.value()?.parse()?;
+                AggregateKind::Sum(lit.value())
+            } else if meta.path.is_ident("avg") {
+                let lit: LitStr = meta.value()?.parse()?;
+                AggregateKind::Avg(lit.value())
+            } else {
+                return Err(meta.error(
+                    "Unsupported aggregate. Supported: count, exists, sum = \"field\", avg = \"field\"",
+                ));
+            };
+            aggregates.push(AggregateOption { kind, span });
+            Ok(())
+        })?;
+    }
+
+    Ok(aggregates)
+}
+
 fn parse_persist_model_options(attrs: &[syn::Attribute]) -> syn::Result<PersistModelOptions> {
     let mut options = PersistModelOptions {
         table_name: None,
         schema_version: None,
+        migrations: None,
+        strict_fields: false,
+        auto_touch: true,
+        generated: Vec::new(),
     };
 
     for attr in attrs {
@@ -2066,11 +3765,173 @@ fn parse_persist_model_options(attrs: &[syn::Attribute]) -> syn::Result<PersistM
                 return Ok(());
             }
 
+            if meta.path.is_ident("migrations") {
+                options.migrations = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+
+            if meta.path.is_ident("strict_fields") {
+                options.strict_fields = if meta.input.peek(Token![=]) {
+                    let lit: syn::LitBool = meta.value()?.parse()?;
+                    lit.value
+                } else {
+                    true
+                };
+                return Ok(());
+            }
+
+            if meta.path.is_ident("auto_touch") {
+                let lit: syn::LitBool = meta.value()?.parse()?;
+                options.auto_touch = lit.value;
+                return Ok(());
+            }
+
+            if meta.path.is_ident("generated") {
+                let span = meta.path.span();
+                let mut name = None;
+                let mut expr = None;
+                let mut sql_type = None;
+                meta.parse_nested_meta(|inner| {
+                    let lit: LitStr = inner.value()?.parse()?;
+                    let value = lit.value().trim().to_string();
+                    if value.is_empty() {
+                        return Err(inner.error("generated column options must not be empty"));
+                    }
+                    if inner.path.is_ident("name") {
+                        name = Some(value);
+                    } else if inner.path.is_ident("expr") {
+                        expr = Some(value);
+                    } else if inner.path.is_ident("type") {
+                        sql_type = Some(value);
+                    } else {
+                        return Err(inner.error(
+                            "Unsupported generated column option. Supported: name = \"...\", expr = \"...\", type = \"...\"",
+                        ));
+                    }
+                    Ok(())
+                })?;
+                let (Some(name), Some(expr), Some(sql_type)) = (name, expr, sql_type) else {
+                    return Err(meta.error(
+                        "generated(...) requires name = \"...\", expr = \"...\" and type = \"...\"",
+                    ));
+                };
+                options.generated.push(GeneratedColumnOption {
+                    name,
+                    expr,
+                    sql_type,
+                    span,
+                });
+                return Ok(());
+            }
+
             Err(meta.error(
-                "Unsupported persist_model attribute. Supported: table = \"...\", schema_version = <u32>",
+                "Unsupported persist_model attribute. Supported: table = \"...\", schema_version = <u32>, migrations = <fn path>, strict_fields = <bool>, auto_touch = <bool>, generated(name = \"...\", expr = \"...\", type = \"...\")",
             ))
         })?;
     }
 
     Ok(options)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_expression_columns_are_validated() {
+        let columns = vec![
+            ("price_cents".to_string(), false),
+            ("address".to_string(), true),
+        ];
+        assert!(validate_check_columns("price_cents >= 0", &columns).is_ok());
+        assert!(
+            validate_check_columns(
+                "LENGTH(address_zip) = 5 AND price_cents <> 'x y' OR price_cents IS NULL",
+                &columns
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            validate_check_columns("price >= 0", &columns),
+            Err("unknown column 'price'".to_string())
+        );
+    }
+
+    #[test]
+    fn persistent_impl_rejects_duplicate_command_names() {
+        let item_impl: ItemImpl = syn::parse_quote! {
+            impl Order {
+                #[command(name = "approve")]
+                fn approve(&mut self) {}
+
+                #[command(name = "approve")]
+                fn approve_again(&mut self) {}
+            }
+        };
+        let err = expand_persistent_impl_attr(item_impl).unwrap_err();
+        assert!(err.to_string().contains("Duplicate #[command] name 'approve'"));
+    }
+
+    #[test]
+    fn persistent_impl_rejects_alias_colliding_with_other_command() {
+        let item_impl: ItemImpl = syn::parse_quote! {
+            impl Order {
+                #[command(name = ["deactivate", "disable"])]
+                fn deactivate(&mut self) {}
+
+                #[command]
+                fn disable(&mut self) {}
+            }
+        };
+        let err = expand_persistent_impl_attr(item_impl).unwrap_err();
+        assert!(err.to_string().contains("Duplicate #[command] name 'disable'"));
+    }
+
+    #[test]
+    fn command_name_rejects_marker_separators() {
+        let err = parse_command_attr_tokens(quote!(name = "pay;deprecated=x"))
+            .err()
+            .expect("';' in a name must be rejected");
+        assert!(err.to_string().contains("must not contain"), "{err}");
+        assert!(parse_command_attr_tokens(quote!(name = "pay,close")).is_err());
+        assert!(parse_command_attr_tokens(quote!(name = ["pay", "close,settle"])).is_err());
+        assert!(parse_command_attr_tokens(quote!(name = ["pay", "close;x"])).is_err());
+    }
+
+    #[test]
+    fn command_doc_marker_round_trips_serialize_per_entity() {
+        let options = parse_command_attr_tokens(quote! {
+            name = ["settle", "close"], serialize_per_entity, deprecated = "use pay; then close"
+        })
+        .unwrap();
+        let parsed = parse_command_doc_marker(&command_doc_marker(&options)).unwrap();
+        assert_eq!(parsed.name.as_deref(), Some("settle"));
+        assert_eq!(parsed.aliases, vec!["close".to_string()]);
+        assert!(parsed.serialize_per_entity);
+        assert_eq!(parsed.deprecated.as_deref(), Some("use pay; then close"));
+
+        let options = parse_command_attr_tokens(quote!(serialize_per_entity)).unwrap();
+        let parsed = parse_command_doc_marker(&command_doc_marker(&options)).unwrap();
+        assert_eq!(parsed.name, None);
+        assert!(parsed.serialize_per_entity);
+    }
+
+    #[test]
+    fn persistent_impl_allows_computed_and_command_with_same_name() {
+        let item_impl: ItemImpl = syn::parse_quote! {
+            impl Order {
+                #[command]
+                fn approve(&mut self) {}
+
+                #[computed]
+                fn approval(&self) -> bool {
+                    true
+                }
+
+                #[command(name = "approval")]
+                fn set_approval(&mut self) {}
+            }
+        };
+        assert!(expand_persistent_impl_attr(item_impl).is_ok());
+    }
+}
@@ -2,11 +2,15 @@ pub mod auth;
 pub mod config;
 pub mod pool;
 
-use crate::core::{DbError, Result};
+use crate::core::{Column, DataType, DbError, Result, Value};
 use crate::facade::InMemoryDB;
-use crate::result::QueryResult;
+use crate::result::result::{render_value_at, utc_offset};
+use crate::result::{QueryResult, RowStream};
+use crate::storage::Table;
 use crate::transaction::TransactionId;
 use auth::{User, enforce_permissions};
+use chrono::FixedOffset;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -19,12 +23,30 @@ pub struct Connection {
     id: u64,
     /// Authenticated user
     user: User,
-    /// Shared database instance
+    /// Database selected with `USE` (the default database unless changed)
     db: Arc<RwLock<InMemoryDB>>,
+    /// Default database instance, which also hosts `CREATE DATABASE` databases
+    default_db: Arc<RwLock<InMemoryDB>>,
+    /// Name of the default database
+    default_database: String,
+    /// Name of the selected database
+    database: String,
     /// Connection state
     state: ConnectionState,
     /// Active transaction ID (if any)
     transaction_id: Option<TransactionId>,
+    /// Savepoints of the active transaction, innermost last
+    savepoints: Vec<Savepoint>,
+    /// Offset used to render timestamps in results (`SET timezone`)
+    timezone: FixedOffset,
+}
+
+/// Variables accepted by `SET` and `SHOW`.
+pub const SESSION_VARIABLES: &[&str] = &["timezone", "search_path"];
+
+struct Savepoint {
+    name: String,
+    tables: HashMap<String, Table>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,13 +58,20 @@ enum ConnectionState {
 
 impl Connection {
     /// Create a new connection (internal use)
-    pub(crate) fn new(id: u64, user: User, db: Arc<RwLock<InMemoryDB>>) -> Self {
+    ///
+    /// `database` is the name under which `db` is selected with `USE`.
+    pub(crate) fn new(id: u64, user: User, db: Arc<RwLock<InMemoryDB>>, database: &str) -> Self {
         Self {
             id,
             user,
+            default_db: Arc::clone(&db),
             db,
+            default_database: database.to_string(),
+            database: database.to_string(),
             state: ConnectionState::Active,
             transaction_id: None,
+            savepoints: Vec::new(),
+            timezone: utc_offset(),
         }
     }
 
@@ -56,7 +85,153 @@ impl Connection {
         self.user.username()
     }
 
+    /// Get the name of the database selected for this connection
+    pub fn current_database(&self) -> &str {
+        &self.database
+    }
+
+    /// Select the database used by subsequent statements (`USE name`)
+    pub async fn use_database(&mut self, name: &str) -> Result<()> {
+        if self.state == ConnectionState::InTransaction {
+            return Err(DbError::ExecutionError(
+                "Cannot switch database inside a transaction".into(),
+            ));
+        }
+        let db = if name == self.default_database {
+            Arc::clone(&self.default_db)
+        } else {
+            self.default_db.read().await.database(name).ok_or_else(|| {
+                DbError::ExecutionError(format!("Database '{}' does not exist", name))
+            })?
+        };
+        self.db = db;
+        self.database = name.to_string();
+        Ok(())
+    }
+
+    /// Switch back to the default database and session variable defaults
+    pub(crate) fn reset_session(&mut self) {
+        self.db = Arc::clone(&self.default_db);
+        self.database = self.default_database.clone();
+        self.timezone = utc_offset();
+    }
+
+    /// Offset set with `SET timezone`, UTC by default
+    pub fn timezone(&self) -> FixedOffset {
+        self.timezone
+    }
+
+    /// Render a value for display, converting timestamps to the session timezone
+    pub fn render_value(&self, value: &Value) -> String {
+        render_value_at(value, self.timezone)
+    }
+
+    /// Current value of a session variable, as shown by `SHOW`
+    pub fn session_variable(&self, name: &str) -> Result<String> {
+        match name.to_ascii_lowercase().as_str() {
+            "timezone" => Ok(if self.timezone == utc_offset() {
+                "UTC".to_string()
+            } else {
+                self.timezone.to_string()
+            }),
+            "search_path" => Ok(self.database.clone()),
+            _ => Err(unknown_session_variable(name)),
+        }
+    }
+
+    /// Set a session variable (`SET name = value`)
+    ///
+    /// `timezone` accepts `UTC` or a fixed offset such as `+03:00`;
+    /// `search_path` selects a database like `USE`.
+    pub async fn set_session_variable(&mut self, name: &str, value: &str) -> Result<()> {
+        match name.to_ascii_lowercase().as_str() {
+            "timezone" => {
+                self.timezone = parse_timezone(value)?;
+                Ok(())
+            }
+            "search_path" => self.use_database(value).await,
+            _ => Err(unknown_session_variable(name)),
+        }
+    }
+
+    async fn execute_database_statement(
+        &mut self,
+        statement: DatabaseStatement,
+    ) -> Result<QueryResult> {
+        match statement {
+            DatabaseStatement::Use(name) => {
+                self.use_database(&name).await?;
+                Ok(QueryResult::empty_with_message(format!(
+                    "Using database '{}'",
+                    name
+                )))
+            }
+            DatabaseStatement::Create {
+                name,
+                if_not_exists,
+            } => {
+                self.require_admin("CREATE DATABASE")?;
+                let default_db = self.default_db.read().await;
+                if name == self.default_database || default_db.database(&name).is_some() {
+                    if if_not_exists {
+                        return Ok(QueryResult::empty_with_message(format!(
+                            "Database '{}' already exists",
+                            name
+                        )));
+                    }
+                    return Err(DbError::ExecutionError(format!(
+                        "Database '{}' already exists",
+                        name
+                    )));
+                }
+                default_db.create_database(&name)?;
+                Ok(QueryResult::empty_with_message(format!(
+                    "Database '{}' created",
+                    name
+                )))
+            }
+            DatabaseStatement::Drop { name, if_exists } => {
+                self.require_admin("DROP DATABASE")?;
+                if name == self.default_database {
+                    return Err(DbError::ExecutionError(
+                        "Cannot drop the default database".into(),
+                    ));
+                }
+                if name == self.database {
+                    return Err(DbError::ExecutionError(
+                        "Cannot drop the currently selected database".into(),
+                    ));
+                }
+                let dropped = self.default_db.read().await.drop_database(&name);
+                if !dropped && !if_exists {
+                    return Err(DbError::ExecutionError(format!(
+                        "Database '{}' does not exist",
+                        name
+                    )));
+                }
+                Ok(QueryResult::empty_with_message(format!(
+                    "Database '{}' dropped",
+                    name
+                )))
+            }
+        }
+    }
+
+    fn require_admin(&self, operation: &str) -> Result<()> {
+        if !self.user.is_admin() {
+            return Err(DbError::ExecutionError(format!(
+                "Permission denied: Admin required for {}",
+                operation
+            )));
+        }
+        Ok(())
+    }
+
     /// Execute a SQL query
+    ///
+    /// Inside a transaction, a statement that fails to parse, is not permitted
+    /// or fails while executing rolls back the whole transaction, savepoints
+    /// included. Use `savepoint`/`rollback_to` to undo steps that succeeded.
     pub async fn execute(&mut self, sql: &str) -> Result<QueryResult> {
         if self.state == ConnectionState::Closed {
             return Err(DbError::ExecutionError("Connection is closed".into()));
@@ -82,7 +257,62 @@ impl Connection {
                 "Transaction rolled back".to_string(),
             ));
         }
+        if let Some(name) = savepoint_statement_name(sql, &["SAVEPOINT"]) {
+            self.savepoint(&name).await?;
+            return Ok(QueryResult::empty_with_message(format!(
+                "Savepoint '{}' created",
+                name
+            )));
+        }
+        if let Some(name) = savepoint_statement_name(sql, &["ROLLBACK", "TO", "SAVEPOINT"])
+            .or_else(|| savepoint_statement_name(sql, &["ROLLBACK", "TO"]))
+        {
+            self.rollback_to(&name).await?;
+            return Ok(QueryResult::empty_with_message(format!(
+                "Rolled back to savepoint '{}'",
+                name
+            )));
+        }
+        if let Some(statement) = parse_database_statement(sql) {
+            return self.execute_database_statement(statement).await;
+        }
+        if let Some(statement) = parse_session_statement(sql) {
+            return match statement {
+                SessionStatement::Set { name, value } => {
+                    self.set_session_variable(&name, &value).await?;
+                    Ok(QueryResult::empty_with_message(format!("SET {}", name)))
+                }
+                SessionStatement::Show(name) => {
+                    let value = self.session_variable(&name)?;
+                    Ok(QueryResult::new(
+                        vec![Column::new(name.to_ascii_lowercase(), DataType::Text)],
+                        vec![vec![Value::Text(value)]],
+                    ))
+                }
+            };
+        }
+        if let Some(name) = savepoint_statement_name(sql, &["RELEASE", "SAVEPOINT"])
+            .or_else(|| savepoint_statement_name(sql, &["RELEASE"]))
+        {
+            self.release(&name)?;
+            return Ok(QueryResult::empty_with_message(format!(
+                "Savepoint '{}' released",
+                name
+            )));
+        }
+
+        match self.execute_statement(sql).await {
+            Ok(result) => Ok(result.with_timezone(self.timezone)),
+            Err(err) => {
+                if self.state == ConnectionState::InTransaction {
+                    let _ = self.rollback().await;
+                }
+                Err(err)
+            }
+        }
+    }
 
+    async fn execute_statement(&self, sql: &str) -> Result<QueryResult> {
         let statement = {
             let db = self.db.read().await;
             db.parse_first(sql)?
@@ -90,41 +320,46 @@ impl Connection {
 
         enforce_permissions(&self.user, &statement)?;
 
-        let result = {
-            let db = self.db.read().await;
-            if InMemoryDB::is_read_only_stmt(&statement) {
-                db.execute_parsed_readonly_with_params_tracked(
-                    &statement,
-                    self.transaction_id,
-                    vec![],
-                    sql,
-                )
-                .await
-            } else if !InMemoryDB::is_ddl_stmt(&statement) {
-                db.execute_parsed_with_params_shared_tracked(
-                    &statement,
-                    self.transaction_id,
-                    vec![],
-                    sql,
-                )
+        let db = self.db.read().await;
+        if InMemoryDB::is_read_only_stmt(&statement) {
+            db.execute_parsed_readonly_with_params_tracked(
+                &statement,
+                self.transaction_id,
+                vec![],
+                sql,
+            )
+            .await
+        } else if !InMemoryDB::is_ddl_stmt(&statement) {
+            db.execute_parsed_with_params_shared_tracked(
+                &statement,
+                self.transaction_id,
+                vec![],
+                sql,
+            )
+            .await
+        } else {
+            drop(db);
+            let mut db = self.db.write().await;
+            db.execute_parsed_with_params_tracked(&statement, self.transaction_id, vec![], sql)
                 .await
-            } else {
-                drop(db);
-                let mut db = self.db.write().await;
-                db.execute_parsed_with_params_tracked(&statement, self.transaction_id, vec![], sql)
-                    .await
-            }
-        };
+        }
+    }
 
-        match result {
-            Ok(result) => Ok(result),
-            Err(err) => {
-                if self.state == ConnectionState::InTransaction {
-                    let _ = self.rollback().await;
-                }
-                Err(err)
-            }
+    /// Execute a SELECT and return its rows as a stream
+    ///
+    /// Rows are produced in batches from the snapshot taken when the stream is
+    /// opened, so the database is not locked while the stream is consumed and
+    /// later writes are not visible. Dropping the stream cancels the query.
+    pub async fn execute_returning_stream(&mut self, sql: &str) -> Result<RowStream> {
+        if self.state == ConnectionState::Closed {
+            return Err(DbError::ExecutionError("Connection is closed".into()));
         }
+
+        let db = self.db.read().await;
+        let statement = db.parse_first(sql)?;
+        enforce_permissions(&self.user, &statement)?;
+        let source = db.open_row_source(&statement, self.transaction_id).await?;
+        Ok(source.into_stream().with_timezone(self.timezone))
     }
 
     /// Execute a query and return the result
@@ -210,6 +445,7 @@ impl Connection {
 
         self.state = ConnectionState::Active;
         self.transaction_id = None;
+        self.savepoints.clear();
 
         Ok(())
     }
@@ -240,10 +476,93 @@ impl Connection {
 
         self.state = ConnectionState::Active;
         self.transaction_id = None;
+        self.savepoints.clear();
+
+        Ok(())
+    }
+
+    /// Create a savepoint inside the active transaction
+    ///
+    /// Reusing a name shadows the earlier savepoint until it is released.
+    pub async fn savepoint(&mut self, name: &str) -> Result<()> {
+        let txn_id = self.require_transaction("SAVEPOINT")?;
+
+        let tables = {
+            let mut db = self.db.write().await;
+            db.storage_mut().get_all_tables().await?
+        };
 
+        if let Some(persistence) = self.db.read().await.persistence() {
+            let mut persistence_guard = persistence.lock().await;
+            persistence_guard.log(&crate::storage::WalEntry::Savepoint {
+                tx_id: txn_id.0,
+                name: name.to_string(),
+            })?;
+        }
+
+        self.savepoints.push(Savepoint {
+            name: name.to_string(),
+            tables,
+        });
         Ok(())
     }
 
+    /// Undo everything written since the named savepoint
+    ///
+    /// The savepoint stays active; savepoints created after it are discarded.
+    pub async fn rollback_to(&mut self, name: &str) -> Result<()> {
+        let txn_id = self.require_transaction("ROLLBACK TO SAVEPOINT")?;
+        let index = self.savepoint_index(name)?;
+
+        {
+            let mut db = self.db.write().await;
+            db.storage_mut()
+                .rollback_tx_to_savepoint(&self.savepoints[index].tables, txn_id.0)
+                .await?;
+        }
+
+        if let Some(persistence) = self.db.read().await.persistence() {
+            let mut persistence_guard = persistence.lock().await;
+            persistence_guard.log(&crate::storage::WalEntry::RollbackToSavepoint {
+                tx_id: txn_id.0,
+                name: name.to_string(),
+            })?;
+        }
+
+        self.savepoints.truncate(index + 1);
+        Ok(())
+    }
+
+    /// Release the named savepoint and every savepoint created after it
+    ///
+    /// Changes made since the savepoint are kept.
+    pub fn release(&mut self, name: &str) -> Result<()> {
+        self.require_transaction("RELEASE SAVEPOINT")?;
+        let index = self.savepoint_index(name)?;
+        self.savepoints.truncate(index);
+        Ok(())
+    }
+
+    fn require_transaction(&self, operation: &str) -> Result<TransactionId> {
+        if self.state == ConnectionState::Closed {
+            return Err(DbError::ExecutionError("Connection is closed".into()));
+        }
+        match self.transaction_id {
+            Some(txn_id) if self.state == ConnectionState::InTransaction => Ok(txn_id),
+            _ => Err(DbError::ExecutionError(format!(
+                "{} can only be used inside an active transaction",
+                operation
+            ))),
+        }
+    }
+
+    fn savepoint_index(&self, name: &str) -> Result<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|savepoint| savepoint.name == name)
+            .ok_or_else(|| DbError::ExecutionError(format!("Savepoint '{}' does not exist", name)))
+    }
+
     /// Check if connection is in a transaction
     pub fn is_in_transaction(&self) -> bool {
         self.state == ConnectionState::InTransaction
@@ -274,6 +593,7 @@ impl Connection {
             sql: sql.to_string(),
             db: Arc::clone(&self.db),
             user: self.user.clone(),
+            timezone: self.timezone,
         })
     }
 }
@@ -308,6 +628,154 @@ impl Drop for Connection {
     }
 }
 
+fn savepoint_statement_name(sql: &str, keywords: &[&str]) -> Option<String> {
+    let sql = sql.trim().trim_end_matches(';');
+    let mut tokens = sql.split_whitespace();
+    for keyword in keywords {
+        if !tokens.next()?.eq_ignore_ascii_case(keyword) {
+            return None;
+        }
+    }
+    let name = tokens.next()?;
+    if tokens.next().is_some() {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+enum DatabaseStatement {
+    Use(String),
+    Create { name: String, if_not_exists: bool },
+    Drop { name: String, if_exists: bool },
+}
+
+fn parse_database_statement(sql: &str) -> Option<DatabaseStatement> {
+    let sql = sql.trim().trim_end_matches(';');
+    let tokens = sql.split_whitespace().collect::<Vec<_>>();
+    let is = |index: usize, keyword: &str| {
+        tokens
+            .get(index)
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    };
+    let name = |index: usize| {
+        (tokens.len() == index + 1).then(|| tokens[index].trim_matches('"').to_string())
+    };
+
+    if is(0, "USE") {
+        return name(1).map(DatabaseStatement::Use);
+    }
+    if !is(1, "DATABASE") {
+        return None;
+    }
+    if is(0, "CREATE") {
+        let if_not_exists = is(2, "IF") && is(3, "NOT") && is(4, "EXISTS");
+        let name = name(if if_not_exists { 5 } else { 2 })?;
+        return Some(DatabaseStatement::Create {
+            name,
+            if_not_exists,
+        });
+    }
+    if is(0, "DROP") {
+        let if_exists = is(2, "IF") && is(3, "EXISTS");
+        let name = name(if if_exists { 4 } else { 2 })?;
+        return Some(DatabaseStatement::Drop { name, if_exists });
+    }
+    None
+}
+
+enum SessionStatement {
+    Set { name: String, value: String },
+    Show(String),
+}
+
+/// Parses `SET name = value`, `SET name TO value` and `SHOW name` for the
+/// names in `SESSION_VARIABLES`; anything else (`SHOW TABLES`, ...) is left to
+/// the SQL parser.
+fn parse_session_statement(sql: &str) -> Option<SessionStatement> {
+    let sql = sql.trim().trim_end_matches(';').trim();
+    let (keyword, rest) = sql.split_once(char::is_whitespace)?;
+    let rest = rest.trim();
+    let is_session_variable = |name: &str| {
+        SESSION_VARIABLES
+            .iter()
+            .any(|variable| name.eq_ignore_ascii_case(variable))
+    };
+    if keyword.eq_ignore_ascii_case("SHOW") {
+        if !is_session_variable(rest) {
+            return None;
+        }
+        return Some(SessionStatement::Show(rest.to_string()));
+    }
+    if !keyword.eq_ignore_ascii_case("SET") {
+        return None;
+    }
+
+    let (name, value) = match rest.split_once('=') {
+        Some((name, value)) => (name.trim(), value.trim()),
+        None => {
+            let (name, value) = rest.split_once(char::is_whitespace)?;
+            let (to, value) = value.trim().split_once(char::is_whitespace)?;
+            if !to.eq_ignore_ascii_case("TO") {
+                return None;
+            }
+            (name, value.trim())
+        }
+    };
+    if value.is_empty() || !is_session_variable(name) {
+        return None;
+    }
+    let value = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+        .unwrap_or(value);
+    Some(SessionStatement::Set {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Accepts `UTC`/`GMT`/`Z` and offsets written as `+03:00`, `-0530` or `+3`.
+fn parse_timezone(value: &str) -> Result<FixedOffset> {
+    let invalid = || {
+        DbError::ExecutionError(format!(
+            "Unsupported timezone '{}': use UTC or a fixed offset such as +03:00",
+            value
+        ))
+    };
+    let trimmed = value.trim();
+    if ["UTC", "GMT", "Z"]
+        .iter()
+        .any(|name| trimmed.eq_ignore_ascii_case(name))
+    {
+        return Ok(utc_offset());
+    }
+
+    let (sign, digits) = match trimmed.chars().next() {
+        Some('+') => (1, &trimmed[1..]),
+        Some('-') => (-1, &trimmed[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours = hours.parse::<i32>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<i32>().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+fn unknown_session_variable(name: &str) -> DbError {
+    DbError::ExecutionError(format!(
+        "Unknown session variable '{}'. Supported: {}",
+        name,
+        SESSION_VARIABLES.join(", ")
+    ))
+}
+
 /// Prepared statement
 ///
 /// Placeholder for future parameterized query support
@@ -315,6 +783,8 @@ pub struct PreparedStatement {
     sql: String,
     db: Arc<RwLock<InMemoryDB>>,
     user: User,
+    /// Session timezone when the statement was prepared
+    timezone: FixedOffset,
 }
 
 impl PreparedStatement {
@@ -336,26 +806,27 @@ impl PreparedStatement {
             db_guard.parse_first(&self.sql)?
         };
 
-        {
+        let result = {
             let db_guard = self.db.read().await;
             enforce_permissions(&self.user, &statement)?;
             if InMemoryDB::is_read_only_stmt(&statement) {
-                return db_guard
+                db_guard
                     .execute_parsed_readonly_with_params_tracked(
                         &statement, None, params, &self.sql,
                     )
-                    .await;
-            }
-            if !InMemoryDB::is_ddl_stmt(&statement) {
-                return db_guard
+                    .await
+            } else if !InMemoryDB::is_ddl_stmt(&statement) {
+                db_guard
                     .execute_parsed_with_params_shared_tracked(&statement, None, params, &self.sql)
-                    .await;
+                    .await
+            } else {
+                drop(db_guard);
+                let mut db = self.db.write().await;
+                db.execute_parsed_with_params_tracked(&statement, None, params, &self.sql)
+                    .await
             }
-        }
-
-        let mut db = self.db.write().await;
-        db.execute_parsed_with_params_tracked(&statement, None, params, &self.sql)
-            .await
+        };
+        result.map(|result| result.with_timezone(self.timezone))
     }
 
     /// Get the SQL text of this prepared statement
@@ -392,7 +863,7 @@ mod tests {
     async fn create_test_connection() -> Connection {
         let db = Arc::new(RwLock::new(InMemoryDB::new()));
         let user = User::new("test_user".to_string(), "hash".to_string(), Vec::new());
-        Connection::new(1, user, db)
+        Connection::new(1, user, db, "rustmemodb")
     }
 
     #[tokio::test]
@@ -193,7 +193,7 @@ impl ConnectionPool {
         *next_id += 1;
 
         // Create connection
-        let connection = Connection::new(id, user, Arc::clone(&self.db));
+        let connection = Connection::new(id, user, Arc::clone(&self.db), &self.config.database);
 
         self.total_connections.fetch_add(1, Ordering::SeqCst);
 
@@ -214,7 +214,7 @@ impl ConnectionPool {
             let id = *next_id;
             *next_id += 1;
 
-            let connection = Connection::new(id, user, Arc::clone(&self.db));
+            let connection = Connection::new(id, user, Arc::clone(&self.db), &self.config.database);
             available.push_back(PooledConnection::new(connection));
 
             self.total_connections.fetch_add(1, Ordering::SeqCst);
@@ -309,6 +309,21 @@ impl PoolGuard {
         self.connection().rollback().await
     }
 
+    /// Create a savepoint (convenience method)
+    pub async fn savepoint(&mut self, name: &str) -> Result<()> {
+        self.connection().savepoint(name).await
+    }
+
+    /// Rollback to a savepoint (convenience method)
+    pub async fn rollback_to(&mut self, name: &str) -> Result<()> {
+        self.connection().rollback_to(name).await
+    }
+
+    /// Release a savepoint (convenience method)
+    pub fn release(&mut self, name: &str) -> Result<()> {
+        self.connection().release(name)
+    }
+
     /// Explicitly close the guard and return the connection to the pool.
     ///
     /// This method allows for async cleanup (rollback) which is not possible in Drop.
@@ -320,6 +335,7 @@ impl PoolGuard {
             }
 
             // Return to pool
+            connection.reset_session();
             let mut pool = self.pool.lock().await;
             pool.push_back(PooledConnection::new(connection));
         }
@@ -329,7 +345,7 @@ impl PoolGuard {
 
 impl Drop for PoolGuard {
     fn drop(&mut self) {
-        if let Some(connection) = self.connection.take() {
+        if let Some(mut connection) = self.connection.take() {
             // If we are here, close() was not called.
             // Check if we can return it to the pool immediately (only if no transaction).
 
@@ -344,6 +360,7 @@ impl Drop for PoolGuard {
             }
 
             // Try to return to pool if we can acquire the lock immediately
+            connection.reset_session();
             if let Ok(mut pool) = self.pool.try_lock() {
                 pool.push_back(PooledConnection::new(connection));
             } else {
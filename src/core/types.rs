@@ -1,3 +1,4 @@
+use super::value::parse_interval;
 use super::{DbError, Result, Value};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -27,6 +28,12 @@ pub enum DataType {
     Uuid,
     Array(Box<DataType>),
     Json,
+    /// Text restricted to a fixed set of values, e.g. `ENUM('open', 'closed')`
+    Enum(Vec<String>),
+    /// Text of at most N characters, e.g. `VARCHAR(255)`
+    Varchar(usize),
+    /// Fixed-length time span, see `Value::Interval`
+    Interval,
     Unknown, // For parameter inference
 }
 
@@ -43,16 +50,20 @@ impl DataType {
             (Self::Timestamp, Value::Timestamp(_)) => true,
             (Self::Date, Value::Date(_)) => true,
             (Self::Uuid, Value::Uuid(_)) => true,
+            (Self::Interval, Value::Interval(_)) => true,
             // Allow string parsing for complex types
             (Self::Timestamp, Value::Text(_)) => true,
             (Self::Date, Value::Text(_)) => true,
             (Self::Uuid, Value::Text(_)) => true,
+            (Self::Interval, Value::Text(_)) => true,
 
             (Self::Array(elem_type), Value::Array(arr)) => {
                 arr.iter().all(|v| elem_type.is_compatible(v))
             }
             (Self::Json, Value::Json(_)) => true,
             (Self::Json, Value::Text(_)) => true, // Allow parsing JSON from text
+            (Self::Enum(_), Value::Text(_)) => true, // Membership is checked by Column::validate
+            (Self::Varchar(_), Value::Text(_)) => true, // Length is checked by Column::validate
 
             _ => false,
         }
@@ -73,6 +84,10 @@ impl DataType {
             (Self::Text, Self::Date) => true,
             (Self::Text, Self::Json) => true,
             (Self::Json, Self::Text) => true,
+            (Self::Enum(_), Self::Text) => true,
+            (Self::Text, Self::Enum(_)) => true,
+            (Self::Varchar(_), Self::Text) => true,
+            (Self::Text, Self::Varchar(_)) => true,
             (Self::Unknown, _) => true, // Unknown can cast to anything (inferred)
             (_, Self::Unknown) => true,
             _ => false,
@@ -89,6 +104,33 @@ impl DataType {
         }
 
         match (self, value) {
+            (Self::Enum(allowed), v) => {
+                let text = match v {
+                    Value::Text(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                if allowed.contains(&text) {
+                    Ok(Value::Text(text))
+                } else {
+                    Err(DbError::ConstraintViolation(format!(
+                        "Value '{}' is not one of {}",
+                        text, self
+                    )))
+                }
+            }
+            (Self::Varchar(max_len), v) => {
+                let text = match v {
+                    Value::Text(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                if text.chars().count() > *max_len {
+                    return Err(DbError::ConstraintViolation(format!(
+                        "Value '{}' is longer than {} characters",
+                        text, max_len
+                    )));
+                }
+                Ok(Value::Text(text))
+            }
             (Self::Float, Value::Integer(i)) => Ok(Value::Float(*i as f64)),
             (Self::Integer, Value::Float(f)) => Ok(Value::Integer(*f as i64)),
 
@@ -130,6 +172,8 @@ impl DataType {
                 }
             }
 
+            (Self::Interval, Value::Text(s)) => parse_interval(s).map(Value::Interval),
+
             (Self::Json, Value::Text(s)) => {
                 if let Ok(json) = serde_json::from_str(s) {
                     Ok(Value::Json(json))
@@ -138,7 +182,27 @@ impl DataType {
                 }
             }
 
+            (Self::Array(inner), Value::Array(items)) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    if !inner.is_compatible(item) {
+                        return Err(DbError::TypeMismatch(format!(
+                            "Array element {} of type {} does not match element type {}",
+                            item,
+                            item.type_name(),
+                            inner
+                        )));
+                    }
+                    values.push(inner.cast_value(item)?);
+                }
+                Ok(Value::Array(values))
+            }
+
             (Self::Array(inner), Value::Text(s)) => {
+                // JSON-encoded `Vec<Value>`, as written by `value_to_sql_literal`
+                if let Ok(items) = serde_json::from_str::<Vec<Value>>(s) {
+                    return self.cast_value(&Value::Array(items));
+                }
                 // Basic array parsing "{a,b}"
                 let trimmed = s.trim();
                 if (trimmed.starts_with('{') && trimmed.ends_with('}'))
@@ -184,7 +248,12 @@ impl DataType {
             (Self::Date, Value::Date(_)) => true,
             (Self::Uuid, Value::Uuid(_)) => true,
             (Self::Json, Value::Json(_)) => true,
-            (Self::Array(_), Value::Array(_)) => true, // Simplification: doesn't check inner types
+            (Self::Interval, Value::Interval(_)) => true,
+            (Self::Enum(allowed), Value::Text(text)) => allowed.contains(text),
+            (Self::Varchar(max_len), Value::Text(text)) => text.chars().count() <= *max_len,
+            (Self::Array(inner), Value::Array(items)) => items
+                .iter()
+                .all(|item| matches!(item, Value::Null) || inner.is_exact_match(item)),
             _ => false,
         }
     }
@@ -202,7 +271,16 @@ impl fmt::Display for DataType {
             Self::Uuid => write!(f, "UUID"),
             Self::Array(t) => write!(f, "{}[]", t),
             Self::Json => write!(f, "JSONB"),
+            Self::Enum(values) => {
+                let quoted = values
+                    .iter()
+                    .map(|value| format!("'{}'", value.replace('\'', "''")))
+                    .collect::<Vec<_>>();
+                write!(f, "ENUM({})", quoted.join(", "))
+            }
+            Self::Varchar(max_len) => write!(f, "VARCHAR({})", max_len),
             Self::Unknown => write!(f, "UNKNOWN"),
+            Self::Interval => write!(f, "INTERVAL"),
         }
     }
 }
@@ -283,6 +361,24 @@ impl Column {
             )));
         }
 
+        if let (DataType::Enum(allowed), Value::Text(text)) = (&self.data_type, value)
+            && !allowed.contains(text)
+        {
+            return Err(DbError::ConstraintViolation(format!(
+                "Column '{}' does not accept '{}'; expected one of {}",
+                self.name, text, self.data_type
+            )));
+        }
+
+        if let (DataType::Varchar(max_len), Value::Text(text)) = (&self.data_type, value)
+            && text.chars().count() > *max_len
+        {
+            return Err(DbError::ConstraintViolation(format!(
+                "Column '{}' value is longer than {} characters",
+                self.name, max_len
+            )));
+        }
+
         Ok(())
     }
 }
@@ -18,6 +18,8 @@ pub enum Value {
     Uuid(Uuid),
     Array(Vec<Value>),
     Json(serde_json::Value),
+    /// Fixed-length interval in microseconds, see [`parse_interval`].
+    Interval(i64),
 }
 
 impl Value {
@@ -59,27 +61,15 @@ impl Value {
             (Value::Timestamp(a), Value::Timestamp(b)) => Ok(a.cmp(b)),
             (Value::Date(a), Value::Date(b)) => Ok(a.cmp(b)),
             (Value::Uuid(a), Value::Uuid(b)) => Ok(a.cmp(b)),
+            (Value::Interval(a), Value::Interval(b)) => Ok(a.cmp(b)),
 
-            // Mixed numeric types
+            // Mixed numeric types; NaN sorts after every number, as with floats.
             (Value::Integer(a), Value::Float(b)) => {
-                let a_float = *a as f64;
-                match (a_float.is_nan(), b.is_nan()) {
-                    (true, true) => Ok(Ordering::Equal),
-                    (true, false) => Ok(Ordering::Greater),
-                    (false, true) => Ok(Ordering::Less),
-                    (false, false) => Ok(a_float.partial_cmp(b).unwrap_or(Ordering::Equal)),
-                }
-            }
-
-            (Value::Float(a), Value::Integer(b)) => {
-                let b_float = *b as f64;
-                match (a.is_nan(), b_float.is_nan()) {
-                    (true, true) => Ok(Ordering::Equal),
-                    (true, false) => Ok(Ordering::Greater),
-                    (false, true) => Ok(Ordering::Less),
-                    (false, false) => Ok(a.partial_cmp(&b_float).unwrap_or(Ordering::Equal)),
-                }
+                Ok(compare_int_float(*a, *b).unwrap_or(Ordering::Less))
             }
+            (Value::Float(a), Value::Integer(b)) => Ok(compare_int_float(*b, *a)
+                .map(Ordering::reverse)
+                .unwrap_or(Ordering::Greater)),
 
             (Value::Array(a), Value::Array(b)) => {
                 // Lexicographical comparison for arrays
@@ -120,6 +110,7 @@ impl Value {
             Self::Uuid(_) => "UUID",
             Self::Array(_) => "ARRAY",
             Self::Json(_) => "JSON",
+            Self::Interval(_) => "INTERVAL",
         }
     }
 
@@ -135,6 +126,16 @@ impl Value {
             Self::Uuid(_) => true,
             Self::Array(a) => !a.is_empty(),
             Self::Json(_) => true,
+            Self::Interval(micros) => *micros != 0,
+        }
+    }
+
+    /// Three-valued read of a BOOLEAN column: `None` for NULL (unknown) and
+    /// for non-boolean values. Unlike `as_bool`, NULL never reads as `false`.
+    pub fn as_nullable_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
         }
     }
 
@@ -187,6 +188,7 @@ impl Value {
             Self::Uuid(_) => 7,
             Self::Array(_) => 8,
             Self::Json(_) => 9,
+            Self::Interval(_) => 10,
         }
     }
 
@@ -199,6 +201,7 @@ impl Value {
             Value::Timestamp(_) => 0,
             Value::Date(_) => 0,
             Value::Uuid(_) => 0,
+            Value::Interval(_) => 0,
             Value::Text(text) => text.len(),
             Value::Array(values) => {
                 let element_overhead = values.len().saturating_mul(std::mem::size_of::<Value>());
@@ -217,6 +220,126 @@ impl Value {
     }
 }
 
+/// Orders an integer against a float without rounding the integer to `f64`,
+/// so precision is kept beyond 2^53. Returns `None` when `f` is NaN.
+pub fn compare_int_float(i: i64, f: f64) -> Option<Ordering> {
+    // 2^63 is exactly representable; every finite float below it and at or
+    // above -2^63 truncates to an in-range i64.
+    const I64_UPPER: f64 = 9_223_372_036_854_775_808.0;
+    if f.is_nan() {
+        return None;
+    }
+    if f >= I64_UPPER {
+        return Some(Ordering::Less);
+    }
+    if f < -I64_UPPER {
+        return Some(Ordering::Greater);
+    }
+    let whole = f.trunc();
+    Some(i.cmp(&(whole as i64)).then_with(|| {
+        let fraction = f - whole;
+        if fraction > 0.0 {
+            Ordering::Less
+        } else if fraction < 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }))
+}
+
+/// Returns `f` as an `i64` when it is integral and in range.
+pub fn float_to_exact_int(f: f64) -> Option<i64> {
+    let i = f as i64;
+    (compare_int_float(i, f) == Some(Ordering::Equal)).then_some(i)
+}
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+
+/// Parses interval text such as `7 days`, `1 day 2 hours` or `-90 minutes`
+/// into microseconds. Supported units are microseconds, milliseconds,
+/// seconds, minutes, hours, days and weeks (singular, plural or short forms).
+/// Months and years have no fixed length and are rejected, as is a total
+/// that does not fit in `i64` microseconds.
+pub fn parse_interval(text: &str) -> Result<i64> {
+    let invalid = || DbError::ExecutionError(format!("Invalid interval: '{}'", text));
+    let out_of_range = || DbError::ExecutionError(format!("Interval out of range: '{}'", text));
+
+    let tokens = text.split_whitespace().collect::<Vec<_>>();
+    if tokens.is_empty() || tokens.len() % 2 != 0 {
+        return Err(invalid());
+    }
+
+    let mut total: i64 = 0;
+    for pair in tokens.chunks(2) {
+        let quantity = pair[0].parse::<i64>().map_err(|_| invalid())?;
+        let unit_micros = match pair[1].to_lowercase().as_str() {
+            "microsecond" | "microseconds" | "us" => 1,
+            "millisecond" | "milliseconds" | "ms" => 1_000,
+            "second" | "seconds" | "sec" | "secs" | "s" => MICROS_PER_SECOND,
+            "minute" | "minutes" | "min" | "mins" | "m" => 60 * MICROS_PER_SECOND,
+            "hour" | "hours" | "h" => 3_600 * MICROS_PER_SECOND,
+            "day" | "days" | "d" => MICROS_PER_DAY,
+            "week" | "weeks" | "w" => 7 * MICROS_PER_DAY,
+            "month" | "months" | "mon" | "mons" | "year" | "years" | "y" => {
+                return Err(DbError::ExecutionError(format!(
+                    "Interval unit '{}' has no fixed length and is not supported",
+                    pair[1]
+                )));
+            }
+            _ => return Err(invalid()),
+        };
+        total = quantity
+            .checked_mul(unit_micros)
+            .and_then(|micros| total.checked_add(micros))
+            .ok_or_else(out_of_range)?;
+    }
+    Ok(total)
+}
+
+/// Formats microseconds as `N microseconds`, the lossless text form
+/// `parse_interval` reads back. Used wherever intervals leave the engine as
+/// text (state JSON, JSON documents, CSV).
+pub fn interval_text(micros: i64) -> String {
+    format!("{} microseconds", micros)
+}
+
+/// Formats microseconds as `[N days ]HH:MM:SS[.ffffff]`, e.g. `7 days` or
+/// `1 day 02:30:00`.
+pub fn format_interval(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let magnitude = micros.unsigned_abs();
+    let days = magnitude / MICROS_PER_DAY as u64;
+    let rest = magnitude % MICROS_PER_DAY as u64;
+
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!(
+            "{}{} {}",
+            sign,
+            days,
+            if days == 1 { "day" } else { "days" }
+        ));
+    }
+    if rest > 0 || days == 0 {
+        let seconds = rest / MICROS_PER_SECOND as u64;
+        let fraction = rest % MICROS_PER_SECOND as u64;
+        let mut time = format!(
+            "{}{:02}:{:02}:{:02}",
+            sign,
+            seconds / 3_600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        if fraction > 0 {
+            time.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+        }
+        parts.push(time);
+    }
+    parts.join(" ")
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -238,9 +361,10 @@ impl PartialEq for Value {
             (Self::Uuid(a), Value::Uuid(b)) => a == b,
             (Self::Array(a), Self::Array(b)) => a == b,
             (Self::Json(a), Self::Json(b)) => a == b,
+            (Self::Interval(a), Self::Interval(b)) => a == b,
 
             (Self::Integer(i), Self::Float(f)) | (Self::Float(f), Self::Integer(i)) => {
-                (*i as f64 - f).abs() < f64::EPSILON
+                compare_int_float(*i, *f) == Some(Ordering::Equal)
             }
             _ => false,
         }
@@ -269,11 +393,18 @@ impl Ord for Value {
                 (false, true) => Ordering::Less,
                 (false, false) => a.partial_cmp(b).unwrap(),
             },
+            (Self::Integer(a), Self::Float(b)) => {
+                compare_int_float(*a, *b).unwrap_or(Ordering::Less)
+            }
+            (Self::Float(a), Self::Integer(b)) => compare_int_float(*b, *a)
+                .map(Ordering::reverse)
+                .unwrap_or(Ordering::Greater),
             (Self::Text(a), Self::Text(b)) => a.cmp(b),
             (Self::Boolean(a), Self::Boolean(b)) => a.cmp(b),
             (Self::Timestamp(a), Self::Timestamp(b)) => a.cmp(b),
             (Self::Date(a), Self::Date(b)) => a.cmp(b),
             (Self::Uuid(a), Self::Uuid(b)) => a.cmp(b),
+            (Self::Interval(a), Self::Interval(b)) => a.cmp(b),
             (Self::Array(a), Self::Array(b)) => {
                 for (va, vb) in a.iter().zip(b.iter()) {
                     // Note: This unwrap is safe because we implement Ord for Value
@@ -342,6 +473,10 @@ impl Hash for Value {
                 9u8.hash(state);
                 j.to_string().hash(state);
             }
+            Self::Interval(micros) => {
+                10u8.hash(state);
+                micros.hash(state);
+            }
         }
     }
 }
@@ -380,6 +515,7 @@ impl fmt::Display for Value {
                 write!(f, "]")
             }
             Self::Json(j) => write!(f, "{}", j),
+            Self::Interval(micros) => write!(f, "{}", format_interval(*micros)),
         }
     }
 }
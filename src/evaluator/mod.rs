@@ -187,6 +187,7 @@ impl EvaluatorRegistry {
         registry.register(Box::new(between::BetweenEvaluator));
         registry.register(Box::new(is_null::IsNullEvaluator));
         registry.register(Box::new(json::JsonEvaluator));
+        registry.register(Box::new(array::ArrayEvaluator));
         registry.register(Box::new(function::FunctionEvaluator));
 
         registry
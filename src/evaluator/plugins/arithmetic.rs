@@ -1,6 +1,7 @@
 use super::super::{EvaluationContext, ExpressionEvaluator};
 use crate::core::{DbError, Result, Row, Schema, Value};
 use crate::parser::ast::{BinaryOp, Expr};
+use chrono::{DateTime, Duration, Utc};
 
 use async_trait::async_trait;
 
@@ -103,6 +104,38 @@ impl ExpressionEvaluator for ArithmeticEvaluator {
                 Ok(Value::Float(result))
             }
 
+            (Value::Timestamp(ts), Value::Interval(micros)) => match op {
+                BinaryOp::Add => shift_timestamp(ts, micros),
+                BinaryOp::Subtract => shift_timestamp(
+                    ts,
+                    micros
+                        .checked_neg()
+                        .ok_or_else(|| DbError::ExecutionError("Interval out of range".into()))?,
+                ),
+                _ => Err(unsupported_temporal_op(op, "TIMESTAMP", "INTERVAL")),
+            },
+            (Value::Interval(micros), Value::Timestamp(ts)) => match op {
+                BinaryOp::Add => shift_timestamp(ts, micros),
+                _ => Err(unsupported_temporal_op(op, "INTERVAL", "TIMESTAMP")),
+            },
+            (Value::Timestamp(a), Value::Timestamp(b)) => match op {
+                BinaryOp::Subtract => (a - b)
+                    .num_microseconds()
+                    .map(Value::Interval)
+                    .ok_or_else(|| DbError::ExecutionError("Interval out of range".into())),
+                _ => Err(unsupported_temporal_op(op, "TIMESTAMP", "TIMESTAMP")),
+            },
+            (Value::Interval(a), Value::Interval(b)) => {
+                let result = match op {
+                    BinaryOp::Add => a.checked_add(b),
+                    BinaryOp::Subtract => a.checked_sub(b),
+                    _ => return Err(unsupported_temporal_op(op, "INTERVAL", "INTERVAL")),
+                };
+                result
+                    .map(Value::Interval)
+                    .ok_or_else(|| DbError::ExecutionError("Interval out of range".into()))
+            }
+
             (a, b) => Err(DbError::TypeMismatch(format!(
                 "Arithmetic requires numeric types, got {} and {}",
                 a.type_name(),
@@ -111,3 +144,17 @@ impl ExpressionEvaluator for ArithmeticEvaluator {
         }
     }
 }
+
+/// Adds `micros` to `ts`, failing instead of wrapping outside chrono's range.
+fn shift_timestamp(ts: DateTime<Utc>, micros: i64) -> Result<Value> {
+    ts.checked_add_signed(Duration::microseconds(micros))
+        .map(Value::Timestamp)
+        .ok_or_else(|| DbError::ExecutionError("Timestamp out of range".into()))
+}
+
+fn unsupported_temporal_op(op: &BinaryOp, left: &str, right: &str) -> DbError {
+    DbError::TypeMismatch(format!(
+        "Unsupported operation {:?} between {} and {}",
+        op, left, right
+    ))
+}
@@ -0,0 +1,114 @@
+use super::comparison::ComparisonEvaluator;
+use crate::core::{DbError, Result, Row, Schema, Value};
+use crate::evaluator::{EvaluationContext, ExpressionEvaluator};
+use crate::parser::ast::{BinaryOp, Expr};
+
+use async_trait::async_trait;
+
+pub struct ArrayEvaluator;
+
+#[async_trait]
+impl ExpressionEvaluator for ArrayEvaluator {
+    fn name(&self) -> &'static str {
+        "ARRAY"
+    }
+
+    fn can_evaluate(&self, expr: &Expr) -> bool {
+        matches!(
+            expr,
+            Expr::Array(_)
+                | Expr::AnyOp { .. }
+                | Expr::BinaryOp {
+                    op: BinaryOp::Contains,
+                    ..
+                }
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        expr: &Expr,
+        row: &Row,
+        schema: &Schema,
+        context: &EvaluationContext<'_>,
+    ) -> Result<Value> {
+        match expr {
+            Expr::Array(items) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    values.push(context.evaluate(item, row, schema).await?);
+                }
+                ensure_homogeneous(&values)?;
+                Ok(Value::Array(values))
+            }
+            Expr::AnyOp { left, op, right } => {
+                let left_val = context.evaluate(left, row, schema).await?;
+                let right_val = context.evaluate(right, row, schema).await?;
+                if matches!(left_val, Value::Null) || matches!(right_val, Value::Null) {
+                    return Ok(Value::Null);
+                }
+                let Value::Array(items) = right_val else {
+                    return Err(DbError::TypeMismatch(format!(
+                        "Right operand of ANY must be an array, got {}",
+                        right_val.type_name()
+                    )));
+                };
+
+                let mut saw_null = false;
+                for item in &items {
+                    if matches!(item, Value::Null) {
+                        saw_null = true;
+                        continue;
+                    }
+                    if ComparisonEvaluator.compare(&left_val, item, op)? {
+                        return Ok(Value::Boolean(true));
+                    }
+                }
+
+                if saw_null {
+                    return Ok(Value::Null);
+                }
+                Ok(Value::Boolean(false))
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                let left_val = context.evaluate(left, row, schema).await?;
+                let right_val = context.evaluate(right, row, schema).await?;
+                match (left_val, right_val) {
+                    (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+                    (Value::Array(haystack), Value::Array(needles)) => Ok(Value::Boolean(
+                        needles.iter().all(|needle| haystack.contains(needle)),
+                    )),
+                    (l, r) => Err(DbError::TypeMismatch(format!(
+                        "Operands of @> must be arrays, got {} and {}",
+                        l.type_name(),
+                        r.type_name()
+                    ))),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn ensure_homogeneous(values: &[Value]) -> Result<()> {
+    let mut element_type: Option<&'static str> = None;
+    for value in values {
+        let type_name = match value {
+            Value::Null => continue,
+            // Integer and Float mix freely, as in column coercion
+            Value::Integer(_) | Value::Float(_) => "NUMBER",
+            other => other.type_name(),
+        };
+        match element_type {
+            None => element_type = Some(type_name),
+            Some(expected) if expected != type_name => {
+                return Err(DbError::TypeMismatch(format!(
+                    "ARRAY elements must share one type, found {} and {}",
+                    expected, type_name
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
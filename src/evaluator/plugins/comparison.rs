@@ -1,6 +1,8 @@
 use super::super::{EvaluationContext, ExpressionEvaluator};
+use crate::core::value::compare_int_float;
 use crate::core::{DbError, Result, Row, Schema, Value};
 use crate::parser::ast::{BinaryOp, Expr};
+use std::cmp::Ordering;
 
 use async_trait::async_trait;
 
@@ -72,32 +74,14 @@ impl ComparisonEvaluator {
                 _ => unreachable!(),
             }),
 
-            // Mixed Integer/Float comparisons
-            (Value::Integer(a), Value::Float(b)) => {
-                let a_float = *a as f64;
-                Ok(match op {
-                    BinaryOp::Eq => (a_float - b).abs() < f64::EPSILON,
-                    BinaryOp::NotEq => (a_float - b).abs() >= f64::EPSILON,
-                    BinaryOp::Lt => a_float < *b,
-                    BinaryOp::LtEq => a_float <= *b,
-                    BinaryOp::Gt => a_float > *b,
-                    BinaryOp::GtEq => a_float >= *b,
-                    _ => unreachable!(),
-                })
-            }
-
-            (Value::Float(a), Value::Integer(b)) => {
-                let b_float = *b as f64;
-                Ok(match op {
-                    BinaryOp::Eq => (a - b_float).abs() < f64::EPSILON,
-                    BinaryOp::NotEq => (a - b_float).abs() >= f64::EPSILON,
-                    BinaryOp::Lt => *a < b_float,
-                    BinaryOp::LtEq => *a <= b_float,
-                    BinaryOp::Gt => *a > b_float,
-                    BinaryOp::GtEq => *a >= b_float,
-                    _ => unreachable!(),
-                })
-            }
+            // Mixed Integer/Float comparisons are exact (no rounding of the
+            // integer to f64); NaN never matches, not even with `<>`.
+            (Value::Integer(a), Value::Float(b)) => Ok(
+                compare_int_float(*a, *b).is_some_and(|ordering| ordering_matches(ordering, op))
+            ),
+
+            (Value::Float(a), Value::Integer(b)) => Ok(compare_int_float(*b, *a)
+                .is_some_and(|ordering| ordering_matches(ordering.reverse(), op))),
 
             (Value::Text(a), Value::Text(b)) => Ok(match op {
                 BinaryOp::Eq => a == b,
@@ -159,3 +143,15 @@ impl ComparisonEvaluator {
         }
     }
 }
+
+fn ordering_matches(ordering: Ordering, op: &BinaryOp) -> bool {
+    match op {
+        BinaryOp::Eq => ordering == Ordering::Equal,
+        BinaryOp::NotEq => ordering != Ordering::Equal,
+        BinaryOp::Lt => ordering == Ordering::Less,
+        BinaryOp::LtEq => ordering != Ordering::Greater,
+        BinaryOp::Gt => ordering == Ordering::Greater,
+        BinaryOp::GtEq => ordering != Ordering::Less,
+        _ => unreachable!(),
+    }
+}
@@ -33,7 +33,8 @@ impl ExpressionEvaluator for FunctionEvaluator {
                 "LOWER" => self.lower(&eval_args),
                 "LENGTH" => self.length(&eval_args),
                 "COALESCE" => self.coalesce(&eval_args),
-                "NOW" => self.now(),
+                "NOW" | "CURRENT_TIMESTAMP" => self.now(),
+                "JSON_EXTRACT" => self.json_extract(&eval_args),
                 _ => Err(DbError::UnsupportedOperation(format!(
                     "Unknown function: {}",
                     name
@@ -91,4 +92,117 @@ impl FunctionEvaluator {
     fn now(&self) -> Result<Value> {
         Ok(Value::Timestamp(chrono::Utc::now()))
     }
+
+    fn json_extract(&self, args: &[Value]) -> Result<Value> {
+        if args.len() != 2 {
+            return Err(DbError::ExecutionError(
+                "JSON_EXTRACT expects 2 arguments".into(),
+            ));
+        }
+
+        let path = match &args[1] {
+            Value::Text(path) => path,
+            Value::Null => return Ok(Value::Null),
+            _ => {
+                return Err(DbError::TypeMismatch(
+                    "JSON_EXTRACT path must be Text".into(),
+                ));
+            }
+        };
+
+        let document = match &args[0] {
+            Value::Json(j) => j.clone(),
+            Value::Text(s) => serde_json::from_str(s)
+                .map_err(|e| DbError::ExecutionError(format!("Invalid JSON: {}", e)))?,
+            Value::Null => return Ok(Value::Null),
+            _ => {
+                return Err(DbError::TypeMismatch(
+                    "JSON_EXTRACT expects a JSON document".into(),
+                ));
+            }
+        };
+
+        let mut current = &document;
+        for segment in parse_json_path(path)? {
+            let next = match segment {
+                JsonPathSegment::Key(key) => current.get(key.as_str()),
+                JsonPathSegment::Index(index) => current.get(index),
+            };
+            match next {
+                Some(value) => current = value,
+                None => return Ok(Value::Null),
+            }
+        }
+
+        // Scalars come back as native values so they compare naturally in WHERE.
+        Ok(match current {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => Value::Text(s.clone()),
+            other => Value::Json(other.clone()),
+        })
+    }
+}
+
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse a JSON path such as `$.a.b`, `$.items[0].name` or `$["odd key"]`.
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathSegment>> {
+    let invalid = || DbError::ExecutionError(format!("Invalid JSON path: {}", path));
+
+    let rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let chars = rest.chars().collect::<Vec<_>>();
+    let mut segments = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        match chars[pos] {
+            '.' => {
+                pos += 1;
+                let start = pos;
+                while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                    pos += 1;
+                }
+                if start == pos {
+                    return Err(invalid());
+                }
+                segments.push(JsonPathSegment::Key(chars[start..pos].iter().collect()));
+            }
+            '[' => {
+                pos += 1;
+                let start = pos;
+                while pos < chars.len() && chars[pos] != ']' {
+                    pos += 1;
+                }
+                if pos >= chars.len() {
+                    return Err(invalid());
+                }
+                let inner = chars[start..pos].iter().collect::<String>();
+                pos += 1;
+
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('"')
+                    .and_then(|s| s.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')));
+                match quoted {
+                    Some(key) => segments.push(JsonPathSegment::Key(key.to_string())),
+                    None => {
+                        let index = inner.parse::<usize>().map_err(|_| invalid())?;
+                        segments.push(JsonPathSegment::Index(index));
+                    }
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(segments)
 }
@@ -1,4 +1,5 @@
 pub mod arithmetic;
+pub mod array;
 pub mod between;
 pub mod boolean;
 pub mod comparison;
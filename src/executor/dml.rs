@@ -169,6 +169,7 @@ impl InsertExecutor {
         let tx_id = ctx.snapshot.tx_id;
         let mut logged_begin = false;
         let mut logged_any = false;
+        let inserted_count = rows.len();
 
         for row in rows {
             // Log to WAL before applying
@@ -199,7 +200,7 @@ impl InsertExecutor {
             }
         }
 
-        Ok(QueryResult::empty())
+        Ok(QueryResult::inserted(inserted_count))
     }
 
     fn build_row_from_exprs(
@@ -325,14 +326,20 @@ impl InsertExecutor {
         expected_type: &crate::core::DataType,
         ctx: &ExecutionContext<'_>,
     ) -> Result<Value> {
+        // `Unknown` keeps ARRAY[...] elements uncast so the array cast can
+        // reject mismatched element types
+        let cast = |value: &Value| match expected_type {
+            crate::core::DataType::Unknown => Ok(value.clone()),
+            _ => expected_type.cast_value(value),
+        };
         match expr {
-            Expr::Literal(val) => expected_type.cast_value(val),
+            Expr::Literal(val) => cast(val),
             Expr::UnaryOp { op, expr } => match (&**expr, op) {
                 (Expr::Literal(Value::Integer(i)), crate::parser::ast::UnaryOp::Minus) => {
-                    expected_type.cast_value(&Value::Integer(-i))
+                    cast(&Value::Integer(-i))
                 }
                 (Expr::Literal(Value::Float(f)), crate::parser::ast::UnaryOp::Minus) => {
-                    expected_type.cast_value(&Value::Float(-f))
+                    cast(&Value::Float(-f))
                 }
                 _ => Err(DbError::UnsupportedOperation(
                     "Only unary minus for numeric literals supported in INSERT".into(),
@@ -346,7 +353,14 @@ impl InsertExecutor {
                     )));
                 }
                 let val = &ctx.params[*idx - 1];
-                expected_type.cast_value(val)
+                cast(val)
+            }
+            Expr::Array(items) => {
+                let values = items
+                    .iter()
+                    .map(|item| self.evaluate_literal(item, &crate::core::DataType::Unknown, ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                expected_type.cast_value(&Value::Array(values))
             }
             _ => Err(DbError::UnsupportedOperation(
                 "Only literal values or parameters supported in INSERT".into(),
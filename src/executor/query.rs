@@ -3,11 +3,12 @@
 // ============================================================================
 
 use super::{ExecutionContext, Executor};
-use crate::core::{DbError, Result, Row, Schema, Value};
+use crate::core::value::{compare_int_float, float_to_exact_int};
+use crate::core::{DataType, DbError, Result, Row, Schema, Value};
 use crate::evaluator::plugins::comparison::ComparisonEvaluator;
 use crate::evaluator::{EvaluationContext, EvaluatorRegistry, SubqueryHandler};
-use crate::parser::ast::{Expr, OrderByExpr, QueryStmt, Statement};
-use crate::planner::logical_plan::SortNode;
+use crate::parser::ast::{BinaryOp, Expr, OrderByExpr, QueryStmt, Statement};
+use crate::planner::logical_plan::{IndexOp, SortNode};
 use crate::planner::{LogicalPlan, QueryPlanner};
 use crate::result::QueryResult;
 use crate::storage::Catalog;
@@ -94,6 +95,50 @@ impl QueryExecutor {
         }
     }
 
+    pub(crate) fn plan_query(&self, stmt: &Statement) -> Result<LogicalPlan> {
+        self.planner.plan(stmt, &self.catalog)
+    }
+
+    /// The full table scan under `plan` when everything above it is filters and
+    /// non-aggregate projections, i.e. rows can be produced batch by batch.
+    pub(crate) fn streamable_scan<'p>(
+        &self,
+        plan: &'p LogicalPlan,
+    ) -> Option<&'p crate::planner::logical_plan::TableScanNode> {
+        match plan {
+            LogicalPlan::TableScan(scan) if scan.index_scan.is_none() => Some(scan),
+            LogicalPlan::Filter(filter) => self.streamable_scan(&filter.input),
+            LogicalPlan::Projection(proj) if !self.has_aggregate_functions(&proj.expressions) => {
+                self.streamable_scan(&proj.input)
+            }
+            _ => None,
+        }
+    }
+
+    /// Runs a plan accepted by `streamable_scan` over one batch of scanned rows.
+    #[async_recursion]
+    pub(crate) async fn execute_plan_on_batch(
+        &self,
+        plan: &LogicalPlan,
+        rows: Vec<Row>,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Vec<Row>> {
+        match plan {
+            LogicalPlan::TableScan(_) => Ok(rows),
+            LogicalPlan::Filter(filter) => {
+                let input = self.execute_plan_on_batch(&filter.input, rows, ctx).await?;
+                self.filter_rows(filter, input, ctx).await
+            }
+            LogicalPlan::Projection(proj) => {
+                let input = self.execute_plan_on_batch(&proj.input, rows, ctx).await?;
+                self.project_rows(proj, input, ctx).await
+            }
+            _ => Err(DbError::ExecutionError(
+                "Plan cannot be executed batch by batch".into(),
+            )),
+        }
+    }
+
     /// Get output column names from plan
     pub fn get_output_columns(
         &self,
@@ -437,14 +482,51 @@ impl QueryExecutor {
         ctx: &ExecutionContext<'_>,
     ) -> Result<Vec<Row>> {
         if let Some(ref idx) = scan.index_scan {
-            let value =
-                self.evaluate_index_expr(&idx.value_expr, &idx.column, scan.schema.columns(), ctx)?;
+            let value = self.evaluate_index_expr(&idx.value_expr, ctx)?;
             let end_value = match &idx.end_value_expr {
-                Some(expr) => {
-                    Some(self.evaluate_index_expr(expr, &idx.column, scan.schema.columns(), ctx)?)
-                }
+                Some(expr) => Some(self.evaluate_index_expr(expr, ctx)?),
                 None => None,
             };
+            // Index keys cannot hold a bound that only compares exactly as the
+            // other numeric type (e.g. 9.5 against INTEGER), so filter row by row.
+            if let Some(col_idx) = scan.schema.find_column_index(&idx.column)
+                && let Some(data_type) = scan.schema.columns().get(col_idx).map(|c| &c.data_type)
+                && (!is_lossless_numeric_cast(data_type, &value)
+                    || end_value
+                        .as_ref()
+                        .is_some_and(|end| !is_lossless_numeric_cast(data_type, end)))
+            {
+                let comparator = ComparisonEvaluator;
+                let bounds = match (&idx.op, &end_value) {
+                    (IndexOp::Between, Some(end)) => {
+                        vec![(BinaryOp::GtEq, &value), (BinaryOp::LtEq, end)]
+                    }
+                    (IndexOp::Eq, _) => vec![(BinaryOp::Eq, &value)],
+                    (IndexOp::Gt, _) => vec![(BinaryOp::Gt, &value)],
+                    (IndexOp::GtEq, _) => vec![(BinaryOp::GtEq, &value)],
+                    (IndexOp::Lt, _) => vec![(BinaryOp::Lt, &value)],
+                    (IndexOp::LtEq | IndexOp::Between, _) => vec![(BinaryOp::LtEq, &value)],
+                };
+                let rows = ctx
+                    .storage
+                    .scan_table(&scan.table_name, &ctx.snapshot)
+                    .await?;
+                let mut matched = Vec::new();
+                for row in rows {
+                    let mut keep = true;
+                    for (op, bound) in &bounds {
+                        keep &= comparator.compare(&row[col_idx], bound, op)?;
+                    }
+                    if keep {
+                        matched.push(row);
+                    }
+                }
+                return Ok(matched);
+            }
+            let value = cast_index_value(&idx.column, scan.schema.columns(), value)?;
+            let end_value = end_value
+                .map(|end| cast_index_value(&idx.column, scan.schema.columns(), end))
+                .transpose()?;
             if let Some(rows) = ctx
                 .storage
                 .scan_index(
@@ -468,8 +550,6 @@ impl QueryExecutor {
     fn evaluate_index_expr(
         &self,
         expr: &Expr,
-        column_name: &str,
-        columns: &[crate::core::Column],
         ctx: &ExecutionContext<'_>,
     ) -> Result<crate::core::Value> {
         let raw = match expr {
@@ -504,9 +584,6 @@ impl QueryExecutor {
                 ));
             }
         };
-        if let Some(col) = columns.iter().find(|c| c.name == column_name) {
-            return col.data_type.cast_value(&raw);
-        }
         Ok(raw)
     }
 
@@ -517,6 +594,15 @@ impl QueryExecutor {
         ctx: &ExecutionContext<'_>,
     ) -> Result<Vec<Row>> {
         let input_rows = self.execute_plan(&filter.input, ctx).await?;
+        self.filter_rows(filter, input_rows, ctx).await
+    }
+
+    async fn filter_rows(
+        &self,
+        filter: &crate::planner::logical_plan::FilterNode,
+        input_rows: Vec<Row>,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Vec<Row>> {
         let schema = &filter.schema;
 
         if let Some(simple) = self.try_simple_predicate(&filter.predicate, schema, &ctx.params) {
@@ -566,6 +652,16 @@ impl QueryExecutor {
                 .await;
         }
 
+        self.project_rows(proj, input_rows, ctx).await
+    }
+
+    async fn project_rows(
+        &self,
+        proj: &crate::planner::logical_plan::ProjectionNode,
+        input_rows: Vec<Row>,
+        ctx: &ExecutionContext<'_>,
+    ) -> Result<Vec<Row>> {
+        let input_schema = proj.input.schema();
         let subquery_handler = ExecutorSubqueryHandler {
             executor: self,
             ctx,
@@ -970,6 +1066,9 @@ impl QueryExecutor {
             }
             _ => return None,
         };
+        if !is_lossless_numeric_cast(&column.data_type, &raw) {
+            return None;
+        }
         let value = column.data_type.cast_value(&raw).ok()?;
 
         Some(SimplePredicate { col_idx, value })
@@ -1023,6 +1122,9 @@ impl QueryExecutor {
             Expr::ArrayIndex { obj, index } => {
                 self.is_constant_expression(obj) && self.is_constant_expression(index)
             }
+            Expr::AnyOp { left, right, .. } => {
+                self.is_constant_expression(left) && self.is_constant_expression(right)
+            }
             Expr::Function { .. }
             | Expr::Column(_)
             | Expr::CompoundIdentifier(_)
@@ -1468,6 +1570,31 @@ impl SimplePredicate {
     }
 }
 
+// Enum/VARCHAR columns store plain text; a non-member or overlong lookup simply finds nothing.
+fn cast_index_value(
+    column_name: &str,
+    columns: &[crate::core::Column],
+    raw: Value,
+) -> Result<Value> {
+    if let Some(col) = columns.iter().find(|c| c.name == column_name)
+        && !matches!(col.data_type, DataType::Enum(_) | DataType::Varchar(_))
+    {
+        return col.data_type.cast_value(&raw);
+    }
+    Ok(raw)
+}
+
+// False when casting `raw` to the column type would round it, e.g. 9.5 -> 9.
+fn is_lossless_numeric_cast(data_type: &DataType, raw: &Value) -> bool {
+    match (data_type, raw) {
+        (DataType::Integer, Value::Float(f)) => float_to_exact_int(*f).is_some(),
+        (DataType::Float, Value::Integer(i)) => {
+            compare_int_float(*i, *i as f64) == Some(Ordering::Equal)
+        }
+        _ => true,
+    }
+}
+
 /// Wrapper for Value to implement strict Hash/Eq for joins
 #[derive(Debug, Clone)]
 struct JoinKey(Value);
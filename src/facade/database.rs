@@ -7,6 +7,7 @@ use crate::executor::query::QueryExecutor;
 use crate::executor::update::UpdateExecutor;
 use crate::executor::{BeginExecutor, CommitExecutor, RollbackExecutor};
 use crate::executor::{ExecutionContext, ExecutorPipeline};
+use crate::facade::row_source::{QueryRowSource, RowSourceCursor};
 use crate::parser::SqlParserAdapter;
 use crate::parser::ast::{CreateTableStmt, CreateViewStmt, DropTableStmt, DropViewStmt, Statement};
 use crate::planner::QueryPlanner;
@@ -15,9 +16,9 @@ use crate::storage::{Catalog, InMemoryStorage, TableSchema};
 use crate::storage::{DurabilityMode, PersistenceManager, WalEntry};
 use crate::transaction::TransactionManager;
 use lazy_static::lazy_static;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{Mutex, RwLock};
 
@@ -38,6 +39,8 @@ pub struct InMemoryDB {
     persistence: Option<Arc<Mutex<PersistenceManager>>>,
     autovac_threshold: Option<usize>,
     query_metrics: Arc<Mutex<QueryMetricsStore>>,
+    /// Additional isolated databases created with `CREATE DATABASE`
+    databases: Arc<StdMutex<HashMap<String, Arc<RwLock<InMemoryDB>>>>>,
 }
 
 impl InMemoryDB {
@@ -79,6 +82,7 @@ impl InMemoryDB {
             persistence: None,
             autovac_threshold,
             query_metrics: Arc::new(Mutex::new(QueryMetricsStore::new())),
+            databases: Arc::new(StdMutex::new(HashMap::new())),
         }
     }
 
@@ -90,6 +94,58 @@ impl InMemoryDB {
         self.persistence.clone()
     }
 
+    /// Create an isolated database hosted by this instance
+    ///
+    /// Each hosted database has its own tables, catalog and transactions.
+    /// Hosted databases are in-memory only: they do not share this instance's
+    /// WAL or snapshots and are gone after a restart.
+    pub fn create_database(&self, name: &str) -> Result<Arc<RwLock<InMemoryDB>>> {
+        let mut databases = self
+            .databases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if databases.contains_key(name) {
+            return Err(DbError::ExecutionError(format!(
+                "Database '{}' already exists",
+                name
+            )));
+        }
+        let db = Arc::new(RwLock::new(InMemoryDB::new()));
+        databases.insert(name.to_string(), Arc::clone(&db));
+        Ok(db)
+    }
+
+    /// Drop a hosted database, returning whether it existed
+    pub fn drop_database(&self, name: &str) -> bool {
+        self.databases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(name)
+            .is_some()
+    }
+
+    /// Get a hosted database by name
+    pub fn database(&self, name: &str) -> Option<Arc<RwLock<InMemoryDB>>> {
+        self.databases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// Names of the hosted databases, sorted
+    pub fn database_names(&self) -> Vec<String> {
+        let mut names = self
+            .databases
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
     pub(crate) fn storage_mut(&mut self) -> &mut InMemoryStorage {
         &mut self.storage
     }
@@ -352,6 +408,10 @@ impl InMemoryDB {
                 self.collect_params_from_expr(obj, max);
                 self.collect_params_from_expr(index, max);
             }
+            Expr::AnyOp { left, right, .. } => {
+                self.collect_params_from_expr(left, max);
+                self.collect_params_from_expr(right, max);
+            }
             Expr::Column(_) | Expr::CompoundIdentifier(_) | Expr::Literal(_) => {}
         }
     }
@@ -596,6 +656,60 @@ impl InMemoryDB {
         result
     }
 
+    /// Prepares a SELECT for `Connection::execute_returning_stream`. The rows are
+    /// read later from a fork of the storage through the snapshot taken here.
+    pub(crate) async fn open_row_source(
+        &self,
+        statement: &Statement,
+        transaction_id: Option<crate::transaction::TransactionId>,
+    ) -> Result<QueryRowSource> {
+        if !matches!(statement, Statement::Query(_)) {
+            return Err(DbError::UnsupportedOperation(
+                "Streaming execution supports only SELECT".into(),
+            ));
+        }
+
+        let snapshot = match transaction_id {
+            Some(txn_id) => self.transaction_manager.get_snapshot(txn_id).await?,
+            None => self.transaction_manager.get_auto_commit_snapshot().await?,
+        };
+        let executor = QueryExecutor::new(self.catalog.clone());
+
+        let (columns, cursor) = match Self::system_query_from_statement(statement) {
+            Some(system_query) => {
+                let result = self.handle_system_query(system_query).await?;
+                (
+                    result.columns().to_vec(),
+                    RowSourceCursor::Buffered(Some(result.rows().to_vec())),
+                )
+            }
+            None => {
+                let plan = executor.plan_query(statement)?;
+                let columns = plan.schema().columns().to_vec();
+                let cursor = match executor.streamable_scan(&plan) {
+                    Some(scan) => RowSourceCursor::Scan {
+                        table_name: scan.table_name.clone(),
+                        next_id: Some(0),
+                        plan,
+                    },
+                    None => RowSourceCursor::Materialize { plan, done: false },
+                };
+                (columns, cursor)
+            }
+        };
+
+        Ok(QueryRowSource {
+            executor,
+            storage: self.storage.fork().await?,
+            transaction_manager: self.transaction_manager.clone(),
+            transaction_id,
+            snapshot,
+            params: Vec::new(),
+            columns,
+            cursor,
+        })
+    }
+
     pub async fn execute_readonly(
         &self,
         sql: &str,
@@ -1772,6 +1886,7 @@ impl InMemoryDB {
             persistence: None, // Forks are ephemeral
             autovac_threshold: self.autovac_threshold,
             query_metrics: Arc::new(Mutex::new(QueryMetricsStore::new())),
+            databases: Arc::new(StdMutex::new(HashMap::new())),
         })
     }
 }
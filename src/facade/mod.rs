@@ -1,3 +1,4 @@
 pub mod database;
+mod row_source;
 
 pub use database::InMemoryDB;
@@ -0,0 +1,105 @@
+use crate::core::{Column, DbError, Result, Row, Snapshot, Value};
+use crate::executor::ExecutionContext;
+use crate::executor::query::QueryExecutor;
+use crate::planner::LogicalPlan;
+use crate::result::RowStream;
+use crate::storage::InMemoryStorage;
+use crate::transaction::{TransactionId, TransactionManager};
+use futures::{StreamExt, TryStreamExt, stream};
+use std::sync::Arc;
+
+const ROW_STREAM_BATCH_SIZE: usize = 1024;
+
+/// A SELECT detached from the database lock: it reads a copy-on-write fork of
+/// the storage through the snapshot taken when the stream was opened.
+pub(crate) struct QueryRowSource {
+    pub(crate) executor: QueryExecutor,
+    pub(crate) storage: InMemoryStorage,
+    pub(crate) transaction_manager: Arc<TransactionManager>,
+    pub(crate) transaction_id: Option<TransactionId>,
+    pub(crate) snapshot: Snapshot,
+    pub(crate) params: Vec<Value>,
+    pub(crate) columns: Vec<Column>,
+    pub(crate) cursor: RowSourceCursor,
+}
+
+pub(crate) enum RowSourceCursor {
+    /// Filters/projections over a full scan, evaluated one batch of row ids at a time.
+    Scan {
+        plan: LogicalPlan,
+        table_name: String,
+        next_id: Option<usize>,
+    },
+    /// Any other plan is executed once and its rows handed out afterwards.
+    Materialize {
+        plan: LogicalPlan,
+        done: bool,
+    },
+    Buffered(Option<Vec<Row>>),
+}
+
+impl QueryRowSource {
+    pub(crate) fn into_stream(self) -> RowStream {
+        let columns = self.columns.clone();
+        let rows = stream::try_unfold(self, |mut source| async move {
+            let batch = source.next_batch().await?;
+            Ok::<_, DbError>(batch.map(|rows| (stream::iter(rows.into_iter().map(Ok)), source)))
+        })
+        .try_flatten();
+        RowStream::new(columns, rows.boxed())
+    }
+
+    async fn next_batch(&mut self) -> Result<Option<Vec<Row>>> {
+        let Self {
+            executor,
+            storage,
+            transaction_manager,
+            transaction_id,
+            snapshot,
+            params,
+            cursor,
+            ..
+        } = self;
+        let ctx = match transaction_id {
+            Some(txn_id) => ExecutionContext::with_transaction(
+                storage,
+                transaction_manager,
+                *txn_id,
+                None,
+                snapshot.clone(),
+            ),
+            None => ExecutionContext::new(storage, transaction_manager, None, snapshot.clone()),
+        }
+        .with_params(params.clone());
+
+        match cursor {
+            RowSourceCursor::Scan {
+                plan,
+                table_name,
+                next_id,
+            } => {
+                while let Some(start_id) = *next_id {
+                    let (rows, resume_at) = {
+                        let table = storage.get_table(table_name)?;
+                        let table = table.read().await;
+                        table.scan_batch(snapshot, start_id, ROW_STREAM_BATCH_SIZE)
+                    };
+                    *next_id = resume_at;
+                    let rows = executor.execute_plan_on_batch(plan, rows, &ctx).await?;
+                    if !rows.is_empty() {
+                        return Ok(Some(rows));
+                    }
+                }
+                Ok(None)
+            }
+            RowSourceCursor::Materialize { plan, done } => {
+                if *done {
+                    return Ok(None);
+                }
+                *done = true;
+                Ok(Some(executor.execute_plan(plan, &ctx).await?))
+            }
+            RowSourceCursor::Buffered(rows) => Ok(rows.take()),
+        }
+    }
+}
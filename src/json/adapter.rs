@@ -379,6 +379,9 @@ impl JsonStorageAdapter {
                 JsonValue::Array(arr.iter().map(|v| self.sql_value_to_json(v)).collect())
             }
             crate::core::Value::Json(j) => j.clone(),
+            crate::core::Value::Interval(micros) => {
+                JsonValue::String(crate::core::value::interval_text(*micros))
+            }
         }
     }
 }
@@ -450,4 +453,17 @@ mod tests {
         assert!(adapter.create("user-profile", doc).await.is_err());
         assert!(adapter.create("123users", doc).await.is_err());
     }
+
+    #[test]
+    fn test_interval_json_round_trip() {
+        let adapter = create_test_adapter();
+        let value = crate::core::Value::Interval(-93_784_000_001);
+
+        let json = adapter.sql_value_to_json(&value);
+        assert_eq!(json, JsonValue::String("-93784000001 microseconds".into()));
+        assert_eq!(
+            JsonToValueConverter::convert(&json, &crate::core::DataType::Interval).unwrap(),
+            value
+        );
+    }
 }
@@ -4,6 +4,7 @@
 //! for flexible and maintainable SQL generation.
 
 use super::error::{JsonError, JsonResult};
+use crate::core::value::{interval_text, parse_interval};
 use crate::core::{DataType, Value};
 use crate::storage::TableSchema;
 use serde_json::Value as JsonValue;
@@ -34,7 +35,9 @@ impl JsonToValueConverter {
                 .ok_or_else(|| JsonError::TypeMismatch(format!("Cannot convert {} to FLOAT", n))),
 
             // String
-            (JsonValue::String(s), DataType::Text) => Ok(Value::Text(s.clone())),
+            (JsonValue::String(s), DataType::Text | DataType::Varchar(_)) => {
+                Ok(Value::Text(s.clone()))
+            }
 
             // Timestamp
             (JsonValue::String(s), DataType::Timestamp) => {
@@ -57,6 +60,11 @@ impl JsonToValueConverter {
                 Ok(Value::Uuid(u))
             }
 
+            // Interval
+            (JsonValue::String(s), DataType::Interval) => parse_interval(s)
+                .map(Value::Interval)
+                .map_err(|e| JsonError::TypeMismatch(format!("Invalid Interval: {}", e))),
+
             // JSON
             (v, DataType::Json) => Ok(Value::Json(v.clone())),
 
@@ -370,6 +378,7 @@ fn format_value_for_sql(value: &Value) -> String {
             format!("ARRAY[{}]", elements.join(", "))
         }
         Value::Json(j) => format!("'{}'", escape_sql_string(&j.to_string())),
+        Value::Interval(micros) => format!("INTERVAL '{}'", interval_text(*micros)),
     }
 }
 
@@ -378,7 +387,8 @@ fn escape_sql_string(s: &str) -> String {
     s.replace('\'', "''")
 }
 
-fn quote_ident(ident: &str) -> String {
+/// Quote an identifier so spaces, reserved words and quotes stay part of the name
+pub(crate) fn quote_ident(ident: &str) -> String {
     let escaped = ident.replace('"', "\"\"");
     format!("\"{}\"", escaped)
 }
@@ -19,4 +19,5 @@ mod schema_inference;
 mod validator;
 
 pub use adapter::JsonStorageAdapter;
+pub(crate) use converter::quote_ident;
 pub use error::{JsonError, JsonResult};
@@ -24,6 +24,8 @@ mod plugins;
 pub mod result;
 pub mod server;
 pub mod storage;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod transaction;
 pub use paste;
 
@@ -35,9 +37,13 @@ pub use model_lang::{
     FieldDecl, FieldType, ModelProgram, StructDecl, parse_and_materialize_models,
 };
 pub use persist::app::{
-    ManagedConflictKind, ManagedPersistVec, ManagedPersistVecStats, PersistApp,
-    PersistAppAutoPolicy, PersistAppPolicy, PersistCollection, PersistIndexedCollection,
-    PersistReplicationMode, PersistReplicationPolicy, classify_managed_conflict,
+    ManagedConflictKind, ManagedPersistTx, ManagedPersistVec, ManagedPersistVecStats,
+    ManagedSnapshotScheduler, PersistApp, PersistAppAutoPolicy, PersistAppMetrics,
+    PersistAppPolicy, PersistChangeEvent, PersistChangeKind, PersistCollection,
+    PersistCollectionPolicy, PersistImportPolicy, PersistIndexedCollection, PersistIntegrityIssue,
+    PersistIntegrityIssueKind, PersistIntegrityReport, PersistRecoveryReport,
+    PersistReplicationMode, PersistReplicationOverflow, PersistReplicationPolicy,
+    PersistSnapshotEncryption, classify_managed_conflict, spawn_managed_snapshot_scheduler,
 };
 pub use persist::cluster::{
     InMemoryRuntimeForwarder, RuntimeClusterApplyResult, RuntimeClusterForwarder,
@@ -52,25 +58,32 @@ pub use persist::runtime::{
     RuntimeCompatReport, RuntimeConsistencyMode, RuntimeDeterminismPolicy,
     RuntimeDeterministicContext, RuntimeDurabilityMode, RuntimeEntityKey,
     RuntimeEnvelopeApplyResult, RuntimeIdempotencyReceipt, RuntimeJournalOp, RuntimeJournalRecord,
-    RuntimeLifecyclePolicy, RuntimeLifecycleReport, RuntimeOperationalPolicy, RuntimeOutboxRecord,
-    RuntimeOutboxStatus, RuntimePaths, RuntimePayloadFieldContract, RuntimePayloadType,
-    RuntimeProjectionContract, RuntimeProjectionField, RuntimeProjectionRow,
-    RuntimeReplicationMode, RuntimeReplicationPolicy, RuntimeRetryPolicy, RuntimeSideEffectSpec,
-    RuntimeSloMetrics, RuntimeSnapshotFile, RuntimeSnapshotPolicy, RuntimeSnapshotWorker,
-    RuntimeStats, RuntimeStoredEntity, runtime_snapshot_compat_check,
-    spawn_runtime_snapshot_worker,
+    RuntimeLifecyclePolicy, RuntimeLifecycleReport, RuntimeModelRegistrar,
+    RuntimeModelRegistration, RuntimeOperationalPolicy, RuntimeOutboxRecord, RuntimeOutboxStatus,
+    RuntimePaths, RuntimePayloadFieldContract, RuntimePayloadType, RuntimeProjectionContract,
+    RuntimeProjectionField, RuntimeProjectionRow, RuntimeReplicationMode, RuntimeReplicationPolicy,
+    RuntimeRetryPolicy, RuntimeSideEffectSpec, RuntimeSloMetrics, RuntimeSnapshotFile,
+    RuntimeSnapshotPolicy, RuntimeSnapshotWorker, RuntimeStats, RuntimeStoredEntity,
+    runtime_snapshot_compat_check, spawn_runtime_snapshot_worker,
 };
 pub use persist::{
     FunctionDescriptor, HeteroPersistVec, HeteroPersistVecSnapshot, HeteroTypeSnapshot,
-    InvokeOutcome, InvokeStatus, ObjectDescriptor, PERSIST_SCHEMA_REGISTRY_TABLE,
-    PersistCommandContract, PersistCommandFieldContract, PersistCommandModel, PersistEntity,
-    PersistEntityFactory, PersistMetadata, PersistMigrationPlan, PersistMigrationStep,
-    PersistModelExt, PersistPatchContract, PersistSession, PersistState, PersistValue, PersistVec,
+    InvokeOutcome, InvokeStatus, ObjectDescriptor, PERSIST_REDACTED_VALUE,
+    PERSIST_SCHEMA_REGISTRY_TABLE, PERSIST_SNAPSHOT_FORMAT_VERSION, PersistCommandContract,
+    PersistCommandFieldContract, PersistCommandModel, PersistCommandRateLimiter,
+    PersistCommandReservation, PersistEntity, PersistEntityFactory, PersistFlatten,
+    PersistIdStrategy, PersistMetadata, PersistMigrationPlan, PersistMigrationStep,
+    PersistModelExt, PersistPatchContract, PersistPendingMigration, PersistSession, PersistState,
+    PersistStateMergeFn, PersistTableDefinition, PersistValidate, PersistValue, PersistVec,
     PersistVecSnapshot, RestoreConflictPolicy, SnapshotMode, StateMigrationFn,
-    default_schema_version,
+    default_schema_version, is_command_input_error, is_command_rate_limited_error,
+    lock_entity_commands, new_persist_id,
+};
+pub use result::{CsvImportOptions, QueryResult, RowStream};
+pub use rustmemodb_derive::{
+    PersistEnumInt, PersistEnumText, PersistFlatten, PersistModel, command, computed, persistent,
+    persistent_impl,
 };
-pub use result::QueryResult;
-pub use rustmemodb_derive::{PersistModel, command, persistent, persistent_impl};
 
 // Re-export persistence types
 pub use storage::{DurabilityMode, PersistenceManager, WalEntry};
@@ -80,7 +93,7 @@ pub use json::{JsonError, JsonResult, JsonStorageAdapter};
 
 // Re-export connection API
 pub use connection::{
-    Connection,
+    Connection, SESSION_VARIABLES,
     auth::{AuthManager, Permission, User},
     config::ConnectionConfig,
     pool::{ConnectionPool, PoolGuard, PoolStats},
@@ -265,6 +278,110 @@ impl Client {
         self.pool.get_connection().await
     }
 
+    /// Import CSV data into an existing table
+    ///
+    /// The first record is a header naming the target columns. Fields are
+    /// coerced to each column's type and all rows are inserted in a single
+    /// transaction; errors report the CSV row number (the header is row 1).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rustmemodb::{Client, CsvImportOptions};
+    /// # tokio_test::block_on(async {
+    /// let client = Client::connect_local("admin", "adminpass").await?;
+    /// client.execute("CREATE TABLE people (id INTEGER, name TEXT)").await?;
+    ///
+    /// let csv = "id,name\n1,Alice\n2,\"Smith, Bob\"\n";
+    /// let imported = client
+    ///     .import_csv("people", csv.as_bytes(), CsvImportOptions::default())
+    ///     .await?;
+    /// assert_eq!(imported, 2);
+    /// # Ok::<(), rustmemodb::core::DbError>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn import_csv<R: std::io::Read>(
+        &self,
+        table: &str,
+        mut reader: R,
+        options: CsvImportOptions,
+    ) -> Result<usize> {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .map_err(|e| DbError::IoError(e.to_string()))?;
+        let input = input.strip_prefix('\u{feff}').unwrap_or(&input);
+
+        let mut records = result::csv::parse_csv(input, options.delimiter)?.into_iter();
+        let Some(header) = records.next() else {
+            return Ok(0);
+        };
+
+        let quoted_table = json::quote_ident(table);
+        let schema = self
+            .query(&format!("SELECT * FROM {} LIMIT 0", quoted_table))
+            .await?;
+        let mut targets = Vec::with_capacity(header.fields.len());
+        for field in &header.fields {
+            let name = field.value.trim();
+            let column = schema
+                .columns()
+                .iter()
+                .find(|c| c.name.rsplit('.').next() == Some(name))
+                .ok_or_else(|| DbError::ColumnNotFound(name.to_string(), table.to_string()))?;
+            targets.push((name.to_string(), column.data_type.clone()));
+        }
+
+        let mut rows = Vec::new();
+        for record in records {
+            if record.fields.len() != targets.len() {
+                return Err(DbError::ParseError(format!(
+                    "CSV row {}: expected {} fields, got {}",
+                    record.row,
+                    targets.len(),
+                    record.fields.len()
+                )));
+            }
+            let mut literals = Vec::with_capacity(targets.len());
+            for (field, (name, data_type)) in record.fields.iter().zip(&targets) {
+                let value = result::csv::coerce_csv_field(field, data_type, options.empty_as_null)
+                    .map_err(|e| {
+                        DbError::TypeMismatch(format!(
+                            "CSV row {}, column '{}': {}",
+                            record.row, name, e
+                        ))
+                    })?;
+                literals.push(persist::value_to_sql_literal(&value));
+            }
+            rows.push(format!("({})", literals.join(", ")));
+        }
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let column_list = targets
+            .iter()
+            .map(|(name, _)| json::quote_ident(name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut conn = self.get_connection().await?;
+        conn.begin().await?;
+        for batch in rows.chunks(options.batch_size.max(1)) {
+            // A failed statement already rolls the connection's transaction back
+            conn.execute(&format!(
+                "INSERT INTO {} ({}) VALUES {}",
+                quoted_table,
+                column_list,
+                batch.join(", ")
+            ))
+            .await?;
+        }
+        conn.commit().await?;
+
+        Ok(rows.len())
+    }
+
     /// Get pool statistics
     ///
     /// # Examples
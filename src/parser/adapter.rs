@@ -396,16 +396,7 @@ impl SqlParserAdapter {
                 expr,
             } => match &**expr {
                 Expr::Value(v) => match &v.value {
-                    SqlValue::Number(n, _) => {
-                        let val = crate::core::Value::parse_number(n)?;
-                        match val {
-                            crate::core::Value::Integer(i) => Ok(crate::core::Value::Integer(-i)),
-                            crate::core::Value::Float(f) => Ok(crate::core::Value::Float(-f)),
-                            _ => Err(DbError::TypeMismatch(
-                                "Invalid default numeric literal".into(),
-                            )),
-                        }
-                    }
+                    SqlValue::Number(n, _) => crate::core::Value::parse_number(&format!("-{}", n)),
                     _ => Err(DbError::UnsupportedOperation(
                         "Only numeric literals supported in DEFAULT".into(),
                     )),
@@ -424,21 +415,27 @@ impl SqlParserAdapter {
         match dt {
             sql_ast::DataType::Int(_)
             | sql_ast::DataType::Integer(_)
+            | sql_ast::DataType::SmallInt(_)
             | sql_ast::DataType::BigInt(_) => Ok(DataType::Integer),
 
             sql_ast::DataType::Float(_)
             | sql_ast::DataType::Double(_)
             | sql_ast::DataType::Real => Ok(DataType::Float),
 
-            sql_ast::DataType::Text
-            | sql_ast::DataType::Varchar(_)
-            | sql_ast::DataType::Char(_)
-            | sql_ast::DataType::String(_) => Ok(DataType::Text),
+            sql_ast::DataType::Text | sql_ast::DataType::Char(_) | sql_ast::DataType::String(_) => {
+                Ok(DataType::Text)
+            }
+            sql_ast::DataType::Varchar(Some(sql_ast::CharacterLength::IntegerLength {
+                length,
+                ..
+            })) => Ok(DataType::Varchar(*length as usize)),
+            sql_ast::DataType::Varchar(_) => Ok(DataType::Text),
 
             sql_ast::DataType::Boolean | sql_ast::DataType::Bool => Ok(DataType::Boolean),
 
             sql_ast::DataType::Timestamp(_, _) => Ok(DataType::Timestamp),
             sql_ast::DataType::Date => Ok(DataType::Date),
+            sql_ast::DataType::Interval { .. } => Ok(DataType::Interval),
             sql_ast::DataType::Uuid => Ok(DataType::Uuid),
 
             sql_ast::DataType::Array(elem_def) => {
@@ -465,6 +462,15 @@ impl SqlParserAdapter {
             }
 
             sql_ast::DataType::JSON | sql_ast::DataType::JSONB => Ok(DataType::Json),
+            sql_ast::DataType::Enum(members, _) => Ok(DataType::Enum(
+                members
+                    .iter()
+                    .map(|member| match member {
+                        sql_ast::EnumMember::Name(name)
+                        | sql_ast::EnumMember::NamedValue(name, _) => name.clone(),
+                    })
+                    .collect(),
+            )),
 
             _ => Err(DbError::TypeMismatch(format!(
                 "Unsupported data type: {:?}",
@@ -337,6 +337,13 @@ pub enum Expr {
         obj: Box<Expr>,
         index: Box<Expr>,
     },
+
+    /// Comparison against any array element (x = ANY(arr))
+    AnyOp {
+        left: Box<Expr>,
+        op: BinaryOp,
+        right: Box<Expr>,
+    },
 }
 
 /// Binary operators
@@ -364,6 +371,9 @@ pub enum BinaryOp {
     // JSON
     Arrow,     // ->
     LongArrow, // ->>
+
+    // Array
+    Contains, // @>
 }
 
 /// Unary operators
@@ -492,6 +502,8 @@ impl fmt::Display for Expr {
             }
 
             Expr::ArrayIndex { obj, index } => write!(f, "{}[{}]", obj, index),
+
+            Expr::AnyOp { left, op, right } => write!(f, "{} {} ANY({})", left, op, right),
         }
     }
 }
@@ -528,6 +540,8 @@ impl fmt::Display for BinaryOp {
             BinaryOp::Arrow => write!(f, "->"),
 
             BinaryOp::LongArrow => write!(f, "->>"),
+
+            BinaryOp::Contains => write!(f, "@>"),
         }
     }
 }
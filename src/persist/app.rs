@@ -1,19 +1,30 @@
-use crate::core::{DbError, Result};
+use crate::core::{DbError, Result, Value};
 use crate::facade::InMemoryDB;
 use crate::persist::{
-    PersistCommandContract, PersistCommandModel, PersistEntity, PersistPatchContract,
-    PersistSession, RestoreConflictPolicy, SnapshotMode,
+    PERSIST_SNAPSHOT_FORMAT_VERSION, PersistCommandContract, PersistCommandModel, PersistEntity,
+    PersistEntityFactory, PersistIdStrategy, PersistPatchContract, PersistPendingMigration,
+    PersistSession, PersistState, PersistTableDefinition, RestoreConflictPolicy, SnapshotMode,
+    sql_escape_string,
 };
 use crate::transaction::TransactionId;
 use chrono::Utc;
 use log::warn;
 use serde::{Serialize, de::DeserializeOwned};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex as StdMutex, OnceLock, Weak};
 use tokio::fs;
+use tokio::sync::{Mutex, Notify, broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration as TokioDuration, sleep};
+
+const CHANGE_EVENTS_CAPACITY: usize = 256;
+const SNAPSHOT_DURATION_WINDOW: usize = 16;
 
 pub trait PersistCollection: Sized + Send + Sync + 'static {
     type Snapshot: Serialize + DeserializeOwned + Send + Sync + 'static;
@@ -21,6 +32,10 @@ pub trait PersistCollection: Sized + Send + Sync + 'static {
     fn new_collection(name: impl Into<String>) -> Self;
     fn len(&self) -> usize;
     fn snapshot(&self, mode: SnapshotMode) -> Self::Snapshot;
+    /// Snapshot with sensitive fields masked; defaults to the full snapshot.
+    fn redacted_snapshot(&self, mode: SnapshotMode) -> Self::Snapshot {
+        self.snapshot(mode)
+    }
     fn save_all<'a>(
         &'a mut self,
         session: &'a PersistSession,
@@ -31,6 +46,17 @@ pub trait PersistCollection: Sized + Send + Sync + 'static {
         session: &'a PersistSession,
         conflict_policy: RestoreConflictPolicy,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+    fn ensure_snapshot_compatible(&self, _snapshot: &Self::Snapshot) -> Result<()> {
+        Ok(())
+    }
+    /// Highest entity version in the collection, 0 when empty.
+    fn max_version(&self) -> i64 {
+        0
+    }
+    /// Tables this collection writes to, used by `PersistApp::create_all_tables`.
+    fn table_definitions(&self) -> Vec<PersistTableDefinition> {
+        Vec::new()
+    }
 }
 
 pub trait PersistIndexedCollection: PersistCollection {
@@ -49,6 +75,19 @@ pub enum PersistReplicationMode {
     AsyncBestEffort,
 }
 
+/// What `AsyncBestEffort` replication does when the queue depth set by
+/// `PersistApp::with_replication_queue` is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PersistReplicationOverflow {
+    /// The writer waits until the replication worker frees a slot.
+    #[default]
+    Block,
+    /// The oldest queued snapshot is discarded and counted in
+    /// `replication_dropped`. Replicas skip intermediate snapshots and may lag
+    /// further behind the primary, but the newest snapshot is always queued.
+    DropOldest,
+}
+
 #[derive(Debug, Clone)]
 pub struct PersistReplicationPolicy {
     pub mode: PersistReplicationMode,
@@ -79,6 +118,186 @@ impl Default for PersistAppPolicy {
     }
 }
 
+const ENCRYPTED_SNAPSHOT_MAGIC: &[u8; 8] = b"RMDBENC1";
+
+/// AES-256-GCM keys for snapshot encryption at rest.
+///
+/// Snapshots are written with the active key and carry its id in a plaintext
+/// header, so files written under a retired key can still be read after rotation.
+#[derive(Clone)]
+pub struct PersistSnapshotEncryption {
+    active_key_id: String,
+    keys: HashMap<String, [u8; 32]>,
+}
+
+impl PersistSnapshotEncryption {
+    pub fn new(key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        let key_id = key_id.into();
+        let mut keys = HashMap::new();
+        keys.insert(key_id.clone(), key);
+        Self {
+            active_key_id: key_id,
+            keys,
+        }
+    }
+
+    /// Keeps a previous key around for reading snapshots written before rotation.
+    pub fn with_retired_key(mut self, key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        self.keys.entry(key_id.into()).or_insert(key);
+        self
+    }
+
+    pub fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    /// Returns the key id of an encrypted snapshot, or `None` for plaintext bytes.
+    pub fn key_id_of(bytes: &[u8]) -> Option<String> {
+        let parts = split_encrypted_snapshot(bytes).ok()??;
+        Some(parts.key_id.to_string())
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+        use ring::rand::{SecureRandom, SystemRandom};
+
+        let key_id = self.active_key_id.as_bytes();
+        let key_id_len = u16::try_from(key_id.len()).map_err(|_| {
+            DbError::ExecutionError("Snapshot encryption key id is too long".to_string())
+        })?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| {
+            DbError::ExecutionError("Failed to generate snapshot encryption nonce".to_string())
+        })?;
+
+        let mut out = Vec::with_capacity(
+            ENCRYPTED_SNAPSHOT_MAGIC.len() + 2 + key_id.len() + NONCE_LEN + plaintext.len() + 16,
+        );
+        out.extend_from_slice(ENCRYPTED_SNAPSHOT_MAGIC);
+        out.extend_from_slice(&key_id_len.to_be_bytes());
+        out.extend_from_slice(key_id);
+        let header_len = out.len();
+        out.extend_from_slice(&nonce);
+
+        let mut sealed = plaintext.to_vec();
+        let key = LessSafeKey::new(
+            UnboundKey::new(&AES_256_GCM, &self.keys[&self.active_key_id]).map_err(|_| {
+                DbError::ExecutionError("Invalid snapshot encryption key".to_string())
+            })?,
+        );
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&out[..header_len]),
+            &mut sealed,
+        )
+        .map_err(|_| DbError::ExecutionError("Failed to encrypt snapshot".to_string()))?;
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    fn decrypt(&self, parts: &EncryptedSnapshotParts<'_>) -> Result<Vec<u8>> {
+        use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+
+        let EncryptedSnapshotParts {
+            key_id,
+            header,
+            body,
+        } = *parts;
+
+        let key = self.keys.get(key_id).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Snapshot is encrypted with unknown key id '{}'",
+                key_id
+            ))
+        })?;
+        if body.len() < NONCE_LEN {
+            return Err(DbError::ExecutionError(
+                "Encrypted snapshot is truncated".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| {
+            DbError::ExecutionError("Encrypted snapshot has an invalid nonce".to_string())
+        })?;
+        let key =
+            LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).map_err(|_| {
+                DbError::ExecutionError("Invalid snapshot encryption key".to_string())
+            })?);
+        let mut opened = ciphertext.to_vec();
+        let plaintext_len = key
+            .open_in_place(nonce, Aad::from(header), &mut opened)
+            .map_err(|_| {
+                DbError::ExecutionError(format!(
+                    "Failed to decrypt snapshot with key id '{}': wrong key or corrupted data",
+                    key_id
+                ))
+            })?
+            .len();
+        opened.truncate(plaintext_len);
+        Ok(opened)
+    }
+}
+
+impl fmt::Debug for PersistSnapshotEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut key_ids = self.keys.keys().collect::<Vec<_>>();
+        key_ids.sort();
+        f.debug_struct("PersistSnapshotEncryption")
+            .field("active_key_id", &self.active_key_id)
+            .field("key_ids", &key_ids)
+            .finish()
+    }
+}
+
+struct EncryptedSnapshotParts<'a> {
+    key_id: &'a str,
+    header: &'a [u8],
+    body: &'a [u8],
+}
+
+// Splits `magic | key_id_len (u16 BE) | key_id | nonce | ciphertext` into the
+// key id, the authenticated header and the remaining body. Plaintext snapshots
+// (no magic) yield `None`.
+fn split_encrypted_snapshot(bytes: &[u8]) -> Result<Option<EncryptedSnapshotParts<'_>>> {
+    let Some(rest) = bytes.strip_prefix(ENCRYPTED_SNAPSHOT_MAGIC.as_slice()) else {
+        return Ok(None);
+    };
+    let truncated = || DbError::ExecutionError("Encrypted snapshot is truncated".to_string());
+    let len_bytes = rest.get(..2).ok_or_else(truncated)?;
+    let key_id_len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+    let header_len = ENCRYPTED_SNAPSHOT_MAGIC.len() + 2 + key_id_len;
+    let header = bytes.get(..header_len).ok_or_else(truncated)?;
+    let key_id =
+        std::str::from_utf8(&header[ENCRYPTED_SNAPSHOT_MAGIC.len() + 2..]).map_err(|_| {
+            DbError::ExecutionError("Encrypted snapshot has an invalid key id".to_string())
+        })?;
+    Ok(Some(EncryptedSnapshotParts {
+        key_id,
+        header,
+        body: &bytes[header_len..],
+    }))
+}
+
+/// Per-collection overrides for `PersistApp::open_vec_with_policy`.
+#[derive(Debug, Clone, Default)]
+pub struct PersistCollectionPolicy {
+    /// Replaces the app's `snapshot_every_ops` for this collection.
+    pub snapshot_every_ops: Option<usize>,
+    /// Never snapshot or replicate the collection and ignore any snapshot on
+    /// disk. Its contents do not survive a restart and must be rebuilt.
+    pub disable_snapshots: bool,
+}
+
+impl PersistCollectionPolicy {
+    /// Policy for derived read models that are rebuilt from their source.
+    pub fn ephemeral() -> Self {
+        Self {
+            disable_snapshots: true,
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PersistAppAutoPolicy {
     pub snapshot_every_ops: usize,
@@ -103,11 +322,19 @@ impl From<PersistAppAutoPolicy> for PersistAppPolicy {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PersistImportPolicy {
+    #[default]
+    FailFast,
+    SkipInvalid,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ManagedConflictKind {
     OptimisticLock,
     WriteWrite,
     UniqueConstraint,
+    VersionConflict,
 }
 
 impl fmt::Display for ManagedConflictKind {
@@ -116,11 +343,53 @@ impl fmt::Display for ManagedConflictKind {
             Self::OptimisticLock => "optimistic_lock",
             Self::WriteWrite => "write_write",
             Self::UniqueConstraint => "unique_constraint",
+            Self::VersionConflict => "version_conflict",
         };
         write!(f, "{label}")
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistIntegrityIssueKind {
+    MissingRow,
+    StaleRow,
+    OrphanRow,
+    DuplicateId,
+    MetadataAnomaly,
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistIntegrityIssue {
+    pub kind: PersistIntegrityIssueKind,
+    pub persist_id: String,
+    pub detail: String,
+    pub repaired: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersistIntegrityReport {
+    pub vec_name: String,
+    pub checked_items: usize,
+    pub issues: Vec<PersistIntegrityIssue>,
+}
+
+impl PersistIntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn issues_of_kind(&self, kind: PersistIntegrityIssueKind) -> Vec<&PersistIntegrityIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.kind == kind)
+            .collect()
+    }
+
+    pub fn unrepaired_count(&self) -> usize {
+        self.issues.iter().filter(|issue| !issue.repaired).count()
+    }
+}
+
 pub fn classify_managed_conflict(err: &DbError) -> Option<ManagedConflictKind> {
     match err {
         DbError::ExecutionError(message) => {
@@ -131,6 +400,9 @@ pub fn classify_managed_conflict(err: &DbError) -> Option<ManagedConflictKind> {
             if lower.contains("write-write conflict detected") {
                 return Some(ManagedConflictKind::WriteWrite);
             }
+            if lower.contains("version conflict") {
+                return Some(ManagedConflictKind::VersionConflict);
+            }
             None
         }
         DbError::ConstraintViolation(message) => {
@@ -144,6 +416,44 @@ pub fn classify_managed_conflict(err: &DbError) -> Option<ManagedConflictKind> {
     }
 }
 
+/// Rejects `candidate` when one of its `unique_index_fields` values is held by
+/// another item of `existing` or `pending`. NULL values never conflict.
+fn ensure_unique_index_fields<T: PersistEntity>(
+    existing: &[T],
+    pending: &[T],
+    candidate: &T,
+) -> Result<()> {
+    let unique_fields = candidate.unique_index_fields();
+    if unique_fields.is_empty() {
+        return Ok(());
+    }
+
+    let candidate_state = candidate.state();
+    for field in unique_fields {
+        let Some(value) = candidate_state.fields.get(field) else {
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+
+        let duplicate = existing.iter().chain(pending).any(|item| {
+            item.persist_id() != candidate.persist_id()
+                && item.state().fields.get(field) == Some(value)
+        });
+        if duplicate {
+            return Err(DbError::ConstraintViolation(format!(
+                "Unique constraint violation: {}.{} value {} already exists",
+                candidate.type_name(),
+                field,
+                value
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 fn map_managed_conflict_error(operation: &str, err: DbError) -> DbError {
     let Some(kind) = classify_managed_conflict(&err) else {
         return err;
@@ -162,8 +472,18 @@ fn map_managed_conflict_error(operation: &str, err: DbError) -> DbError {
 #[derive(Clone)]
 pub struct PersistApp {
     session: PersistSession,
-    root: PathBuf,
+    root: Option<PathBuf>,
     policy: PersistAppPolicy,
+    snapshot_interval: Option<PersistSnapshotInterval>,
+    replication_queue: PersistReplicationQueueLimits,
+    encryption: Option<PersistSnapshotEncryption>,
+    lazy_table_creation: bool,
+    id_strategy: PersistIdStrategy,
+    tenant_id: Option<String>,
+    tenant_sessions: Arc<StdMutex<HashMap<String, PersistSession>>>,
+    collection_metrics: Arc<StdMutex<Vec<Weak<ManagedMetricsCell>>>>,
+    recovery_reports: Arc<StdMutex<Vec<PersistRecoveryReport>>>,
+    table_definitions: Arc<StdMutex<BTreeMap<String, PersistTableDefinition>>>,
 }
 
 impl PersistApp {
@@ -190,32 +510,226 @@ impl PersistApp {
 
         Ok(Self {
             session: PersistSession::new(InMemoryDB::new()),
-            root,
+            root: Some(root),
+            policy,
+            snapshot_interval: None,
+            replication_queue: PersistReplicationQueueLimits::default(),
+            encryption: None,
+            lazy_table_creation: true,
+            id_strategy: PersistIdStrategy::default(),
+            tenant_id: None,
+            tenant_sessions: Arc::default(),
+            collection_metrics: Arc::default(),
+            recovery_reports: Arc::default(),
+            table_definitions: Arc::default(),
+        })
+    }
+
+    pub fn open_in_memory() -> Self {
+        Self {
+            session: PersistSession::new(InMemoryDB::new()),
+            root: None,
+            policy: PersistAppPolicy::default(),
+            snapshot_interval: None,
+            replication_queue: PersistReplicationQueueLimits::default(),
+            encryption: None,
+            lazy_table_creation: true,
+            id_strategy: PersistIdStrategy::default(),
+            tenant_id: None,
+            tenant_sessions: Arc::default(),
+            collection_metrics: Arc::default(),
+            recovery_reports: Arc::default(),
+            table_definitions: Arc::default(),
+        }
+    }
+
+    pub fn is_in_memory(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns an app scoped to `tenant_id`.
+    ///
+    /// Each tenant gets its own database (so tables never share rows across
+    /// tenants) and its snapshots live under `<root>/tenants/<tenant_id>/`,
+    /// mirrored under the same sub-path of every replica root. Repeated calls
+    /// with the same id share the tenant's database.
+    pub fn tenant(&self, tenant_id: &str) -> Result<PersistApp> {
+        if self.tenant_id.is_some() {
+            return Err(DbError::ExecutionError(
+                "Tenant-scoped PersistApp cannot be scoped to another tenant".to_string(),
+            ));
+        }
+        if tenant_id.is_empty()
+            || !tenant_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(DbError::ExecutionError(format!(
+                "Invalid tenant id '{}': use ASCII letters, digits, '-' or '_'",
+                tenant_id
+            )));
+        }
+
+        let session = self
+            .tenant_sessions
+            .lock()
+            .map_err(|_| DbError::LockError("tenant session registry poisoned".to_string()))?
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| PersistSession::new(InMemoryDB::new()))
+            .clone();
+
+        let tenant_path = Path::new("tenants").join(tenant_id);
+        let mut policy = self.policy.clone();
+        policy.replication.replica_roots = policy
+            .replication
+            .replica_roots
+            .iter()
+            .map(|root| root.join(&tenant_path))
+            .collect();
+
+        Ok(PersistApp {
+            session,
+            root: self.root.as_ref().map(|root| root.join(&tenant_path)),
             policy,
+            snapshot_interval: self.snapshot_interval,
+            replication_queue: self.replication_queue,
+            encryption: self.encryption.clone(),
+            lazy_table_creation: self.lazy_table_creation,
+            id_strategy: self.id_strategy.clone(),
+            tenant_id: Some(tenant_id.to_string()),
+            tenant_sessions: Arc::default(),
+            collection_metrics: Arc::default(),
+            recovery_reports: Arc::default(),
+            table_definitions: Arc::default(),
         })
     }
 
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
     pub fn policy(&self) -> &PersistAppPolicy {
         &self.policy
     }
 
+    /// Enables time-triggered snapshots for collections opened afterwards:
+    /// `spawn_managed_snapshot_scheduler` snapshots each one every
+    /// `interval_ms` plus a random delay of up to `jitter_ms`.
+    pub fn with_snapshot_interval(mut self, interval_ms: u64, jitter_ms: u64) -> Result<Self> {
+        if interval_ms == 0 {
+            return Err(DbError::ExecutionError(
+                "Snapshot interval must be greater than zero".to_string(),
+            ));
+        }
+        self.snapshot_interval = Some(PersistSnapshotInterval {
+            interval_ms,
+            jitter_ms,
+        });
+        Ok(self)
+    }
+
+    pub fn snapshot_interval_ms(&self) -> Option<u64> {
+        self.snapshot_interval.map(|interval| interval.interval_ms)
+    }
+
+    /// Bounds the `AsyncBestEffort` replication queue of collections opened
+    /// afterwards: at most `max_queue_depth` snapshots wait for the worker
+    /// before `overflow` applies. Defaults to 64 and `Block`.
+    pub fn with_replication_queue(
+        mut self,
+        max_queue_depth: usize,
+        overflow: PersistReplicationOverflow,
+    ) -> Result<Self> {
+        if max_queue_depth == 0 {
+            return Err(DbError::ExecutionError(
+                "Replication queue depth must be greater than zero".to_string(),
+            ));
+        }
+        self.replication_queue = PersistReplicationQueueLimits {
+            max_queue_depth,
+            overflow,
+        };
+        Ok(self)
+    }
+
+    /// Encrypts the snapshot files (and replicas) of collections opened
+    /// afterwards; plaintext snapshots still load and are encrypted on the
+    /// next write.
+    pub fn with_encryption(mut self, encryption: PersistSnapshotEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// When `false`, `open_vec` creates the collection's tables up front and a
+    /// save into a missing table fails instead of running `CREATE TABLE`.
+    /// Defaults to `true`.
+    pub fn with_lazy_table_creation(mut self, lazy_table_creation: bool) -> Self {
+        self.lazy_table_creation = lazy_table_creation;
+        self
+    }
+
+    /// Id strategy of collections opened afterwards, applied to entities they
+    /// create from drafts (`create_from_draft` and imports). Items built with
+    /// `new()` keep their random UUIDv4, and existing ids are never rewritten.
+    pub fn with_id_strategy(mut self, id_strategy: PersistIdStrategy) -> Self {
+        self.id_strategy = id_strategy;
+        self
+    }
+
     pub async fn open_vec<V>(&self, name: impl Into<String>) -> Result<ManagedPersistVec<V>>
+    where
+        V: PersistCollection,
+    {
+        self.open_vec_with_policy(name, PersistCollectionPolicy::default())
+            .await
+    }
+
+    pub async fn open_vec_with_policy<V>(
+        &self,
+        name: impl Into<String>,
+        collection_policy: PersistCollectionPolicy,
+    ) -> Result<ManagedPersistVec<V>>
     where
         V: PersistCollection,
     {
         let name = name.into();
-        let snapshot_path = self.snapshot_path_for(&name);
+        let recovery_started = std::time::Instant::now();
+        let mut snapshot_loaded = false;
+        let snapshot_path = if collection_policy.disable_snapshots {
+            None
+        } else {
+            self.snapshot_path_for(&name)
+        };
         let mut collection = V::new_collection(name.clone());
         let mut last_snapshot_at = None;
+        let mut upgraded_format = false;
+        let session = self.session.with_lazy_tables(self.lazy_table_creation);
 
-        if fs::try_exists(&snapshot_path).await.map_err(|err| {
-            DbError::ExecutionError(format!(
-                "Failed to check snapshot path '{}': {}",
-                snapshot_path.display(),
-                err
-            ))
-        })? {
-            let bytes = fs::read(&snapshot_path).await.map_err(|err| {
+        let definitions = collection.table_definitions();
+        if !self.lazy_table_creation {
+            for definition in &definitions {
+                definition.create(&self.session).await?;
+            }
+        }
+        self.table_definitions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .extend(
+                definitions
+                    .into_iter()
+                    .map(|definition| (definition.type_name.clone(), definition)),
+            );
+
+        if let Some(snapshot_path) = &snapshot_path
+            && fs::try_exists(snapshot_path).await.map_err(|err| {
+                DbError::ExecutionError(format!(
+                    "Failed to check snapshot path '{}': {}",
+                    snapshot_path.display(),
+                    err
+                ))
+            })?
+        {
+            let bytes = fs::read(snapshot_path).await.map_err(|err| {
                 DbError::ExecutionError(format!(
                     "Failed to read snapshot '{}': {}",
                     snapshot_path.display(),
@@ -224,39 +738,215 @@ impl PersistApp {
             })?;
 
             if !bytes.is_empty() {
-                let snapshot: V::Snapshot = serde_json::from_slice(&bytes).map_err(|err| {
-                    DbError::ExecutionError(format!(
-                        "Failed to decode snapshot '{}': {}",
-                        snapshot_path.display(),
-                        err
-                    ))
-                })?;
+                let (snapshot, upgraded) = decode_snapshot::<V::Snapshot>(
+                    &bytes,
+                    snapshot_path,
+                    self.encryption.as_ref(),
+                )?;
+                upgraded_format = upgraded;
 
                 collection
                     .restore_with_policy(
                         snapshot,
-                        &self.session,
+                        &session,
                         RestoreConflictPolicy::OverwriteExisting,
                     )
                     .await?;
                 last_snapshot_at = Some(Utc::now().to_rfc3339());
+                snapshot_loaded = true;
             }
         }
 
-        Ok(ManagedPersistVec {
+        let recovery = PersistRecoveryReport {
+            collection: name.clone(),
+            snapshot_loaded,
+            ops_replayed: collection.len(),
+            duration: recovery_started.elapsed(),
+            last_version: collection.max_version(),
+        };
+        {
+            let mut reports = self
+                .recovery_reports
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            reports.retain(|report| report.collection != recovery.collection);
+            reports.push(recovery.clone());
+        }
+
+        let mut managed = ManagedPersistVec {
             name,
             collection,
-            session: self.session.clone(),
+            session,
             snapshot_path,
-            snapshot_every_ops: self.policy.snapshot_every_ops.max(1),
+            snapshot_every_ops: collection_policy
+                .snapshot_every_ops
+                .unwrap_or(self.policy.snapshot_every_ops)
+                .max(1),
+            snapshot_interval: self.snapshot_interval,
             ops_since_snapshot: 0,
             replication: self.policy.replication.clone(),
+            replication_queue_limits: self.replication_queue,
+            encryption: self.encryption.clone(),
+            id_strategy: self.id_strategy.clone(),
             replication_failures: 0,
             last_snapshot_at,
-        })
+            last_interval_snapshot_at: None,
+            change_events: None,
+            replication_queue: None,
+            total_ops: 0,
+            snapshots_written: 0,
+            snapshot_durations_ms: VecDeque::new(),
+            metrics: Arc::default(),
+            recovery,
+        };
+        self.collection_metrics
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Arc::downgrade(&managed.metrics));
+        if upgraded_format {
+            managed.force_snapshot().await?;
+        }
+        managed.publish_metrics();
+        Ok(managed)
+    }
+
+    /// Metrics of every live collection opened through this app, with totals.
+    /// Each collection reports its state as of its last committed operation or
+    /// snapshot; async replication counters are read live.
+    pub fn metrics_snapshot(&self) -> PersistAppMetrics {
+        let mut registry = self
+            .collection_metrics
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        registry.retain(|cell| cell.strong_count() > 0);
+        let collections = registry
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter_map(|cell| cell.current())
+            .collect::<Vec<_>>();
+        drop(registry);
+
+        PersistAppMetrics {
+            tenant_id: self.tenant_id.clone(),
+            collection_count: collections.len(),
+            total_entities: collections.iter().map(|stats| stats.item_count).sum(),
+            total_ops: collections.iter().map(|stats| stats.total_ops).sum(),
+            snapshots_written: collections
+                .iter()
+                .map(|stats| stats.snapshots_written)
+                .sum(),
+            replication_failures: collections
+                .iter()
+                .map(|stats| stats.replication_failures)
+                .sum(),
+            replication_dropped: collections
+                .iter()
+                .map(|stats| stats.replication_dropped)
+                .sum(),
+            replication_queue_depth: collections
+                .iter()
+                .map(|stats| stats.replication_queue_depth)
+                .sum(),
+            collections,
+        }
     }
 
-    fn snapshot_path_for(&self, vec_name: &str) -> PathBuf {
+    /// Creates the tables of every collection opened so far, including their
+    /// metadata columns and schema version records, and returns how many were
+    /// ensured. Idempotent; meant for boot when lazy table creation is off or
+    /// DDL should not run on the write path. Heterogeneous collections only
+    /// report the types registered when they were opened.
+    pub async fn create_all_tables(&self) -> Result<usize> {
+        let definitions = self.registered_table_definitions();
+        for definition in &definitions {
+            definition.create(&self.session).await?;
+        }
+        Ok(definitions.len())
+    }
+
+    /// Adds `definition` to the registry behind `create_all_tables`,
+    /// `pending_migrations` and `migrate_all`, replacing any definition of the
+    /// same type. Collections opened through the app register themselves; this
+    /// covers types written through the session directly.
+    pub fn register_table_definition(&self, definition: PersistTableDefinition) {
+        self.table_definitions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(definition.type_name.clone(), definition);
+    }
+
+    /// Registered tables whose recorded schema version is behind their type's
+    /// migration plan, ordered by type name.
+    pub async fn pending_migrations(&self) -> Result<Vec<PersistPendingMigration>> {
+        let mut pending = Vec::new();
+        for definition in self.registered_table_definitions() {
+            if let Some(migration) = definition.pending_migration(&self.session).await? {
+                pending.push(migration);
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Runs the SQL steps of every pending migration and returns what was
+    /// applied. Stops at the first failing table; tables migrated before it
+    /// keep their new schema version, so a rerun resumes from there.
+    pub async fn migrate_all(&self) -> Result<Vec<PersistPendingMigration>> {
+        let mut applied = Vec::new();
+        for definition in self.registered_table_definitions() {
+            let Some(migration) = definition.pending_migration(&self.session).await? else {
+                continue;
+            };
+            definition
+                .migration_plan
+                .migrate_table_from(&self.session, &migration.table_name, migration.from_version)
+                .await
+                .map_err(|err| {
+                    DbError::ExecutionError(format!(
+                        "Failed to migrate table '{}' ({}) from schema version {} to {}: {}",
+                        migration.table_name,
+                        migration.type_name,
+                        migration.from_version,
+                        migration.to_version,
+                        err
+                    ))
+                })?;
+            applied.push(migration);
+        }
+        Ok(applied)
+    }
+
+    fn registered_table_definitions(&self) -> Vec<PersistTableDefinition> {
+        self.table_definitions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Latest recovery report of every collection opened through this app,
+    /// ordered by when each was last opened.
+    pub fn recovery_reports(&self) -> Vec<PersistRecoveryReport> {
+        self.recovery_reports
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Same as `ManagedPersistVec::integrity_check`.
+    pub async fn integrity_check<V>(
+        &self,
+        collection: &mut ManagedPersistVec<V>,
+        repair: bool,
+    ) -> Result<PersistIntegrityReport>
+    where
+        V: PersistIndexedCollection,
+    {
+        collection.integrity_check(repair).await
+    }
+
+    fn snapshot_path_for(&self, vec_name: &str) -> Option<PathBuf> {
+        let root = self.root.as_ref()?;
         let sanitized = vec_name
             .chars()
             .map(|c| {
@@ -267,7 +957,7 @@ impl PersistApp {
                 }
             })
             .collect::<String>();
-        self.root.join(format!("{sanitized}.snapshot.json"))
+        Some(root.join(format!("{sanitized}.snapshot.json")))
     }
 }
 
@@ -282,18 +972,106 @@ pub struct ManagedPersistVecStats {
     pub replication_targets: usize,
     pub replication_failures: u64,
     pub last_snapshot_at: Option<String>,
+    pub snapshot_interval_ms: Option<u64>,
+    pub last_interval_snapshot_at: Option<String>,
+    pub replication_queue_depth: usize,
+    pub replication_dropped: u64,
+    pub total_ops: u64,
+    pub snapshots_written: u64,
+    /// Encode + write + replicate time of the most recent snapshot.
+    pub last_snapshot_duration_ms: Option<u64>,
+    /// Mean over the last 16 snapshots.
+    pub avg_snapshot_duration_ms: Option<f64>,
+}
+
+/// What `PersistApp::open_vec` recovered from disk for one collection.
+#[derive(Debug, Clone)]
+pub struct PersistRecoveryReport {
+    pub collection: String,
+    pub snapshot_loaded: bool,
+    /// Entity states restored from the snapshot. Snapshots hold full state,
+    /// so there is no separate operation log to replay.
+    pub ops_replayed: usize,
+    pub duration: std::time::Duration,
+    /// Highest entity version after recovery, 0 for an empty collection.
+    pub last_version: i64,
+}
+
+/// App-wide rollup returned by `PersistApp::metrics_snapshot`.
+#[derive(Debug, Clone)]
+pub struct PersistAppMetrics {
+    pub tenant_id: Option<String>,
+    pub collection_count: usize,
+    pub total_entities: usize,
+    pub total_ops: u64,
+    pub snapshots_written: u64,
+    pub replication_failures: u64,
+    pub replication_dropped: u64,
+    pub replication_queue_depth: usize,
+    pub collections: Vec<ManagedPersistVecStats>,
+}
+
+// Published by a managed vec after each committed operation and snapshot so the
+// app can report it without holding the vec.
+#[derive(Default)]
+struct ManagedMetricsCell {
+    stats: StdMutex<Option<ManagedPersistVecStats>>,
+    replication_queue: OnceLock<Arc<ReplicationQueue>>,
+}
+
+impl ManagedMetricsCell {
+    fn current(&self) -> Option<ManagedPersistVecStats> {
+        let mut stats = self
+            .stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()?;
+        if let Some(queue) = self.replication_queue.get() {
+            queue.apply_to_stats(&mut stats);
+        }
+        Some(stats)
+    }
 }
 
 pub struct ManagedPersistVec<V: PersistCollection> {
     name: String,
     collection: V,
     session: PersistSession,
-    snapshot_path: PathBuf,
+    snapshot_path: Option<PathBuf>,
     snapshot_every_ops: usize,
+    snapshot_interval: Option<PersistSnapshotInterval>,
     ops_since_snapshot: usize,
     replication: PersistReplicationPolicy,
+    replication_queue_limits: PersistReplicationQueueLimits,
+    encryption: Option<PersistSnapshotEncryption>,
+    id_strategy: PersistIdStrategy,
     replication_failures: u64,
     last_snapshot_at: Option<String>,
+    last_interval_snapshot_at: Option<String>,
+    change_events: Option<broadcast::Sender<PersistChangeEvent>>,
+    replication_queue: Option<ReplicationQueueHandle>,
+    total_ops: u64,
+    snapshots_written: u64,
+    snapshot_durations_ms: VecDeque<u64>,
+    metrics: Arc<ManagedMetricsCell>,
+    recovery: PersistRecoveryReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistChangeEvent {
+    pub vec_name: String,
+    pub persist_id: String,
+    pub kind: PersistChangeKind,
+    pub old_version: Option<i64>,
+    pub new_version: Option<i64>,
+    pub state: Option<PersistState>,
 }
 
 impl<V: PersistCollection> ManagedPersistVec<V> {
@@ -305,17 +1083,38 @@ impl<V: PersistCollection> ManagedPersistVec<V> {
         &self.collection
     }
 
+    pub fn recovery_report(&self) -> &PersistRecoveryReport {
+        &self.recovery
+    }
+
+    pub fn id_strategy(&self) -> &PersistIdStrategy {
+        &self.id_strategy
+    }
+
     pub fn collection_mut(&mut self) -> &mut V {
         &mut self.collection
     }
 
     pub fn stats(&self) -> ManagedPersistVecStats {
+        let mut stats = self.base_stats();
+        if let Some(handle) = self.replication_queue.as_ref() {
+            handle.queue.apply_to_stats(&mut stats);
+        }
+        stats
+    }
+
+    // Stats without the async replication queue counters, which are read live.
+    fn base_stats(&self) -> ManagedPersistVecStats {
         ManagedPersistVecStats {
             vec_name: self.name.clone(),
             item_count: self.collection.len(),
             snapshot_every_ops: self.snapshot_every_ops,
             ops_since_snapshot: self.ops_since_snapshot,
-            snapshot_path: self.snapshot_path.to_string_lossy().to_string(),
+            snapshot_path: self
+                .snapshot_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default(),
             replication_mode: match self.replication.mode {
                 PersistReplicationMode::Sync => "sync".to_string(),
                 PersistReplicationMode::AsyncBestEffort => "async".to_string(),
@@ -323,6 +1122,43 @@ impl<V: PersistCollection> ManagedPersistVec<V> {
             replication_targets: self.replication.replica_roots.len(),
             replication_failures: self.replication_failures,
             last_snapshot_at: self.last_snapshot_at.clone(),
+            snapshot_interval_ms: self.snapshot_interval.map(|interval| interval.interval_ms),
+            last_interval_snapshot_at: self.last_interval_snapshot_at.clone(),
+            replication_queue_depth: 0,
+            replication_dropped: 0,
+            total_ops: self.total_ops,
+            snapshots_written: self.snapshots_written,
+            last_snapshot_duration_ms: self.snapshot_durations_ms.back().copied(),
+            avg_snapshot_duration_ms: (!self.snapshot_durations_ms.is_empty()).then(|| {
+                self.snapshot_durations_ms.iter().sum::<u64>() as f64
+                    / self.snapshot_durations_ms.len() as f64
+            }),
+        }
+    }
+
+    fn publish_metrics(&self) {
+        *self
+            .metrics
+            .stats
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(self.base_stats());
+    }
+
+    /// Waits until every queued async replication snapshot has been written
+    /// (or has failed).
+    pub async fn flush_replication(&self) {
+        let Some(handle) = self.replication_queue.as_ref() else {
+            return;
+        };
+        loop {
+            let progress = handle.queue.progress.notified();
+            {
+                let state = handle.queue.lock_state();
+                if state.jobs.is_empty() && !state.in_flight {
+                    return;
+                }
+            }
+            progress.await;
         }
     }
 
@@ -351,6 +1187,35 @@ impl<V: PersistCollection> ManagedPersistVec<V> {
     }
 
     pub async fn force_snapshot(&mut self) -> Result<()> {
+        let Some(snapshot_path) = self.snapshot_path.clone() else {
+            self.ops_since_snapshot = 0;
+            return Ok(());
+        };
+
+        let started = std::time::Instant::now();
+        let bytes = self.encode_snapshot()?;
+        atomic_write(&snapshot_path, &bytes).await?;
+        self.snapshots_written += 1;
+        let replicated = self.replicate_snapshot(&snapshot_path, &bytes).await;
+        if self.snapshot_durations_ms.len() == SNAPSHOT_DURATION_WINDOW {
+            self.snapshot_durations_ms.pop_front();
+        }
+        self.snapshot_durations_ms
+            .push_back(started.elapsed().as_millis() as u64);
+        if replicated.is_ok() {
+            self.ops_since_snapshot = 0;
+            self.last_snapshot_at = Some(Utc::now().to_rfc3339());
+        }
+        self.publish_metrics();
+        replicated
+    }
+
+    pub async fn snapshot_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let bytes = self.encode_snapshot()?;
+        atomic_write(path.as_ref(), &bytes).await
+    }
+
+    fn encode_snapshot(&self) -> Result<Vec<u8>> {
         let snapshot = self.collection.snapshot(SnapshotMode::WithData);
         let bytes = serde_json::to_vec_pretty(&snapshot).map_err(|err| {
             DbError::ExecutionError(format!(
@@ -358,34 +1223,90 @@ impl<V: PersistCollection> ManagedPersistVec<V> {
                 self.name, err
             ))
         })?;
-        atomic_write(&self.snapshot_path, &bytes).await?;
-        self.replicate_snapshot(&bytes).await?;
-        self.ops_since_snapshot = 0;
-        self.last_snapshot_at = Some(Utc::now().to_rfc3339());
-        Ok(())
-    }
-
-    async fn on_mutation_committed(&mut self) -> Result<()> {
-        self.ops_since_snapshot += 1;
-        if self.ops_since_snapshot >= self.snapshot_every_ops {
-            self.force_snapshot().await?;
+        match &self.encryption {
+            Some(encryption) => encryption.encrypt(&bytes),
+            None => Ok(bytes),
         }
-        Ok(())
     }
 
-    async fn begin_atomic_scope(&mut self) -> Result<(V::Snapshot, TransactionId, PersistSession)> {
-        let rollback_snapshot = self.collection.snapshot(SnapshotMode::WithData);
-        let transaction_id = self.session.begin_transaction().await?;
-        let tx_session = self.session.with_transaction_id(transaction_id);
-        Ok((rollback_snapshot, transaction_id, tx_session))
+    /// Writes a snapshot with `#[sql(redact)]` fields masked, for sharing
+    /// outside the team. It is not meant to be restored as a backup.
+    pub async fn redacted_snapshot_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let snapshot = self.collection.redacted_snapshot(SnapshotMode::WithData);
+        let bytes = serde_json::to_vec_pretty(&snapshot).map_err(|err| {
+            DbError::ExecutionError(format!(
+                "Failed to encode redacted snapshot for vec '{}': {}",
+                self.name, err
+            ))
+        })?;
+        atomic_write(path.as_ref(), &bytes).await
     }
 
-    async fn finalize_atomic_scope<T>(
+    pub async fn restore_from_file(
         &mut self,
-        operation: &str,
-        rollback_snapshot: V::Snapshot,
-        transaction_id: TransactionId,
-        operation_result: Result<T>,
+        path: impl AsRef<Path>,
+        conflict_policy: RestoreConflictPolicy,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).await.map_err(|err| {
+            DbError::ExecutionError(format!(
+                "Failed to read snapshot '{}': {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let (snapshot, _) = decode_snapshot::<V::Snapshot>(&bytes, path, self.encryption.as_ref())?;
+        self.collection.ensure_snapshot_compatible(&snapshot)?;
+
+        let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
+        let operation_result = self
+            .collection
+            .restore_with_policy(snapshot, &tx_session, conflict_policy)
+            .await;
+        self.finalize_atomic_scope(
+            "restore_from_file",
+            rollback_snapshot,
+            transaction_id,
+            operation_result,
+        )
+        .await?;
+        self.on_mutation_committed().await
+    }
+
+    async fn run_interval_snapshot_tick(&mut self) -> Result<()> {
+        if self.ops_since_snapshot == 0 {
+            return Ok(());
+        }
+        self.force_snapshot().await?;
+        if self.ops_since_snapshot == 0 {
+            self.last_interval_snapshot_at = self.last_snapshot_at.clone();
+        }
+        Ok(())
+    }
+
+    async fn on_mutation_committed(&mut self) -> Result<()> {
+        self.ops_since_snapshot += 1;
+        self.total_ops += 1;
+        self.publish_metrics();
+        if self.ops_since_snapshot >= self.snapshot_every_ops {
+            self.force_snapshot().await?;
+        }
+        Ok(())
+    }
+
+    async fn begin_atomic_scope(&mut self) -> Result<(V::Snapshot, TransactionId, PersistSession)> {
+        let rollback_snapshot = self.collection.snapshot(SnapshotMode::WithData);
+        let transaction_id = self.session.begin_transaction().await?;
+        let tx_session = self.session.with_transaction_id(transaction_id);
+        Ok((rollback_snapshot, transaction_id, tx_session))
+    }
+
+    async fn finalize_atomic_scope<T>(
+        &mut self,
+        operation: &str,
+        rollback_snapshot: V::Snapshot,
+        transaction_id: TransactionId,
+        operation_result: Result<T>,
     ) -> Result<T> {
         match operation_result {
             Ok(value) => {
@@ -440,17 +1361,43 @@ impl<V: PersistCollection> ManagedPersistVec<V> {
         }
     }
 
-    async fn replicate_snapshot(&mut self, bytes: &[u8]) -> Result<()> {
+    async fn replicate_snapshot(&mut self, snapshot_path: &Path, bytes: &[u8]) -> Result<()> {
         if self.replication.replica_roots.is_empty() {
             return Ok(());
         }
 
         let mode = self.replication.mode.clone();
+        if matches!(mode, PersistReplicationMode::AsyncBestEffort) {
+            let file_name = snapshot_path
+                .file_name()
+                .unwrap_or_else(|| std::ffi::OsStr::new("snapshot.json"))
+                .to_os_string();
+            let queue = self
+                .replication_queue
+                .get_or_insert_with(|| {
+                    ReplicationQueueHandle::spawn(
+                        self.name.clone(),
+                        &self.replication,
+                        self.replication_queue_limits,
+                    )
+                })
+                .queue
+                .clone();
+            let _ = self.metrics.replication_queue.set(queue.clone());
+            queue
+                .enqueue(ReplicationJob {
+                    file_name,
+                    bytes: bytes.to_vec(),
+                })
+                .await;
+            return Ok(());
+        }
+
         let mut failures = 0u64;
 
         for root in self.replication.replica_roots.clone() {
             let target = root.join(
-                self.snapshot_path
+                snapshot_path
                     .file_name()
                     .unwrap_or_else(|| std::ffi::OsStr::new("snapshot.json")),
             );
@@ -478,6 +1425,69 @@ impl<V> ManagedPersistVec<V>
 where
     V: PersistIndexedCollection,
 {
+    /// Subscribes to change events emitted after each committed CRUD or command
+    /// mutation. Delivery is at-most-once: events are only sent to live receivers,
+    /// and a receiver that falls behind gets `RecvError::Lagged` and misses events.
+    pub fn subscribe_changes(&mut self) -> broadcast::Receiver<PersistChangeEvent> {
+        self.change_events
+            .get_or_insert_with(|| broadcast::channel(CHANGE_EVENTS_CAPACITY).0)
+            .subscribe()
+    }
+
+    fn change_baseline(&self) -> Option<BTreeMap<String, i64>> {
+        let sender = self.change_events.as_ref()?;
+        if sender.receiver_count() == 0 {
+            return None;
+        }
+
+        Some(
+            self.collection
+                .items()
+                .iter()
+                .filter(|item| item.metadata().persisted)
+                .map(|item| (item.persist_id().to_string(), item.metadata().version))
+                .collect(),
+        )
+    }
+
+    fn publish_changes(&self, baseline: Option<BTreeMap<String, i64>>) {
+        let (Some(sender), Some(mut baseline)) = (self.change_events.as_ref(), baseline) else {
+            return;
+        };
+
+        for item in self.collection.items() {
+            if !item.metadata().persisted {
+                continue;
+            }
+            let new_version = item.metadata().version;
+            let old_version = baseline.remove(item.persist_id());
+            let kind = match old_version {
+                None => PersistChangeKind::Created,
+                Some(version) if version != new_version => PersistChangeKind::Updated,
+                Some(_) => continue,
+            };
+            let _ = sender.send(PersistChangeEvent {
+                vec_name: self.name.clone(),
+                persist_id: item.persist_id().to_string(),
+                kind,
+                old_version,
+                new_version: Some(new_version),
+                state: Some(item.state()),
+            });
+        }
+
+        for (persist_id, old_version) in baseline {
+            let _ = sender.send(PersistChangeEvent {
+                vec_name: self.name.clone(),
+                persist_id,
+                kind: PersistChangeKind::Deleted,
+                old_version: Some(old_version),
+                new_version: None,
+                state: None,
+            });
+        }
+    }
+
     pub fn list(&self) -> &[V::Item] {
         self.collection.items()
     }
@@ -489,6 +1499,24 @@ where
             .find(|item| item.persist_id() == persist_id && item.metadata().persisted)
     }
 
+    /// Returns the persisted items for `persist_ids` in request order.
+    /// Missing ids are omitted and duplicate ids are returned once.
+    pub fn get_many(&self, persist_ids: &[String]) -> Vec<&V::Item> {
+        let by_id = self
+            .collection
+            .items()
+            .iter()
+            .filter(|item| item.metadata().persisted)
+            .map(|item| (item.persist_id(), item))
+            .collect::<HashMap<_, _>>();
+        let mut seen = HashSet::new();
+        persist_ids
+            .iter()
+            .filter(|id| seen.insert(id.as_str()))
+            .filter_map(|id| by_id.get(id.as_str()).copied())
+            .collect()
+    }
+
     pub fn list_page(&self, offset: usize, limit: usize) -> Vec<&V::Item> {
         if limit == 0 {
             return Vec::new();
@@ -515,6 +1543,10 @@ where
             .collect()
     }
 
+    pub fn find_by_label(&self, key: &str, value: &str) -> Vec<&V::Item> {
+        self.list_filtered(|item| item.get_label(key) == Some(value))
+    }
+
     pub fn list_sorted_by<F>(&self, mut compare: F) -> Vec<&V::Item>
     where
         F: FnMut(&V::Item, &V::Item) -> Ordering,
@@ -530,6 +1562,9 @@ where
     }
 
     pub async fn create(&mut self, item: V::Item) -> Result<()> {
+        ensure_unique_index_fields(self.collection.items(), &[], &item)
+            .map_err(|err| map_managed_conflict_error("create", err))?;
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
         self.collection.add_one(item);
         let operation_result = self.collection.save_all(&tx_session).await;
@@ -540,6 +1575,7 @@ where
             operation_result,
         )
         .await?;
+        self.publish_changes(change_baseline);
         self.on_mutation_committed().await
     }
 
@@ -548,7 +1584,12 @@ where
         if count == 0 {
             return Ok(0);
         }
+        for (index, item) in items.iter().enumerate() {
+            ensure_unique_index_fields(self.collection.items(), &items[..index], item)
+                .map_err(|err| map_managed_conflict_error("create_many", err))?;
+        }
 
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
         self.collection.add_many(items);
         let operation_result = self.collection.save_all(&tx_session).await;
@@ -559,6 +1600,7 @@ where
             operation_result,
         )
         .await?;
+        self.publish_changes(change_baseline);
         self.on_mutation_committed().await?;
         Ok(count)
     }
@@ -568,6 +1610,7 @@ where
         F: FnOnce(&mut V::Item) -> Result<()>,
     {
         let persist_id = persist_id.to_string();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let operation_result = match self
@@ -591,6 +1634,7 @@ where
                 operation_result,
             )
             .await?;
+        self.publish_changes(change_baseline);
 
         if updated {
             self.on_mutation_committed().await?;
@@ -598,6 +1642,234 @@ where
         Ok(updated)
     }
 
+    pub async fn update_if_version<F>(
+        &mut self,
+        persist_id: &str,
+        expected_version: i64,
+        mutator: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce(&mut V::Item) -> Result<()>,
+    {
+        if !self
+            .ensure_expected_version(persist_id, expected_version)
+            .map_err(|err| map_managed_conflict_error("update_if_version", err))?
+        {
+            return Ok(false);
+        }
+        self.update(persist_id, mutator).await
+    }
+
+    /// Upsert keyed by `item.persist_id()`: creates the item when the id is
+    /// unknown, otherwise replaces the stored item's fields while keeping its
+    /// metadata (version, timestamps, labels). Returns `true` when created.
+    /// `expected_version` acts like `If-Match`: it must equal the stored
+    /// version, and an unknown id with an expected version is a conflict.
+    pub async fn put(&mut self, item: V::Item, expected_version: Option<i64>) -> Result<bool> {
+        let persist_id = item.persist_id().to_string();
+        if self.get(&persist_id).is_none() {
+            if let Some(expected_version) = expected_version {
+                return Err(map_managed_conflict_error(
+                    "put",
+                    DbError::ExecutionError(format!(
+                        "Version conflict: {} '{}' does not exist, expected version {}",
+                        item.type_name(),
+                        persist_id,
+                        expected_version
+                    )),
+                ));
+            }
+            self.create(item).await?;
+            return Ok(true);
+        }
+
+        if let Some(expected_version) = expected_version {
+            self.ensure_expected_version(&persist_id, expected_version)
+                .map_err(|err| map_managed_conflict_error("put", err))?;
+        }
+        ensure_unique_index_fields(self.collection.items(), &[], &item)
+            .map_err(|err| map_managed_conflict_error("put", err))?;
+        self.update(&persist_id, move |stored| {
+            let metadata = stored.metadata().clone();
+            *stored = item;
+            *stored.metadata_mut() = metadata;
+            stored.mark_all_dirty();
+            Ok(())
+        })
+        .await?;
+        Ok(false)
+    }
+
+    /// Compares every item with its SQL row and reports missing or stale
+    /// rows, rows without an item, duplicate ids and metadata anomalies.
+    /// With `repair`, only rows the item is ahead of are rewritten: stale rows
+    /// whose `__version` is lower than the item version, and missing rows of
+    /// items that were never persisted. Rows that are newer than the item or
+    /// were deleted after the item was persisted may come from another writer,
+    /// so they are only reported, like orphan rows.
+    pub async fn integrity_check(&mut self, repair: bool) -> Result<PersistIntegrityReport> {
+        let mut report = PersistIntegrityReport {
+            vec_name: self.name.clone(),
+            checked_items: self.collection.items().len(),
+            issues: Vec::new(),
+        };
+        let mut row_versions: BTreeMap<String, HashMap<String, i64>> = BTreeMap::new();
+        let mut seen_ids = HashSet::new();
+        let mut rebuild = Vec::new();
+
+        for (index, item) in self.collection.items().iter().enumerate() {
+            let persist_id = item.persist_id().to_string();
+            if !seen_ids.insert(persist_id.clone()) {
+                report.issues.push(PersistIntegrityIssue {
+                    kind: PersistIntegrityIssueKind::DuplicateId,
+                    persist_id,
+                    detail: "persist id appears more than once in the collection".to_string(),
+                    repaired: false,
+                });
+                continue;
+            }
+
+            let metadata = item.metadata();
+            let mut anomalies = Vec::new();
+            if metadata.version < 1 {
+                anomalies.push(format!("version {} is below 1", metadata.version));
+            }
+            if metadata.updated_at < metadata.created_at {
+                anomalies.push("updated_at is earlier than created_at".to_string());
+            }
+            if !metadata.persisted {
+                anomalies.push("item is not marked as persisted".to_string());
+            }
+            for detail in anomalies {
+                report.issues.push(PersistIntegrityIssue {
+                    kind: PersistIntegrityIssueKind::MetadataAnomaly,
+                    persist_id: persist_id.clone(),
+                    detail,
+                    repaired: false,
+                });
+            }
+
+            let table_name = item.table_name().to_string();
+            if !row_versions.contains_key(&table_name) {
+                let rows = self.load_row_versions(&table_name).await?;
+                row_versions.insert(table_name.clone(), rows);
+            }
+            let row_version = row_versions[&table_name].get(&persist_id).copied();
+            let row_issue = match row_version {
+                None => Some((
+                    PersistIntegrityIssueKind::MissingRow,
+                    format!("no row in table '{}'", table_name),
+                    !metadata.persisted,
+                )),
+                Some(row_version) if row_version != metadata.version => Some((
+                    PersistIntegrityIssueKind::StaleRow,
+                    format!(
+                        "row version {} differs from item version {}",
+                        row_version, metadata.version
+                    ),
+                    row_version < metadata.version,
+                )),
+                Some(_) => None,
+            };
+            if let Some((kind, detail, repairable)) = row_issue {
+                if repairable {
+                    rebuild.push((index, report.issues.len(), row_version));
+                }
+                report.issues.push(PersistIntegrityIssue {
+                    kind,
+                    persist_id,
+                    detail,
+                    repaired: false,
+                });
+            }
+        }
+
+        for (table_name, rows) in &row_versions {
+            let mut orphan_ids = rows
+                .keys()
+                .filter(|persist_id| !seen_ids.contains(*persist_id))
+                .collect::<Vec<_>>();
+            orphan_ids.sort();
+            for persist_id in orphan_ids {
+                report.issues.push(PersistIntegrityIssue {
+                    kind: PersistIntegrityIssueKind::OrphanRow,
+                    persist_id: persist_id.clone(),
+                    detail: format!("row in table '{}' has no collection item", table_name),
+                    repaired: false,
+                });
+            }
+        }
+
+        if repair {
+            for (item_index, issue_index, row_version) in rebuild {
+                let item = &mut self.collection.items_mut()[item_index];
+                if let Some(row_version) = row_version {
+                    // Another writer may have moved the row on since it was read.
+                    let deleted = self
+                        .session
+                        .execute(&format!(
+                            "DELETE FROM {} WHERE __persist_id = '{}' AND __version = {}",
+                            item.table_name(),
+                            sql_escape_string(item.persist_id()),
+                            row_version
+                        ))
+                        .await?
+                        .affected_rows()
+                        .unwrap_or(0);
+                    if deleted == 0 {
+                        continue;
+                    }
+                }
+                item.metadata_mut().persisted = false;
+                item.mark_all_dirty();
+                item.save(&self.session).await?;
+                report.issues[issue_index].repaired = true;
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn load_row_versions(&self, table_name: &str) -> Result<HashMap<String, i64>> {
+        if !self.session.table_exists(table_name).await {
+            return Ok(HashMap::new());
+        }
+        let result = self
+            .session
+            .query(&format!(
+                "SELECT __persist_id, __version FROM {}",
+                table_name
+            ))
+            .await?;
+        let mut rows = HashMap::with_capacity(result.row_count());
+        for row in result.rows() {
+            if let (Some(Value::Text(persist_id)), Some(Value::Integer(version))) =
+                (row.first(), row.get(1))
+            {
+                rows.insert(persist_id.clone(), *version);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn ensure_expected_version(&self, persist_id: &str, expected_version: i64) -> Result<bool> {
+        let Some(item) = self.get(persist_id) else {
+            return Ok(false);
+        };
+
+        let stored_version = item.metadata().version;
+        if stored_version != expected_version {
+            return Err(DbError::ExecutionError(format!(
+                "Version conflict: {} '{}' is at version {}, expected {}",
+                item.type_name(),
+                persist_id,
+                stored_version,
+                expected_version
+            )));
+        }
+        Ok(true)
+    }
+
     pub async fn apply_many<F>(&mut self, persist_ids: &[String], mutator: F) -> Result<usize>
     where
         F: Fn(&mut V::Item) -> Result<()>,
@@ -606,6 +1878,7 @@ where
             .iter()
             .cloned()
             .collect::<std::collections::HashSet<_>>();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let mut updated = 0usize;
@@ -638,6 +1911,7 @@ where
                 operation_result.map(|_| updated),
             )
             .await?;
+        self.publish_changes(change_baseline);
 
         if updated > 0 {
             self.on_mutation_committed().await?;
@@ -647,6 +1921,7 @@ where
 
     pub async fn delete(&mut self, persist_id: &str) -> Result<bool> {
         let persist_id = persist_id.to_string();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let operation_result = match self.collection.remove_by_persist_id(&persist_id) {
@@ -662,6 +1937,7 @@ where
                 operation_result,
             )
             .await?;
+        self.publish_changes(change_baseline);
 
         if deleted {
             self.on_mutation_committed().await?;
@@ -669,8 +1945,135 @@ where
         Ok(deleted)
     }
 
+    /// Runs `f` inside a single transaction: every create/update/delete made
+    /// through the `ManagedPersistTx` commits together, or all of them roll back
+    /// (database rows and in-memory items) when `f` or the commit fails.
+    ///
+    /// The scope counts as one op toward `snapshot_every_ops` and change events
+    /// are published after commit. Items' own auto-persist sessions are not part
+    /// of the transaction, so mutate through `tx` only.
+    pub async fn transaction<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: for<'a, 'b> FnOnce(
+            &'a mut ManagedPersistTx<'b, V>,
+        ) -> Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>,
+    {
+        let change_baseline = self.change_baseline();
+        let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
+
+        let mut tx = ManagedPersistTx {
+            collection: &mut self.collection,
+            session: tx_session,
+            changed: false,
+        };
+        let operation_result = f(&mut tx).await;
+        let changed = tx.changed;
+
+        let value = self
+            .finalize_atomic_scope(
+                "transaction",
+                rollback_snapshot,
+                transaction_id,
+                operation_result,
+            )
+            .await?;
+        self.publish_changes(change_baseline);
+
+        if changed {
+            self.on_mutation_committed().await?;
+        }
+        Ok(value)
+    }
+
+    /// Replaces the in-memory items with the collection's snapshot on disk, for
+    /// example after a replica promotion or a write by another process. Rows of
+    /// items missing from the snapshot are deleted and change events are published.
+    ///
+    /// Fails while mutations since the last snapshot are pending, because the
+    /// reload would discard them; call `force_snapshot` first to keep them.
+    pub async fn reload_from_storage(&mut self) -> Result<()> {
+        if self.ops_since_snapshot > 0 {
+            return Err(DbError::ExecutionError(format!(
+                "Cannot reload vec '{}': {} mutation(s) are not snapshotted yet",
+                self.name, self.ops_since_snapshot
+            )));
+        }
+        let Some(snapshot_path) = self.snapshot_path.clone() else {
+            return Err(DbError::ExecutionError(format!(
+                "Cannot reload vec '{}': it has no snapshot storage",
+                self.name
+            )));
+        };
+
+        let bytes = match fs::read(&snapshot_path).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                return Err(DbError::ExecutionError(format!(
+                    "Failed to read snapshot '{}': {}",
+                    snapshot_path.display(),
+                    err
+                )));
+            }
+        };
+        let snapshot = if bytes.is_empty() {
+            V::new_collection(self.name.clone()).snapshot(SnapshotMode::WithData)
+        } else {
+            decode_snapshot::<V::Snapshot>(&bytes, &snapshot_path, self.encryption.as_ref())?.0
+        };
+        self.collection.ensure_snapshot_compatible(&snapshot)?;
+
+        let previous = self
+            .collection
+            .items()
+            .iter()
+            .map(|item| (item.persist_id().to_string(), item.table_name().to_string()))
+            .collect::<Vec<_>>();
+        let change_baseline = self.change_baseline();
+        let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
+
+        let mut operation_result = self
+            .collection
+            .restore_with_policy(
+                snapshot,
+                &tx_session,
+                RestoreConflictPolicy::OverwriteExisting,
+            )
+            .await;
+        if operation_result.is_ok() {
+            let kept = self
+                .collection
+                .items()
+                .iter()
+                .map(|item| item.persist_id())
+                .collect::<HashSet<_>>();
+            for (persist_id, table_name) in &previous {
+                if kept.contains(persist_id.as_str()) {
+                    continue;
+                }
+                operation_result = tx_session.delete_persist_row(table_name, persist_id).await;
+                if operation_result.is_err() {
+                    break;
+                }
+            }
+        }
+
+        self.finalize_atomic_scope(
+            "reload_from_storage",
+            rollback_snapshot,
+            transaction_id,
+            operation_result,
+        )
+        .await?;
+        self.publish_changes(change_baseline);
+        self.last_snapshot_at = Some(Utc::now().to_rfc3339());
+        self.publish_metrics();
+        Ok(())
+    }
+
     pub async fn delete_many(&mut self, persist_ids: &[String]) -> Result<usize> {
         let persist_ids = persist_ids.to_vec();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let mut removed = 0usize;
@@ -696,6 +2099,7 @@ where
                 operation_result.map(|_| removed),
             )
             .await?;
+        self.publish_changes(change_baseline);
 
         if removed > 0 {
             self.on_mutation_committed().await?;
@@ -721,13 +2125,102 @@ where
         &mut self,
         draft: <V::Item as PersistCommandModel>::Draft,
     ) -> Result<String> {
-        <V::Item as PersistCommandModel>::validate_draft_payload(&draft)?;
-        let item = <V::Item as PersistCommandModel>::try_from_draft(draft)?;
+        let item = self.item_from_draft(draft)?;
         let persist_id = item.persist_id().to_string();
         self.create(item).await?;
         Ok(persist_id)
     }
 
+    pub async fn import_models<M>(
+        &mut self,
+        models: Vec<M>,
+        policy: PersistImportPolicy,
+    ) -> Result<Vec<String>>
+    where
+        M: Into<<V::Item as PersistCommandModel>::Draft>,
+    {
+        self.import_drafts(models.into_iter().map(|model| Ok(model.into())), policy)
+            .await
+    }
+
+    pub async fn import_json_file<M>(
+        &mut self,
+        path: impl AsRef<Path>,
+        policy: PersistImportPolicy,
+    ) -> Result<Vec<String>>
+    where
+        M: DeserializeOwned + Into<<V::Item as PersistCommandModel>::Draft>,
+    {
+        let path = path.as_ref();
+        let bytes = fs::read(path).await.map_err(|err| {
+            DbError::ExecutionError(format!(
+                "Failed to read import file '{}': {}",
+                path.display(),
+                err
+            ))
+        })?;
+        let records: Vec<serde_json::Value> = serde_json::from_slice(&bytes).map_err(|err| {
+            DbError::ExecutionError(format!(
+                "Import file '{}' must contain a JSON array: {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        let drafts = records.into_iter().enumerate().map(|(index, record)| {
+            serde_json::from_value::<M>(record)
+                .map(Into::into)
+                .map_err(|err| {
+                    DbError::ExecutionError(format!(
+                        "Malformed import record #{} in '{}': {}",
+                        index,
+                        path.display(),
+                        err
+                    ))
+                })
+        });
+        self.import_drafts(drafts, policy).await
+    }
+
+    async fn import_drafts<I>(
+        &mut self,
+        drafts: I,
+        policy: PersistImportPolicy,
+    ) -> Result<Vec<String>>
+    where
+        I: IntoIterator<Item = Result<<V::Item as PersistCommandModel>::Draft>>,
+    {
+        let mut items = Vec::new();
+        for draft in drafts {
+            let item = draft.and_then(|draft| {
+                let item = self.item_from_draft(draft)?;
+                ensure_unique_index_fields(self.collection.items(), &items, &item)?;
+                Ok(item)
+            });
+            match item {
+                Ok(item) => items.push(item),
+                Err(_) if policy == PersistImportPolicy::SkipInvalid => continue,
+                Err(err) => return Err(map_managed_conflict_error("import", err)),
+            }
+        }
+
+        let ids = items
+            .iter()
+            .map(|item| item.persist_id().to_string())
+            .collect::<Vec<_>>();
+        self.create_many(items).await?;
+        Ok(ids)
+    }
+
+    fn item_from_draft(&self, draft: <V::Item as PersistCommandModel>::Draft) -> Result<V::Item> {
+        <V::Item as PersistCommandModel>::validate_draft_payload(&draft)?;
+        let mut item = <V::Item as PersistCommandModel>::try_from_draft(draft)?;
+        if !matches!(self.id_strategy, PersistIdStrategy::Uuid4) {
+            item.set_persist_id(self.id_strategy.generate())?;
+        }
+        Ok(item)
+    }
+
     pub async fn patch(
         &mut self,
         persist_id: &str,
@@ -736,6 +2229,7 @@ where
         <V::Item as PersistCommandModel>::validate_patch_payload(&patch)?;
 
         let persist_id = persist_id.to_string();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let operation_result = match self
@@ -757,6 +2251,7 @@ where
         let (found, changed) = self
             .finalize_atomic_scope("patch", rollback_snapshot, transaction_id, operation_result)
             .await?;
+        self.publish_changes(change_baseline);
 
         if changed {
             self.on_mutation_committed().await?;
@@ -765,6 +2260,21 @@ where
         Ok(found)
     }
 
+    pub async fn patch_if_version(
+        &mut self,
+        persist_id: &str,
+        expected_version: i64,
+        patch: <V::Item as PersistCommandModel>::Patch,
+    ) -> Result<bool> {
+        if !self
+            .ensure_expected_version(persist_id, expected_version)
+            .map_err(|err| map_managed_conflict_error("patch_if_version", err))?
+        {
+            return Ok(false);
+        }
+        self.patch(persist_id, patch).await
+    }
+
     pub async fn apply_command(
         &mut self,
         persist_id: &str,
@@ -773,6 +2283,7 @@ where
         <V::Item as PersistCommandModel>::validate_command_payload(&command)?;
 
         let persist_id = persist_id.to_string();
+        let change_baseline = self.change_baseline();
         let (rollback_snapshot, transaction_id, tx_session) = self.begin_atomic_scope().await?;
 
         let operation_result = match self
@@ -799,6 +2310,7 @@ where
                 operation_result,
             )
             .await?;
+        self.publish_changes(change_baseline);
 
         if changed {
             self.on_mutation_committed().await?;
@@ -806,6 +2318,452 @@ where
 
         Ok(found)
     }
+
+    /// Dry run of `apply_command`: applies the command to a detached copy of
+    /// the item rebuilt from its state and returns that copy (`None` if the id
+    /// is unknown). Nothing is saved, no change event is published and no
+    /// snapshot counter moves. Commands that perform side effects of their own
+    /// (I/O, external calls) still perform them; only the model mutation is
+    /// previewed.
+    pub fn preview_command(
+        &self,
+        persist_id: &str,
+        command: <V::Item as PersistCommandModel>::Command,
+    ) -> Result<Option<V::Item>>
+    where
+        V::Item: PersistEntityFactory,
+    {
+        <V::Item as PersistCommandModel>::validate_command_payload(&command)?;
+
+        let Some(item) = self.get(persist_id) else {
+            return Ok(None);
+        };
+        let mut preview = <V::Item as PersistEntityFactory>::from_state(&item.state())?;
+        <V::Item as PersistCommandModel>::apply_command_model(&mut preview, command)?;
+        Ok(Some(preview))
+    }
+}
+
+pub struct ManagedPersistTx<'a, V: PersistIndexedCollection> {
+    collection: &'a mut V,
+    session: PersistSession,
+    changed: bool,
+}
+
+impl<V> ManagedPersistTx<'_, V>
+where
+    V: PersistIndexedCollection,
+{
+    pub fn list(&self) -> &[V::Item] {
+        self.collection.items()
+    }
+
+    pub fn get(&self, persist_id: &str) -> Option<&V::Item> {
+        self.collection
+            .items()
+            .iter()
+            .find(|item| item.persist_id() == persist_id && item.metadata().persisted)
+    }
+
+    pub async fn create(&mut self, item: V::Item) -> Result<()> {
+        ensure_unique_index_fields(self.collection.items(), &[], &item)
+            .map_err(|err| map_managed_conflict_error("create", err))?;
+        self.collection.add_one(item);
+        self.changed = true;
+        self.collection.save_all(&self.session).await
+    }
+
+    pub async fn update<F>(&mut self, persist_id: &str, mutator: F) -> Result<bool>
+    where
+        F: FnOnce(&mut V::Item) -> Result<()>,
+    {
+        let Some(item) = self
+            .collection
+            .items_mut()
+            .iter_mut()
+            .find(|item| item.persist_id() == persist_id && item.metadata().persisted)
+        else {
+            return Ok(false);
+        };
+
+        mutator(item)?;
+        self.changed = true;
+        self.collection.save_all(&self.session).await?;
+        Ok(true)
+    }
+
+    pub async fn delete(&mut self, persist_id: &str) -> Result<bool> {
+        let Some(mut item) = self.collection.remove_by_persist_id(persist_id) else {
+            return Ok(false);
+        };
+
+        self.changed = true;
+        item.delete(&self.session).await?;
+        Ok(true)
+    }
+}
+
+impl<V> ManagedPersistTx<'_, V>
+where
+    V: PersistIndexedCollection,
+    V::Item: PersistCommandModel,
+{
+    pub async fn patch(
+        &mut self,
+        persist_id: &str,
+        patch: <V::Item as PersistCommandModel>::Patch,
+    ) -> Result<bool> {
+        <V::Item as PersistCommandModel>::validate_patch_payload(&patch)?;
+
+        let Some(item) = self
+            .collection
+            .items_mut()
+            .iter_mut()
+            .find(|item| item.persist_id() == persist_id && item.metadata().persisted)
+        else {
+            return Ok(false);
+        };
+
+        if <V::Item as PersistCommandModel>::apply_patch_model(item, patch)? {
+            self.changed = true;
+            self.collection.save_all(&self.session).await?;
+        }
+        Ok(true)
+    }
+}
+
+pub struct ManagedSnapshotScheduler {
+    stop_tx: Option<oneshot::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ManagedSnapshotScheduler {
+    pub async fn stop(mut self) -> Result<()> {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+
+        if let Some(join_handle) = self.join_handle.take() {
+            join_handle.await.map_err(|err| {
+                DbError::ExecutionError(format!("snapshot scheduler join: {}", err))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ManagedSnapshotScheduler {
+    fn drop(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(join_handle) = self.join_handle.take() {
+            join_handle.abort();
+        }
+    }
+}
+
+/// Snapshots `managed` every interval set by `PersistApp::with_snapshot_interval`
+/// (plus its random jitter) whenever it has mutations not yet covered by a
+/// snapshot, alongside the op-count trigger. Fails for collections without
+/// an interval or without a snapshot file (in-memory apps and
+/// `disable_snapshots` collections).
+pub async fn spawn_managed_snapshot_scheduler<V>(
+    managed: Arc<Mutex<ManagedPersistVec<V>>>,
+) -> Result<ManagedSnapshotScheduler>
+where
+    V: PersistCollection,
+{
+    let PersistSnapshotInterval {
+        interval_ms,
+        jitter_ms,
+    } = {
+        let guard = managed.lock().await;
+        let interval = guard.snapshot_interval.ok_or_else(|| {
+            DbError::ExecutionError(
+                "PersistApp::with_snapshot_interval must be set to start the snapshot scheduler"
+                    .to_string(),
+            )
+        })?;
+        if guard.snapshot_path.is_none() {
+            return Err(DbError::ExecutionError(format!(
+                "Vec '{}' has no snapshot file to write on an interval",
+                guard.name
+            )));
+        }
+        interval
+    };
+
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    let join_handle = tokio::spawn(async move {
+        loop {
+            let jitter = random_jitter_ms(jitter_ms);
+            tokio::select! {
+                _ = &mut stop_rx => {
+                    break;
+                }
+                _ = sleep(TokioDuration::from_millis(interval_ms + jitter)) => {
+                    let mut guard = managed.lock().await;
+                    if let Err(err) = guard.run_interval_snapshot_tick().await {
+                        warn!("interval snapshot for vec '{}' failed: {}", guard.name, err);
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(ManagedSnapshotScheduler {
+        stop_tx: Some(stop_tx),
+        join_handle: Some(join_handle),
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PersistReplicationQueueLimits {
+    max_queue_depth: usize,
+    overflow: PersistReplicationOverflow,
+}
+
+impl Default for PersistReplicationQueueLimits {
+    fn default() -> Self {
+        Self {
+            max_queue_depth: 64,
+            overflow: PersistReplicationOverflow::Block,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PersistSnapshotInterval {
+    interval_ms: u64,
+    jitter_ms: u64,
+}
+
+fn random_jitter_ms(max_jitter_ms: u64) -> u64 {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    if max_jitter_ms == 0 {
+        return 0;
+    }
+    let mut bytes = [0u8; 8];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        return 0;
+    }
+    u64::from_le_bytes(bytes) % (max_jitter_ms + 1)
+}
+
+// Decodes a collection snapshot, upgrading older on-disk formats to
+// `PERSIST_SNAPSHOT_FORMAT_VERSION` first. Returns whether an upgrade happened.
+// Encrypted snapshots are decrypted with the key named in their header.
+fn decode_snapshot<S: DeserializeOwned>(
+    bytes: &[u8],
+    path: &Path,
+    encryption: Option<&PersistSnapshotEncryption>,
+) -> Result<(S, bool)> {
+    let decode_err = |err: serde_json::Error| {
+        DbError::ExecutionError(format!(
+            "Failed to decode snapshot '{}': {}",
+            path.display(),
+            err
+        ))
+    };
+
+    let decrypted;
+    let bytes = match split_encrypted_snapshot(bytes)? {
+        None => bytes,
+        Some(parts) => {
+            let encryption = encryption.ok_or_else(|| {
+                DbError::ExecutionError(format!(
+                    "Snapshot '{}' is encrypted with key id '{}' but no encryption is configured",
+                    path.display(),
+                    parts.key_id
+                ))
+            })?;
+            decrypted = encryption.decrypt(&parts)?;
+            decrypted.as_slice()
+        }
+    };
+
+    let mut value: serde_json::Value = serde_json::from_slice(bytes).map_err(decode_err)?;
+    let object = value.as_object_mut().ok_or_else(|| {
+        DbError::ExecutionError(format!(
+            "Failed to decode snapshot '{}': expected a JSON object",
+            path.display()
+        ))
+    })?;
+
+    // Snapshots written before the format header existed carry no version.
+    let format_version = match object.get("format_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u16::try_from(version).ok())
+            .ok_or_else(|| {
+                DbError::ExecutionError(format!(
+                    "Snapshot '{}' has an invalid format_version: {}",
+                    path.display(),
+                    version
+                ))
+            })?,
+    };
+    if format_version > PERSIST_SNAPSHOT_FORMAT_VERSION {
+        return Err(DbError::ExecutionError(format!(
+            "Snapshot '{}' uses format version {}, but this build only understands up to version {}",
+            path.display(),
+            format_version,
+            PERSIST_SNAPSHOT_FORMAT_VERSION
+        )));
+    }
+
+    let mut version = format_version;
+    while version < PERSIST_SNAPSHOT_FORMAT_VERSION {
+        match version {
+            0 => {
+                object
+                    .entry("created_at_unix_ms")
+                    .or_insert_with(|| serde_json::Value::from(0));
+            }
+            _ => unreachable!("missing snapshot format upgrade from version {}", version),
+        }
+        version += 1;
+        object.insert(
+            "format_version".to_string(),
+            serde_json::Value::from(version),
+        );
+    }
+
+    let snapshot = serde_json::from_value(value).map_err(decode_err)?;
+    Ok((snapshot, format_version < PERSIST_SNAPSHOT_FORMAT_VERSION))
+}
+
+struct ReplicationJob {
+    file_name: std::ffi::OsString,
+    bytes: Vec<u8>,
+}
+
+#[derive(Default)]
+struct ReplicationQueueState {
+    jobs: VecDeque<ReplicationJob>,
+    in_flight: bool,
+    closed: bool,
+}
+
+// Bounded hand-off between a managed vec and its async replication worker.
+// `work` wakes the worker; `progress` wakes blocked writers and flushers
+// whenever the worker takes or finishes a job.
+struct ReplicationQueue {
+    vec_name: String,
+    replica_roots: Vec<PathBuf>,
+    max_depth: usize,
+    overflow: PersistReplicationOverflow,
+    state: StdMutex<ReplicationQueueState>,
+    work: Notify,
+    progress: Notify,
+    dropped: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl ReplicationQueue {
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, ReplicationQueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    async fn enqueue(&self, job: ReplicationJob) {
+        loop {
+            let progress = self.progress.notified();
+            {
+                let mut state = self.lock_state();
+                let full = state.jobs.len() >= self.max_depth;
+                if !full || self.overflow == PersistReplicationOverflow::DropOldest {
+                    if full {
+                        state.jobs.pop_front();
+                        self.dropped.fetch_add(1, AtomicOrdering::Relaxed);
+                        warn!(
+                            "async snapshot replication queue full, dropped oldest snapshot: vec='{}'",
+                            self.vec_name
+                        );
+                    }
+                    state.jobs.push_back(job);
+                    break;
+                }
+            }
+            progress.await;
+        }
+        self.work.notify_one();
+    }
+
+    fn apply_to_stats(&self, stats: &mut ManagedPersistVecStats) {
+        stats.replication_failures += self.failures.load(AtomicOrdering::Relaxed);
+        stats.replication_dropped = self.dropped.load(AtomicOrdering::Relaxed);
+        stats.replication_queue_depth = self.lock_state().jobs.len();
+    }
+
+    async fn run(self: Arc<Self>) {
+        loop {
+            let (job, closed) = {
+                let mut state = self.lock_state();
+                let job = state.jobs.pop_front();
+                state.in_flight = job.is_some();
+                (job, state.closed)
+            };
+            self.progress.notify_waiters();
+
+            match job {
+                Some(job) => {
+                    for root in &self.replica_roots {
+                        if let Err(err) = atomic_write(&root.join(&job.file_name), &job.bytes).await
+                        {
+                            self.failures.fetch_add(1, AtomicOrdering::Relaxed);
+                            warn!(
+                                "async snapshot replication failed: vec='{}' replica='{}' error='{}'",
+                                self.vec_name,
+                                root.display(),
+                                err
+                            );
+                        }
+                    }
+                }
+                None if closed => break,
+                None => self.work.notified().await,
+            }
+        }
+    }
+}
+
+// Owned by the managed vec; dropping it lets the worker drain the queue and exit.
+struct ReplicationQueueHandle {
+    queue: Arc<ReplicationQueue>,
+}
+
+impl ReplicationQueueHandle {
+    fn spawn(
+        vec_name: String,
+        policy: &PersistReplicationPolicy,
+        limits: PersistReplicationQueueLimits,
+    ) -> Self {
+        let queue = Arc::new(ReplicationQueue {
+            vec_name,
+            replica_roots: policy.replica_roots.clone(),
+            max_depth: limits.max_queue_depth,
+            overflow: limits.overflow,
+            state: StdMutex::new(ReplicationQueueState::default()),
+            work: Notify::new(),
+            progress: Notify::new(),
+            dropped: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        });
+        tokio::spawn(queue.clone().run());
+        Self { queue }
+    }
+}
+
+impl Drop for ReplicationQueueHandle {
+    fn drop(&mut self) {
+        self.queue.lock_state().closed = true;
+        self.queue.work.notify_one();
+    }
 }
 
 async fn atomic_write(path: &Path, bytes: &[u8]) -> Result<()> {
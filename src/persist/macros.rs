@@ -150,6 +150,9 @@ macro_rules! persist_struct {
                     "__created_at TIMESTAMP NOT NULL".to_string(),
                     "__updated_at TIMESTAMP NOT NULL".to_string(),
                     "__last_touch_at TIMESTAMP NOT NULL".to_string(),
+                    "__labels JSON".to_string(),
+                    "__created_by TEXT".to_string(),
+                    "__updated_by TEXT".to_string(),
                 ];
                 $(
                     columns.push(format!(
@@ -373,6 +376,9 @@ macro_rules! persist_struct {
                     "__created_at".to_string(),
                     "__updated_at".to_string(),
                     "__last_touch_at".to_string(),
+                    "__labels".to_string(),
+                    "__created_by".to_string(),
+                    "__updated_by".to_string(),
                 ];
                 let mut values = vec![
                     format!(
@@ -385,7 +391,9 @@ macro_rules! persist_struct {
                     format!("'{}'", self.__metadata.created_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.updated_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.last_touch_at.to_rfc3339()),
+                    $crate::persist::persist_labels_sql_literal(&self.__metadata.labels),
                 ];
+                values.extend(self.__metadata.actor_sql_literals());
 
                 $(
                     columns.push(stringify!($field).to_string());
@@ -433,6 +441,12 @@ macro_rules! persist_struct {
                     self.__metadata.last_touch_at.to_rfc3339()
                 ));
                 set_clauses.push(format!("__touch_count = {}", self.__metadata.touch_count));
+                set_clauses.push(format!(
+                    "__labels = {}",
+                    $crate::persist::persist_labels_sql_literal(&self.__metadata.labels)
+                ));
+                let [_, updated_by] = self.__metadata.actor_sql_literals();
+                set_clauses.push(format!("__updated_by = {}", updated_by));
 
                 Some(format!(
                     "UPDATE {} SET {} WHERE __persist_id = '{}' AND __version = {}",
@@ -504,6 +518,11 @@ macro_rules! persist_struct {
                 &self.__persist_id
             }
 
+            fn set_persist_id(&mut self, persist_id: String) -> $crate::core::Result<()> {
+                self.__persist_id = persist_id;
+                Ok(())
+            }
+
             fn metadata(&self) -> &$crate::persist::PersistMetadata {
                 &self.__metadata
             }
@@ -512,6 +531,15 @@ macro_rules! persist_struct {
                 &mut self.__metadata
             }
 
+            fn set_label(&mut self, key: &str, value: &str) {
+                if self.__metadata.labels.get(key).map(String::as_str) != Some(value) {
+                    self.__metadata
+                        .labels
+                        .insert(key.to_string(), value.to_string());
+                    self.__mark_dirty("__labels");
+                }
+            }
+
             fn descriptor(&self) -> $crate::persist::ObjectDescriptor {
                 $crate::persist::ObjectDescriptor {
                     type_name: stringify!($name).to_string(),
@@ -609,7 +637,10 @@ macro_rules! persist_struct {
                 if self.__table_ready {
                     return Ok(());
                 }
-                session.execute(&self.__create_table_sql()).await?;
+                session
+                    .ensure_entity_table(&self.__table_name, &self.__create_table_sql())
+                    .await?;
+                session.ensure_persist_metadata_columns(&self.__table_name).await?;
                 let migration_plan = <Self as $crate::persist::PersistEntityFactory>::migration_plan();
                 migration_plan
                     .ensure_table_schema_version(session, &self.__table_name)
@@ -618,6 +649,16 @@ macro_rules! persist_struct {
                 Ok(())
             }
 
+            fn has_unsaved_changes(&self) -> bool {
+                !self.__metadata.persisted || !self.__dirty_fields.is_empty()
+            }
+
+            fn mark_all_dirty(&mut self) {
+                $(
+                    self.__dirty_fields.insert(stringify!($field));
+                )+
+            }
+
             async fn save(
                 &mut self,
                 session: &$crate::persist::PersistSession,
@@ -627,6 +668,7 @@ macro_rules! persist_struct {
                     .__metadata
                     .schema_version
                     .max(<Self as $crate::persist::PersistEntityFactory>::schema_version());
+                self.__metadata.record_actor(session);
                 let now = chrono::Utc::now();
 
                 if !self.__metadata.persisted {
@@ -775,6 +817,8 @@ macro_rules! persist_struct {
             }
 
             fn from_state(state: &$crate::persist::PersistState) -> $crate::core::Result<Self> {
+                let state = <Self as $crate::persist::PersistEntityFactory>::upcast_state(state)?;
+                let state = state.as_ref();
                 let fields = state
                     .fields
                     .as_object()
@@ -789,6 +833,7 @@ macro_rules! persist_struct {
                         fields
                             .get(stringify!($field))
                             .cloned()
+                            .map(<$field_ty as $crate::persist::PersistValue>::coerce_state_value)
                             .ok_or_else(|| $crate::core::DbError::ExecutionError(
                                 format!("Field '{}' missing in persisted state", stringify!($field))
                             ))?
@@ -869,6 +914,7 @@ macro_rules! persist_struct {
                                     },
                                 ],
                                 mutates_state: true,
+                                deprecated: None,
                             },
                         )+
                     ];
@@ -877,6 +923,7 @@ macro_rules! persist_struct {
                         name: "Touch".to_string(),
                         fields: Vec::new(),
                         mutates_state: true,
+                        deprecated: None,
                     });
 
                     contracts
@@ -1187,8 +1234,7 @@ macro_rules! persist_struct {
                     .is_none_or(|current| current != &value);
                 if needs_update {
                     self.__fields.insert(name.clone(), value);
-                    self.__dirty_fields.insert(name);
-                    self.touch();
+                    self.__mark_dirty(name);
                 }
 
                 Ok(needs_update)
@@ -1360,6 +1406,11 @@ macro_rules! persist_struct {
                 self.__metadata.last_touch_at = chrono::Utc::now();
             }
 
+            fn __mark_dirty(&mut self, field: impl Into<String>) {
+                self.__dirty_fields.insert(field.into());
+                self.touch();
+            }
+
             pub fn register_function<F>(&mut self, name: impl Into<String>, handler: F)
             where
                 F: Fn(
@@ -1386,6 +1437,9 @@ macro_rules! persist_struct {
                     "__created_at".to_string(),
                     "__updated_at".to_string(),
                     "__last_touch_at".to_string(),
+                    "__labels".to_string(),
+                    "__created_by".to_string(),
+                    "__updated_by".to_string(),
                 ];
                 let mut values = vec![
                     format!("'{}'", $crate::persist::sql_escape_string(&self.__persist_id)),
@@ -1395,7 +1449,9 @@ macro_rules! persist_struct {
                     format!("'{}'", self.__metadata.created_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.updated_at.to_rfc3339()),
                     format!("'{}'", self.__metadata.last_touch_at.to_rfc3339()),
+                    $crate::persist::persist_labels_sql_literal(&self.__metadata.labels),
                 ];
+                values.extend(self.__metadata.actor_sql_literals());
 
                 for field in &self.__schema.fields {
                     columns.push(field.name.clone());
@@ -1445,6 +1501,12 @@ macro_rules! persist_struct {
                     self.__metadata.last_touch_at.to_rfc3339()
                 ));
                 assignments.push(format!("__touch_count = {}", self.__metadata.touch_count));
+                assignments.push(format!(
+                    "__labels = {}",
+                    $crate::persist::persist_labels_sql_literal(&self.__metadata.labels)
+                ));
+                let [_, updated_by] = self.__metadata.actor_sql_literals();
+                assignments.push(format!("__updated_by = {}", updated_by));
 
                 Some(format!(
                     "UPDATE {} SET {} WHERE __persist_id = '{}' AND __version = {}",
@@ -1484,6 +1546,11 @@ macro_rules! persist_struct {
                 &self.__persist_id
             }
 
+            fn set_persist_id(&mut self, persist_id: String) -> $crate::core::Result<()> {
+                self.__persist_id = persist_id;
+                Ok(())
+            }
+
             fn metadata(&self) -> &$crate::persist::PersistMetadata {
                 &self.__metadata
             }
@@ -1492,6 +1559,15 @@ macro_rules! persist_struct {
                 &mut self.__metadata
             }
 
+            fn set_label(&mut self, key: &str, value: &str) {
+                if self.__metadata.labels.get(key).map(String::as_str) != Some(value) {
+                    self.__metadata
+                        .labels
+                        .insert(key.to_string(), value.to_string());
+                    self.__mark_dirty("__labels");
+                }
+            }
+
             fn descriptor(&self) -> $crate::persist::ObjectDescriptor {
                 $crate::persist::ObjectDescriptor {
                     type_name: stringify!($name).to_string(),
@@ -1589,7 +1665,15 @@ macro_rules! persist_struct {
                 if self.__table_ready {
                     return Ok(());
                 }
-                session.execute(&self.__schema.create_table_sql()).await?;
+                session
+                    .ensure_entity_table(
+                        &self.__schema.table_name,
+                        &self.__schema.create_table_sql(),
+                    )
+                    .await?;
+                session
+                    .ensure_persist_metadata_columns(&self.__schema.table_name)
+                    .await?;
                 let migration_plan = <Self as $crate::persist::PersistEntityFactory>::migration_plan();
                 migration_plan
                     .ensure_table_schema_version(session, &self.__schema.table_name)
@@ -1598,6 +1682,16 @@ macro_rules! persist_struct {
                 Ok(())
             }
 
+            fn has_unsaved_changes(&self) -> bool {
+                !self.__metadata.persisted || !self.__dirty_fields.is_empty()
+            }
+
+            fn mark_all_dirty(&mut self) {
+                for field in &self.__schema.fields {
+                    self.__dirty_fields.insert(field.name.clone());
+                }
+            }
+
             async fn save(
                 &mut self,
                 session: &$crate::persist::PersistSession,
@@ -1607,6 +1701,7 @@ macro_rules! persist_struct {
                     .__metadata
                     .schema_version
                     .max(<Self as $crate::persist::PersistEntityFactory>::schema_version());
+                self.__metadata.record_actor(session);
                 let now = chrono::Utc::now();
 
                 if !self.__metadata.persisted {
@@ -1760,6 +1855,8 @@ macro_rules! persist_struct {
             }
 
             fn from_state(state: &$crate::persist::PersistState) -> $crate::core::Result<Self> {
+                let state = <Self as $crate::persist::PersistEntityFactory>::upcast_state(state)?;
+                let state = state.as_ref();
                 let schema = Self::__schema_from_source(state.table_name.clone())?;
                 let now = chrono::Utc::now();
 
@@ -1919,11 +2016,13 @@ macro_rules! persist_struct {
                                 },
                             ],
                             mutates_state: true,
+                            deprecated: None,
                         },
                         $crate::persist::PersistCommandContract {
                             name: "Touch".to_string(),
                             fields: Vec::new(),
                             mutates_state: true,
+                            deprecated: None,
                         },
                     ]
                 }
@@ -2055,6 +2154,13 @@ macro_rules! persist_vec {
                 self.inner.snapshot(mode)
             }
 
+            pub fn snapshot_redacted(
+                &self,
+                mode: $crate::persist::SnapshotMode,
+            ) -> $crate::persist::HeteroPersistVecSnapshot {
+                self.inner.snapshot_redacted(mode)
+            }
+
             pub async fn restore(
                 &mut self,
                 snapshot: $crate::persist::HeteroPersistVecSnapshot,
@@ -2090,6 +2196,10 @@ macro_rules! persist_vec {
                 self.inner.snapshot(mode)
             }
 
+            fn redacted_snapshot(&self, mode: $crate::persist::SnapshotMode) -> Self::Snapshot {
+                self.inner.snapshot_redacted(mode)
+            }
+
             fn save_all<'a>(
                 &'a mut self,
                 session: &'a $crate::persist::PersistSession,
@@ -2121,6 +2231,21 @@ macro_rules! persist_vec {
                         .await
                 })
             }
+
+            fn ensure_snapshot_compatible(
+                &self,
+                snapshot: &Self::Snapshot,
+            ) -> $crate::core::Result<()> {
+                self.inner.ensure_snapshot_compatible(snapshot)
+            }
+
+            fn max_version(&self) -> i64 {
+                self.inner.max_version()
+            }
+
+            fn table_definitions(&self) -> Vec<$crate::persist::PersistTableDefinition> {
+                self.inner.table_definitions()
+            }
         }
     };
     ($vis:vis $name:ident, $item_ty:ty) => {
@@ -2178,6 +2303,10 @@ macro_rules! persist_vec {
                 self.inner.items_mut()
             }
 
+            pub fn find_by_label(&self, key: &str, value: &str) -> Vec<&$item_ty> {
+                self.inner.find_by_label(key, value)
+            }
+
             pub async fn ensure_all_tables(
                 &mut self,
                 session: &$crate::persist::PersistSession,
@@ -2216,6 +2345,13 @@ macro_rules! persist_vec {
                 self.inner.snapshot(mode)
             }
 
+            pub fn snapshot_redacted(
+                &self,
+                mode: $crate::persist::SnapshotMode,
+            ) -> $crate::persist::PersistVecSnapshot {
+                self.inner.snapshot_redacted(mode)
+            }
+
             pub async fn restore(
                 &mut self,
                 snapshot: $crate::persist::PersistVecSnapshot,
@@ -2268,6 +2404,10 @@ macro_rules! persist_vec {
                 self.inner.snapshot(mode)
             }
 
+            fn redacted_snapshot(&self, mode: $crate::persist::SnapshotMode) -> Self::Snapshot {
+                self.inner.snapshot_redacted(mode)
+            }
+
             fn save_all<'a>(
                 &'a mut self,
                 session: &'a $crate::persist::PersistSession,
@@ -2299,6 +2439,21 @@ macro_rules! persist_vec {
                         .await
                 })
             }
+
+            fn ensure_snapshot_compatible(
+                &self,
+                snapshot: &Self::Snapshot,
+            ) -> $crate::core::Result<()> {
+                self.inner.ensure_snapshot_compatible(snapshot)
+            }
+
+            fn max_version(&self) -> i64 {
+                self.inner.max_version()
+            }
+
+            fn table_definitions(&self) -> Vec<$crate::persist::PersistTableDefinition> {
+                self.inner.table_definitions()
+            }
         }
 
         impl $crate::persist::app::PersistIndexedCollection for $name {
@@ -2326,3 +2481,13 @@ macro_rules! persist_vec {
         }
     };
 }
+
+#[macro_export]
+macro_rules! register_models {
+    ($runtime:expr, $($persisted:ty),+ $(,)?) => {
+        $crate::PersistEntityRuntime::register_all(
+            $runtime,
+            [$(<$persisted>::runtime_model_registration()),+],
+        )
+    };
+}
@@ -1,10 +1,13 @@
+use crate::core::value::interval_text;
 use crate::core::{DbError, Result, Value};
 use crate::facade::InMemoryDB;
 use crate::transaction::TransactionId;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -15,6 +18,9 @@ mod macros;
 pub mod runtime;
 
 pub const PERSIST_SCHEMA_REGISTRY_TABLE: &str = "__persist_schema_versions";
+pub const PERSIST_SNAPSHOT_FORMAT_VERSION: u16 = 1;
+/// Placeholder written over `#[sql(redact)]` fields in redacted snapshots.
+pub const PERSIST_REDACTED_VALUE: &str = "[REDACTED]";
 
 pub const fn default_schema_version() -> u32 {
     1
@@ -24,6 +30,9 @@ pub const fn default_schema_version() -> u32 {
 pub struct PersistSession {
     db: Arc<Mutex<InMemoryDB>>,
     transaction_id: Option<TransactionId>,
+    actor: Option<String>,
+    lazy_tables: bool,
+    command_limits: Arc<PersistCommandRateLimiter>,
 }
 
 impl PersistSession {
@@ -31,6 +40,9 @@ impl PersistSession {
         Self {
             db: Arc::new(Mutex::new(db)),
             transaction_id: None,
+            actor: None,
+            lazy_tables: true,
+            command_limits: Arc::default(),
         }
     }
 
@@ -38,6 +50,9 @@ impl PersistSession {
         Self {
             db,
             transaction_id: None,
+            actor: None,
+            lazy_tables: true,
+            command_limits: Arc::default(),
         }
     }
 
@@ -49,6 +64,9 @@ impl PersistSession {
         Self {
             db: self.db.clone(),
             transaction_id: Some(transaction_id),
+            actor: self.actor.clone(),
+            lazy_tables: self.lazy_tables,
+            command_limits: self.command_limits.clone(),
         }
     }
 
@@ -56,15 +74,100 @@ impl PersistSession {
         self.transaction_id
     }
 
+    /// Returns a session whose saves record `actor` as `created_by`/`updated_by`.
+    pub fn with_actor(&self, actor: impl Into<String>) -> Self {
+        Self {
+            db: self.db.clone(),
+            transaction_id: self.transaction_id,
+            actor: Some(actor.into()),
+            lazy_tables: self.lazy_tables,
+            command_limits: self.command_limits.clone(),
+        }
+    }
+
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Returns a session whose saves create missing entity tables on first
+    /// write (`true`, the default) or fail because the table is missing.
+    pub fn with_lazy_tables(&self, lazy_tables: bool) -> Self {
+        Self {
+            db: self.db.clone(),
+            transaction_id: self.transaction_id,
+            actor: self.actor.clone(),
+            lazy_tables,
+            command_limits: self.command_limits.clone(),
+        }
+    }
+
+    pub fn lazy_tables(&self) -> bool {
+        self.lazy_tables
+    }
+
+    /// `#[command(min_interval_ms = ...)]` limits of this session and every
+    /// session derived from it, such as the sessions of one `PersistApp`.
+    pub fn command_limits(&self) -> &PersistCommandRateLimiter {
+        &self.command_limits
+    }
+
+    /// Runs `create_table_sql` when lazy table creation is enabled; otherwise
+    /// only checks that `table_name` already exists.
+    pub async fn ensure_entity_table(
+        &self,
+        table_name: &str,
+        create_table_sql: &str,
+    ) -> Result<()> {
+        if self.lazy_tables {
+            self.execute(create_table_sql).await?;
+            return Ok(());
+        }
+        if !self.table_exists(table_name).await {
+            return Err(DbError::ExecutionError(format!(
+                "Table '{}' does not exist and lazy table creation is disabled; create it at boot with PersistApp::create_all_tables",
+                table_name
+            )));
+        }
+        Ok(())
+    }
+
     pub async fn execute(&self, sql: &str) -> Result<crate::result::QueryResult> {
         let mut db = self.db.lock().await;
         db.execute_with_transaction(sql, self.transaction_id).await
     }
 
+    /// Runs `statements` in order so that they apply together or not at all:
+    /// inside the session's transaction when it has one, otherwise inside a
+    /// new transaction that is committed after the last statement.
+    pub async fn execute_in_transaction(
+        &self,
+        statements: &[String],
+    ) -> Result<Vec<crate::result::QueryResult>> {
+        if statements.len() < 2 || self.transaction_id.is_some() {
+            let mut results = Vec::with_capacity(statements.len());
+            for sql in statements {
+                results.push(self.execute(sql).await?);
+            }
+            return Ok(results);
+        }
+        self.with_transaction(|tx_session| async move {
+            let mut results = Vec::with_capacity(statements.len());
+            for sql in statements {
+                results.push(tx_session.execute(sql).await?);
+            }
+            Ok(results)
+        })
+        .await
+    }
+
     pub async fn query(&self, sql: &str) -> Result<crate::result::QueryResult> {
         self.execute(sql).await
     }
 
+    pub async fn table_exists(&self, table_name: &str) -> bool {
+        self.db.lock().await.table_exists(table_name)
+    }
+
     pub async fn persist_row_exists(&self, table_name: &str, persist_id: &str) -> Result<bool> {
         let sql = format!(
             "SELECT __persist_id FROM {} WHERE __persist_id = '{}'",
@@ -75,6 +178,95 @@ impl PersistSession {
         Ok(result.row_count() > 0)
     }
 
+    pub async fn load_persist_state(
+        &self,
+        table_name: &str,
+        persist_id: &str,
+        type_name: &str,
+    ) -> Result<Option<PersistState>> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE __persist_id = '{}'",
+            table_name,
+            sql_escape_string(persist_id)
+        );
+        let result = self.query(&sql).await?;
+        let Some(row) = result.rows().first() else {
+            return Ok(None);
+        };
+
+        let mut metadata = PersistMetadata::new(Utc::now());
+        metadata.persisted = true;
+        let mut fields = serde_json::Map::new();
+        for (column, value) in result.columns().iter().zip(row.iter()) {
+            let name = column.name.rsplit('.').next().unwrap_or(&column.name);
+            match (name, value) {
+                ("__version", Value::Integer(version)) => metadata.version = *version,
+                ("__schema_version", Value::Integer(version)) => {
+                    metadata.schema_version = (*version).max(0) as u32
+                }
+                ("__touch_count", Value::Integer(count)) => {
+                    metadata.touch_count = (*count).max(0) as u64
+                }
+                ("__created_at", Value::Timestamp(at)) => metadata.created_at = *at,
+                ("__updated_at", Value::Timestamp(at)) => metadata.updated_at = *at,
+                ("__last_touch_at", Value::Timestamp(at)) => metadata.last_touch_at = *at,
+                ("__labels", value) => metadata.labels = persist_labels_from_value(value),
+                ("__created_by", Value::Text(actor)) => metadata.created_by = Some(actor.clone()),
+                ("__updated_by", Value::Text(actor)) => metadata.updated_by = Some(actor.clone()),
+                (name, _) if name.starts_with("__") => {}
+                (name, value) => {
+                    fields.insert(name.to_string(), value_to_state_json(value));
+                }
+            }
+        }
+
+        Ok(Some(PersistState {
+            persist_id: persist_id.to_string(),
+            type_name: type_name.to_string(),
+            table_name: table_name.to_string(),
+            metadata,
+            fields: serde_json::Value::Object(fields),
+        }))
+    }
+
+    pub async fn ensure_persist_labels_column(&self, table_name: &str) -> Result<()> {
+        let has_labels = {
+            let db = self.db.lock().await;
+            let schema = db.get_table_schema(table_name).await?;
+            schema.schema().find_column_index("__labels").is_some()
+        };
+        if !has_labels {
+            self.execute(&format!(
+                "ALTER TABLE {} ADD COLUMN __labels JSON",
+                table_name
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Adds the `__labels` and `__created_by`/`__updated_by` columns to tables
+    /// created before they existed.
+    pub async fn ensure_persist_metadata_columns(&self, table_name: &str) -> Result<()> {
+        self.ensure_persist_labels_column(table_name).await?;
+        let missing = {
+            let db = self.db.lock().await;
+            let schema = db.get_table_schema(table_name).await?;
+            ["__created_by", "__updated_by"]
+                .into_iter()
+                .filter(|column| schema.schema().find_column_index(column).is_none())
+                .collect::<Vec<_>>()
+        };
+        for column in missing {
+            self.execute(&format!(
+                "ALTER TABLE {} ADD COLUMN {} TEXT",
+                table_name, column
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
     pub async fn delete_persist_row(&self, table_name: &str, persist_id: &str) -> Result<()> {
         let sql = format!(
             "DELETE FROM {} WHERE __persist_id = '{}'",
@@ -196,7 +388,7 @@ impl PersistSession {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PersistMetadata {
     pub version: i64,
     #[serde(default = "default_schema_version")]
@@ -206,6 +398,12 @@ pub struct PersistMetadata {
     pub last_touch_at: DateTime<Utc>,
     pub touch_count: u64,
     pub persisted: bool,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub created_by: Option<String>,
+    #[serde(default)]
+    pub updated_by: Option<String>,
 }
 
 impl PersistMetadata {
@@ -218,7 +416,29 @@ impl PersistMetadata {
             last_touch_at: now,
             touch_count: 0,
             persisted: false,
+            labels: BTreeMap::new(),
+            created_by: None,
+            updated_by: None,
+        }
+    }
+
+    /// Records the session actor: both fields on first insert, `updated_by`
+    /// afterwards. Saves without an actor leave the fields unchanged.
+    pub fn record_actor(&mut self, session: &PersistSession) {
+        let Some(actor) = session.actor() else {
+            return;
+        };
+        if !self.persisted {
+            self.created_by = Some(actor.to_string());
         }
+        self.updated_by = Some(actor.to_string());
+    }
+
+    pub fn actor_sql_literals(&self) -> [String; 2] {
+        [&self.created_by, &self.updated_by].map(|actor| match actor {
+            Some(actor) => format!("'{}'", sql_escape_string(actor)),
+            None => "NULL".to_string(),
+        })
     }
 }
 
@@ -245,7 +465,56 @@ pub struct PersistState {
     pub fields: serde_json::Value,
 }
 
+/// Structural equality over `persist_id`, `type_name`, `table_name`, `fields`
+/// and the metadata `version`, `schema_version` and `labels`. Volatile metadata
+/// (`created_at`, `updated_at`, `last_touch_at`, `touch_count`, `persisted`,
+/// `created_by`, `updated_by`) is ignored; see `eq_with_metadata`.
+impl PartialEq for PersistState {
+    fn eq(&self, other: &Self) -> bool {
+        self.persist_id == other.persist_id
+            && self.type_name == other.type_name
+            && self.table_name == other.table_name
+            && self.metadata.version == other.metadata.version
+            && self.metadata.schema_version == other.metadata.schema_version
+            && self.metadata.labels == other.metadata.labels
+            && self.fields == other.fields
+    }
+}
+
+impl Eq for PersistState {}
+
+impl Hash for PersistState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.persist_id.hash(state);
+        self.type_name.hash(state);
+        self.table_name.hash(state);
+        self.metadata.version.hash(state);
+        self.metadata.schema_version.hash(state);
+        self.metadata.labels.hash(state);
+        state_json_checksum(&self.fields).hash(state);
+    }
+}
+
 impl PersistState {
+    /// Like `==`, but also compares every metadata field, timestamps included.
+    pub fn eq_with_metadata(&self, other: &Self) -> bool {
+        self == other && self.metadata == other.metadata
+    }
+
+    /// Replaces every non-null value of `fields` with `PERSIST_REDACTED_VALUE`.
+    pub fn redact_fields(&mut self, fields: &[&str]) {
+        let Some(object) = self.fields.as_object_mut() else {
+            return;
+        };
+        for field in fields {
+            if let Some(value) = object.get_mut(*field)
+                && !value.is_null()
+            {
+                *value = serde_json::Value::String(PERSIST_REDACTED_VALUE.to_string());
+            }
+        }
+    }
+
     pub fn fields_object(&self) -> Result<&serde_json::Map<String, serde_json::Value>> {
         self.fields.as_object().ok_or_else(|| {
             DbError::ExecutionError("Persist state fields must be a JSON object".to_string())
@@ -294,6 +563,9 @@ impl DynamicSchema {
             "__created_at TIMESTAMP NOT NULL".to_string(),
             "__updated_at TIMESTAMP NOT NULL".to_string(),
             "__last_touch_at TIMESTAMP NOT NULL".to_string(),
+            "__labels JSON".to_string(),
+            "__created_by TEXT".to_string(),
+            "__updated_by TEXT".to_string(),
         ];
 
         for field in &self.fields {
@@ -334,13 +606,33 @@ pub enum SnapshotMode {
     WithData,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub type PersistStateMergeFn = fn(&PersistState, &PersistState) -> PersistState;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum RestoreConflictPolicy {
     FailFast,
     SkipExisting,
     OverwriteExisting,
+    /// Reconciles a conflicting row as `merge(existing, incoming)`.
+    ///
+    /// Conflicts are merged one at a time in snapshot order. The function must be
+    /// deterministic and keep the incoming `persist_id`; the merged state replaces
+    /// the stored row.
+    #[serde(skip)]
+    Merge(PersistStateMergeFn),
+}
+
+impl PartialEq for RestoreConflictPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Merge(left), Self::Merge(right)) => std::ptr::fn_addr_eq(*left, *right),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
 }
 
+impl Eq for RestoreConflictPolicy {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistVecSnapshot {
     pub format_version: u16,
@@ -645,19 +937,134 @@ impl PersistMigrationPlan {
     }
 }
 
+/// DDL needed to store one entity type in its default table.
+#[derive(Debug, Clone)]
+pub struct PersistTableDefinition {
+    pub type_name: String,
+    pub table_name: String,
+    pub create_table_sql: String,
+    pub migration_plan: PersistMigrationPlan,
+}
+
+impl PersistTableDefinition {
+    pub fn of<T: PersistEntityFactory>() -> Self {
+        let table_name = T::default_table_name();
+        Self {
+            type_name: T::entity_type_name().to_string(),
+            create_table_sql: T::create_table_sql(&table_name),
+            table_name,
+            migration_plan: T::migration_plan(),
+        }
+    }
+
+    /// Creates the table, its metadata columns and its schema version record.
+    /// Idempotent.
+    pub async fn create(&self, session: &PersistSession) -> Result<()> {
+        session.execute(&self.create_table_sql).await?;
+        session
+            .ensure_persist_metadata_columns(&self.table_name)
+            .await?;
+        self.migration_plan
+            .ensure_table_schema_version(session, &self.table_name)
+            .await
+    }
+
+    /// The migration the table still needs, or `None` when it is missing,
+    /// current or ahead of the plan.
+    pub async fn pending_migration(
+        &self,
+        session: &PersistSession,
+    ) -> Result<Option<PersistPendingMigration>> {
+        let to_version = self.migration_plan.current_version();
+        Ok(
+            match session.get_table_schema_version(&self.table_name).await? {
+                Some(from_version) if from_version < to_version => Some(PersistPendingMigration {
+                    type_name: self.type_name.clone(),
+                    table_name: self.table_name.clone(),
+                    from_version,
+                    to_version,
+                }),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// A table whose recorded schema version is behind its type's migration plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistPendingMigration {
+    pub type_name: String,
+    pub table_name: String,
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
 #[async_trait]
 pub trait PersistEntity: Send + Sync {
     fn type_name(&self) -> &'static str;
     fn table_name(&self) -> &str;
     fn persist_id(&self) -> &str;
+    /// Replaces the id of an entity that has not been saved yet; used when a
+    /// `ManagedPersistVec` assigns ids from its `PersistIdStrategy`.
+    fn set_persist_id(&mut self, _persist_id: String) -> Result<()> {
+        Err(DbError::ExecutionError(format!(
+            "Entity type '{}' does not support assigning persist ids",
+            self.type_name()
+        )))
+    }
+    /// Fields marked `#[persist(unique_index)]`; `ManagedPersistVec` rejects
+    /// an insert or replacement that duplicates one of their values.
+    fn unique_index_fields(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
     fn metadata(&self) -> &PersistMetadata;
     fn metadata_mut(&mut self) -> &mut PersistMetadata;
+    fn get_label(&self, key: &str) -> Option<&str> {
+        self.metadata().labels.get(key).map(String::as_str)
+    }
+    /// Implementations without per-field dirty tracking rely on the default,
+    /// which marks every field dirty so the label reaches the next `save`.
+    fn set_label(&mut self, key: &str, value: &str) {
+        if self.get_label(key) != Some(value) {
+            self.metadata_mut()
+                .labels
+                .insert(key.to_string(), value.to_string());
+            self.mark_all_dirty();
+        }
+    }
     fn descriptor(&self) -> ObjectDescriptor;
     fn state(&self) -> PersistState;
+    fn state_checksum(&self) -> u64 {
+        state_json_checksum(&self.state().fields)
+    }
     fn supports_function(&self, function: &str) -> bool;
     fn available_functions(&self) -> Vec<FunctionDescriptor>;
     async fn ensure_table(&mut self, session: &PersistSession) -> Result<()>;
+    /// True when the entity was never saved or has dirty fields. Without
+    /// dirty tracking the default answers `true`, so `save_if_changed` always
+    /// saves.
+    fn has_unsaved_changes(&self) -> bool {
+        true
+    }
+    /// Marks every field dirty so the next `save` rewrites all columns. The
+    /// default does nothing, for implementations whose `save` always writes
+    /// every column.
+    fn mark_all_dirty(&mut self) {}
     async fn save(&mut self, session: &PersistSession) -> Result<()>;
+    /// Saves only when `has_unsaved_changes()`; returns whether SQL ran.
+    async fn save_if_changed(&mut self, session: &PersistSession) -> Result<bool> {
+        if !self.has_unsaved_changes() {
+            return Ok(false);
+        }
+        self.save(session).await?;
+        Ok(true)
+    }
+    /// Writes every field regardless of dirty state, e.g. to backfill
+    /// columns after a migration.
+    async fn force_save(&mut self, session: &PersistSession) -> Result<()> {
+        self.mark_all_dirty();
+        self.save(session).await
+    }
     async fn delete(&mut self, session: &PersistSession) -> Result<()>;
     async fn invoke(
         &mut self,
@@ -678,10 +1085,54 @@ pub trait PersistEntityFactory: PersistEntity + Sized {
         default_schema_version()
     }
 
+    /// State fields masked by `PersistVec::snapshot_redacted`.
+    fn redacted_fields() -> &'static [&'static str] {
+        &[]
+    }
+
     fn migration_plan() -> PersistMigrationPlan {
         PersistMigrationPlan::new(Self::schema_version())
     }
 
+    /// Runs the migration plan's state migrators on a state older than
+    /// `schema_version()` so `from_state` sees fields added since it was written.
+    fn upcast_state(state: &PersistState) -> Result<Cow<'_, PersistState>> {
+        let version = state.metadata.schema_version;
+        if version == 0 || version >= Self::schema_version() {
+            return Ok(Cow::Borrowed(state));
+        }
+
+        let plan = Self::migration_plan();
+        if plan.steps().is_empty() {
+            return Ok(Cow::Borrowed(state));
+        }
+
+        let mut upcast = state.clone();
+        plan.migrate_state_to_current(&mut upcast)?;
+        Ok(Cow::Owned(upcast))
+    }
+
+    /// Batch form of `from_state`: the migration plan is built once for the
+    /// whole batch and older states are upcast in place. Each state yields its
+    /// own result, in input order, so one bad row does not fail the batch.
+    fn from_states(states: Vec<PersistState>) -> Vec<Result<Self>> {
+        let current_version = Self::schema_version();
+        let mut plan = None;
+        states
+            .into_iter()
+            .map(|mut state| {
+                let version = state.metadata.schema_version;
+                if version != 0 && version < current_version {
+                    let plan = plan.get_or_insert_with(Self::migration_plan);
+                    if !plan.steps().is_empty() {
+                        plan.migrate_state_to_current(&mut state)?;
+                    }
+                }
+                Self::from_state(&state)
+            })
+            .collect()
+    }
+
     async fn restore_into_db(&mut self, session: &PersistSession) -> Result<()> {
         self.save(session).await
     }
@@ -712,6 +1163,8 @@ pub struct PersistCommandContract {
     pub name: String,
     pub fields: Vec<PersistCommandFieldContract>,
     pub mutates_state: bool,
+    /// Set by `#[command(deprecated = "...")]`; a bare `deprecated` yields an empty note.
+    pub deprecated: Option<String>,
 }
 
 pub trait PersistCommandModel: PersistEntity + Sized {
@@ -767,6 +1220,18 @@ impl<T: PersistEntityFactory> PersistVec<T> {
         self.items.is_empty()
     }
 
+    pub fn max_version(&self) -> i64 {
+        self.items
+            .iter()
+            .map(|item| item.metadata().version)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn table_definitions(&self) -> Vec<PersistTableDefinition> {
+        vec![PersistTableDefinition::of::<T>()]
+    }
+
     pub fn items(&self) -> &[T] {
         &self.items
     }
@@ -775,6 +1240,13 @@ impl<T: PersistEntityFactory> PersistVec<T> {
         &mut self.items
     }
 
+    pub fn find_by_label(&self, key: &str, value: &str) -> Vec<&T> {
+        self.items
+            .iter()
+            .filter(|item| item.get_label(key) == Some(value))
+            .collect()
+    }
+
     pub fn add_one(&mut self, item: T) {
         self.items.push(item);
     }
@@ -903,7 +1375,7 @@ impl<T: PersistEntityFactory> PersistVec<T> {
             .unwrap_or_else(T::default_table_name);
 
         PersistVecSnapshot {
-            format_version: 1,
+            format_version: PERSIST_SNAPSHOT_FORMAT_VERSION,
             created_at_unix_ms: Utc::now().timestamp_millis(),
             mode: mode.clone(),
             vec_name: self.name.clone(),
@@ -922,6 +1394,48 @@ impl<T: PersistEntityFactory> PersistVec<T> {
         }
     }
 
+    /// Like `snapshot`, with `T::redacted_fields()` masked in every state.
+    /// Meant for sharing data; restore backups from `snapshot` instead.
+    pub fn snapshot_redacted(&self, mode: SnapshotMode) -> PersistVecSnapshot {
+        let mut snapshot = self.snapshot(mode);
+        for state in &mut snapshot.states {
+            state.redact_fields(T::redacted_fields());
+        }
+        snapshot
+    }
+
+    pub fn ensure_snapshot_compatible(&self, snapshot: &PersistVecSnapshot) -> Result<()> {
+        if snapshot.object_type != T::entity_type_name() {
+            return Err(DbError::ExecutionError(format!(
+                "Snapshot '{}' holds '{}' entities, expected '{}'",
+                snapshot.vec_name,
+                snapshot.object_type,
+                T::entity_type_name()
+            )));
+        }
+
+        let migration_plan = T::migration_plan();
+        migration_plan.validate()?;
+        let state_versions = snapshot
+            .states
+            .iter()
+            .map(|state| state.metadata.schema_version)
+            .filter(|version| *version != 0);
+        for version in std::iter::once(snapshot.schema_version).chain(state_versions) {
+            migration_plan.resolve_chain(version).map_err(|err| {
+                DbError::ExecutionError(format!(
+                    "Snapshot '{}' schema version {} is incompatible with '{}' schema version {}: {}",
+                    snapshot.vec_name,
+                    version,
+                    T::entity_type_name(),
+                    migration_plan.current_version(),
+                    err
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
     pub async fn restore(
         &mut self,
         snapshot: PersistVecSnapshot,
@@ -955,7 +1469,9 @@ impl<T: PersistEntityFactory> PersistVec<T> {
     ) -> Result<()> {
         migration_plan.validate()?;
         let create_sql = T::create_table_sql(&snapshot.table_name);
-        session.execute(&create_sql).await?;
+        session
+            .ensure_entity_table(&snapshot.table_name, &create_sql)
+            .await?;
         migration_plan
             .ensure_table_schema_version(session, &snapshot.table_name)
             .await?;
@@ -991,6 +1507,12 @@ impl<T: PersistEntityFactory> PersistVec<T> {
                                 .delete_persist_row(&state.table_name, &state.persist_id)
                                 .await?;
                         }
+                        RestoreConflictPolicy::Merge(merge) => {
+                            state = merge_restore_conflict(session, state, merge).await?;
+                            session
+                                .delete_persist_row(&state.table_name, &state.persist_id)
+                                .await?;
+                        }
                     }
                 }
 
@@ -1017,6 +1539,7 @@ struct PersistTypeRegistration {
     from_state: DynamicFromState,
     migration_plan: DynamicMigrationPlan,
     schema_version: DynamicSchemaVersion,
+    redacted_fields: &'static [&'static str],
 }
 
 pub struct HeteroPersistVec {
@@ -1046,6 +1569,33 @@ impl HeteroPersistVec {
         self.items.is_empty()
     }
 
+    pub fn max_version(&self) -> i64 {
+        self.items
+            .iter()
+            .map(|item| item.metadata().version)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One definition per registered type, ordered by type name.
+    pub fn table_definitions(&self) -> Vec<PersistTableDefinition> {
+        let mut type_names = self.registrations.keys().collect::<Vec<_>>();
+        type_names.sort();
+        type_names
+            .into_iter()
+            .map(|type_name| {
+                let registration = &self.registrations[type_name];
+                let table_name = (registration.default_table_name)();
+                PersistTableDefinition {
+                    type_name: type_name.clone(),
+                    create_table_sql: (registration.create_table_sql)(&table_name),
+                    table_name,
+                    migration_plan: (registration.migration_plan)(),
+                }
+            })
+            .collect()
+    }
+
     pub fn registered_types(&self) -> Vec<String> {
         let mut names = self.registrations.keys().cloned().collect::<Vec<_>>();
         names.sort();
@@ -1076,6 +1626,7 @@ impl HeteroPersistVec {
             }),
             migration_plan: Arc::new(move || plan_clone.clone()),
             schema_version: Arc::new(move || schema_version),
+            redacted_fields: T::redacted_fields(),
         };
         self.registrations.insert(type_name, registration);
     }
@@ -1251,7 +1802,7 @@ impl HeteroPersistVec {
         types.sort_by(|a, b| a.type_name.cmp(&b.type_name));
 
         HeteroPersistVecSnapshot {
-            format_version: 1,
+            format_version: PERSIST_SNAPSHOT_FORMAT_VERSION,
             created_at_unix_ms: Utc::now().timestamp_millis(),
             mode: mode.clone(),
             vec_name: self.name.clone(),
@@ -1264,6 +1815,52 @@ impl HeteroPersistVec {
         }
     }
 
+    /// Like `snapshot`, with each registered type's `redacted_fields()` masked.
+    /// Meant for sharing data; restore backups from `snapshot` instead.
+    pub fn snapshot_redacted(&self, mode: SnapshotMode) -> HeteroPersistVecSnapshot {
+        let mut snapshot = self.snapshot(mode);
+        for state in &mut snapshot.states {
+            if let Some(registration) = self.registrations.get(&state.type_name) {
+                state.redact_fields(registration.redacted_fields);
+            }
+        }
+        snapshot
+    }
+
+    pub fn ensure_snapshot_compatible(&self, snapshot: &HeteroPersistVecSnapshot) -> Result<()> {
+        let type_versions = snapshot
+            .types
+            .iter()
+            .map(|item| (item.type_name.as_str(), item.schema_version));
+        let state_versions = snapshot
+            .states
+            .iter()
+            .filter(|state| state.metadata.schema_version != 0)
+            .map(|state| (state.type_name.as_str(), state.metadata.schema_version));
+
+        for (type_name, version) in type_versions.chain(state_versions) {
+            let registration = self.registrations.get(type_name).ok_or_else(|| {
+                DbError::ExecutionError(format!(
+                    "Type '{}' is not registered for hetero restore",
+                    type_name
+                ))
+            })?;
+            let migration_plan = (registration.migration_plan)();
+            migration_plan.validate()?;
+            migration_plan.resolve_chain(version).map_err(|err| {
+                DbError::ExecutionError(format!(
+                    "Snapshot '{}' schema version {} is incompatible with '{}' schema version {}: {}",
+                    snapshot.vec_name,
+                    version,
+                    type_name,
+                    migration_plan.current_version(),
+                    err
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
     pub async fn restore(
         &mut self,
         snapshot: HeteroPersistVecSnapshot,
@@ -1301,7 +1898,7 @@ impl HeteroPersistVec {
             })?;
 
             let ddl = (registration.create_table_sql)(table_name);
-            session.execute(&ddl).await?;
+            session.ensure_entity_table(table_name, &ddl).await?;
             let migration_plan = (registration.migration_plan)();
             migration_plan
                 .ensure_table_schema_version(session, table_name)
@@ -1353,6 +1950,12 @@ impl HeteroPersistVec {
                                 .delete_persist_row(&state.table_name, &state.persist_id)
                                 .await?;
                         }
+                        RestoreConflictPolicy::Merge(merge) => {
+                            state = merge_restore_conflict(session, state, merge).await?;
+                            session
+                                .delete_persist_row(&state.table_name, &state.persist_id)
+                                .await?;
+                        }
                     }
                 }
 
@@ -1366,6 +1969,32 @@ impl HeteroPersistVec {
     }
 }
 
+async fn merge_restore_conflict(
+    session: &PersistSession,
+    incoming: PersistState,
+    merge: PersistStateMergeFn,
+) -> Result<PersistState> {
+    let Some(existing) = session
+        .load_persist_state(
+            &incoming.table_name,
+            &incoming.persist_id,
+            &incoming.type_name,
+        )
+        .await?
+    else {
+        return Ok(incoming);
+    };
+
+    let merged = merge(&existing, &incoming);
+    if merged.persist_id != incoming.persist_id {
+        return Err(DbError::ExecutionError(format!(
+            "Restore merge must keep persist_id {} (got {})",
+            incoming.persist_id, merged.persist_id
+        )));
+    }
+    Ok(merged)
+}
+
 pub fn default_table_name(type_name: &str, line: u32, column: u32) -> String {
     let mut sanitized = String::with_capacity(type_name.len());
     for ch in type_name.chars() {
@@ -1379,6 +2008,63 @@ pub fn default_table_name(type_name: &str, line: u32, column: u32) -> String {
     format!("persist_{}_{}_{}", sanitized, line, column)
 }
 
+/// Default table of a generic model: each instantiation gets its own table,
+/// named after the model and its type arguments without module paths, so
+/// `Wrapper<i64>` maps to `persist_wrapper_i64`.
+pub fn default_table_name_generic(type_name: &str, type_args: &[&str]) -> String {
+    let mut name = type_name.to_string();
+    for type_arg in type_args {
+        name.push('_');
+        push_short_type_name(&mut name, type_arg);
+    }
+    default_table_name_stable(&name)
+}
+
+/// Type name of one instantiation of a generic model, e.g. `MoneyModel<i64>`.
+/// Each distinct name is allocated once and kept for the life of the process.
+pub fn generic_type_name(type_name: &str, type_args: &[&str]) -> &'static str {
+    static NAMES: std::sync::LazyLock<std::sync::Mutex<HashSet<&'static str>>> =
+        std::sync::LazyLock::new(Default::default);
+
+    let mut name = format!("{}<", type_name);
+    for (index, type_arg) in type_args.iter().enumerate() {
+        if index > 0 {
+            name.push_str(", ");
+        }
+        push_short_type_name(&mut name, type_arg);
+    }
+    name.push('>');
+
+    let mut names = NAMES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(existing) = names.get(name.as_str()) {
+        return existing;
+    }
+    let name: &'static str = Box::leak(name.into_boxed_str());
+    names.insert(name);
+    name
+}
+
+// Appends `type_arg` with module paths dropped: `alloc::string::String` -> `String`.
+fn push_short_type_name(out: &mut String, type_arg: &str) {
+    let mut segment = String::new();
+    let mut chars = type_arg.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch.is_ascii_alphanumeric() || ch == '_' {
+            segment.push(ch);
+        } else if ch == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else {
+            out.push_str(&segment);
+            segment.clear();
+            out.push(ch);
+        }
+    }
+    out.push_str(&segment);
+}
+
 pub fn default_table_name_stable(type_name: &str) -> String {
     let mut sanitized = String::with_capacity(type_name.len());
     for ch in type_name.chars() {
@@ -1392,8 +2078,124 @@ pub fn default_table_name_stable(type_name: &str) -> String {
     format!("persist_{}", sanitized)
 }
 
+/// How `PersistApp` / `ManagedPersistVec` and `PersistEntityRuntime` generate
+/// ids for the entities they create.
+#[derive(Clone, Default)]
+pub enum PersistIdStrategy {
+    /// Random UUIDv4 (the default)
+    #[default]
+    Uuid4,
+    /// Time-ordered UUIDv7, which keeps inserts close together in indexes
+    Uuid7,
+    /// Time-ordered 26-character ULID (Crockford base32)
+    Ulid,
+    Custom(Arc<dyn Fn() -> String + Send + Sync>),
+}
+
+impl PersistIdStrategy {
+    pub fn custom<F>(generator: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(generator))
+    }
+
+    pub fn generate(&self) -> String {
+        match self {
+            Self::Uuid4 => Uuid::new_v4().to_string(),
+            Self::Uuid7 => Uuid::now_v7().to_string(),
+            Self::Ulid => new_ulid(Utc::now().timestamp_millis().max(0) as u64),
+            Self::Custom(generator) => generator(),
+        }
+    }
+}
+
+impl std::fmt::Debug for PersistIdStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uuid4 => write!(f, "Uuid4"),
+            Self::Uuid7 => write!(f, "Uuid7"),
+            Self::Ulid => write!(f, "Ulid"),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+fn new_ulid(timestamp_ms: u64) -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    // The last five bytes of a v4 UUID are fully random; two of them give the
+    // 80 random bits of a ULID.
+    let mut random = 0u128;
+    for _ in 0..2 {
+        let bytes = Uuid::new_v4().into_bytes();
+        for byte in &bytes[11..] {
+            random = (random << 8) | u128::from(*byte);
+        }
+    }
+    let value = (u128::from(timestamp_ms & 0xFFFF_FFFF_FFFF) << 80) | random;
+
+    (0..26)
+        .rev()
+        .map(|index| ALPHABET[((value >> (index * 5)) & 0x1F) as usize] as char)
+        .collect()
+}
+
 pub fn new_persist_id() -> String {
-    Uuid::new_v4().to_string()
+    PersistIdStrategy::default().generate()
+}
+
+/// Stable FNV-1a hash of a JSON value with object keys visited in sorted order,
+/// so equal states hash equally across runs and map implementations.
+pub fn state_json_checksum(value: &serde_json::Value) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    fn feed(hash: &mut u64, bytes: &[u8]) {
+        for byte in bytes {
+            *hash ^= u64::from(*byte);
+            *hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn visit(hash: &mut u64, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::Null => feed(hash, b"n"),
+            serde_json::Value::Bool(true) => feed(hash, b"t"),
+            serde_json::Value::Bool(false) => feed(hash, b"f"),
+            serde_json::Value::Number(number) => {
+                feed(hash, b"d");
+                feed(hash, number.to_string().as_bytes());
+            }
+            serde_json::Value::String(text) => {
+                feed(hash, b"s");
+                feed(hash, &(text.len() as u64).to_le_bytes());
+                feed(hash, text.as_bytes());
+            }
+            serde_json::Value::Array(items) => {
+                feed(hash, b"[");
+                for item in items {
+                    visit(hash, item);
+                }
+                feed(hash, b"]");
+            }
+            serde_json::Value::Object(map) => {
+                let mut keys = map.keys().collect::<Vec<_>>();
+                keys.sort();
+                feed(hash, b"{");
+                for key in keys {
+                    feed(hash, &(key.len() as u64).to_le_bytes());
+                    feed(hash, key.as_bytes());
+                    visit(hash, &map[key]);
+                }
+                feed(hash, b"}");
+            }
+        }
+    }
+
+    let mut hash = FNV_OFFSET;
+    visit(&mut hash, value);
+    hash
 }
 
 pub fn sql_escape_string(value: &str) -> String {
@@ -1405,6 +2207,11 @@ pub trait PersistValue:
 {
     fn sql_type() -> &'static str;
     fn to_sql_literal(&self) -> String;
+    /// Converts a column value from a state loaded straight from the table
+    /// into the shape `from_state` deserializes; values are kept by default.
+    fn coerce_state_value(value: serde_json::Value) -> serde_json::Value {
+        value
+    }
 }
 
 impl PersistValue for i64 {
@@ -1521,6 +2328,46 @@ impl PersistValue for NaiveDate {
     }
 }
 
+// Durations are stored as BIGINT milliseconds so they sort and index by
+// length. The column truncates sub-millisecond precision and clamps to the
+// i64 range; `state()`/`from_state` keep the exact value, and states loaded
+// from the table read the milliseconds back.
+impl PersistValue for std::time::Duration {
+    fn sql_type() -> &'static str {
+        "BIGINT"
+    }
+
+    fn to_sql_literal(&self) -> String {
+        i64::try_from(self.as_millis())
+            .unwrap_or(i64::MAX)
+            .to_string()
+    }
+
+    fn coerce_state_value(value: serde_json::Value) -> serde_json::Value {
+        match value.as_u64() {
+            Some(millis) => serde_json::to_value(Self::from_millis(millis)).unwrap_or(value),
+            None => value,
+        }
+    }
+}
+
+impl PersistValue for chrono::Duration {
+    fn sql_type() -> &'static str {
+        "BIGINT"
+    }
+
+    fn to_sql_literal(&self) -> String {
+        self.num_milliseconds().to_string()
+    }
+
+    fn coerce_state_value(value: serde_json::Value) -> serde_json::Value {
+        match value.as_i64() {
+            Some(millis) => serde_json::to_value(Self::milliseconds(millis)).unwrap_or(value),
+            None => value,
+        }
+    }
+}
+
 impl<T: PersistValue> PersistValue for Option<T> {
     fn sql_type() -> &'static str {
         T::sql_type()
@@ -1532,12 +2379,265 @@ impl<T: PersistValue> PersistValue for Option<T> {
             None => "NULL".to_string(),
         }
     }
+
+    fn coerce_state_value(value: serde_json::Value) -> serde_json::Value {
+        if value.is_null() {
+            value
+        } else {
+            T::coerce_state_value(value)
+        }
+    }
+}
+
+/// A struct stored as prefixed columns of the model embedding it with
+/// `#[sql(flatten)]` (`address: Address` becomes `address_street`, `address_zip`, ...).
+/// Usually derived with `#[derive(PersistFlatten)]`.
+pub trait PersistFlatten:
+    Clone + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    /// Whether every column may be NULL for an absent value.
+    const NULLABLE: bool = false;
+
+    /// `(field name, sql type)` for each flattened column, in declaration order.
+    fn flatten_fields() -> Vec<(&'static str, &'static str)>;
+    /// SQL literals in `flatten_fields()` order.
+    fn flatten_sql_literals(&self) -> Vec<String>;
+}
+
+impl<T: PersistFlatten> PersistFlatten for Option<T> {
+    const NULLABLE: bool = true;
+
+    fn flatten_fields() -> Vec<(&'static str, &'static str)> {
+        T::flatten_fields()
+    }
+
+    fn flatten_sql_literals(&self) -> Vec<String> {
+        match self {
+            Some(value) => value.flatten_sql_literals(),
+            None => vec!["NULL".to_string(); T::flatten_fields().len()],
+        }
+    }
+}
+
+/// SQL literal for a `#[sql(bool_as = "int")]` column: `1`/`0` instead of `TRUE`/`FALSE`.
+pub fn bool_as_int_sql_literal(literal: String) -> String {
+    match literal.as_str() {
+        "TRUE" => "1".to_string(),
+        "FALSE" => "0".to_string(),
+        _ => literal,
+    }
+}
+
+/// Reads `0`/`1` back as a boolean state value; anything else is returned unchanged.
+pub fn coerce_bool_state_value(value: serde_json::Value) -> serde_json::Value {
+    match value.as_i64() {
+        Some(0) => serde_json::Value::Bool(false),
+        Some(1) => serde_json::Value::Bool(true),
+        _ => value,
+    }
+}
+
+pub fn flatten_column_name(prefix: &str, field: &str) -> String {
+    format!("{}_{}", prefix, field)
+}
+
+/// Rebuilds the JSON value of a flattened field from its prefixed columns, as
+/// found in states loaded straight from the table. Returns `None` when none of
+/// the columns is present.
+pub fn unflatten_state_field<T: PersistFlatten>(
+    prefix: &str,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let mut object = serde_json::Map::new();
+    let mut found = false;
+    for (field, _) in T::flatten_fields() {
+        let value = fields.get(&flatten_column_name(prefix, field)).cloned();
+        found |= value.is_some();
+        object.insert(field.to_string(), value.unwrap_or(serde_json::Value::Null));
+    }
+    if !found {
+        return None;
+    }
+    if T::NULLABLE && object.values().all(serde_json::Value::is_null) {
+        return Some(serde_json::Value::Null);
+    }
+    Some(serde_json::Value::Object(object))
+}
+
+// `sql_type()` hands out `&'static str`, so each distinct `T[]` name is leaked
+// once and reused afterwards.
+static ARRAY_SQL_TYPES: std::sync::LazyLock<std::sync::Mutex<HashMap<&'static str, &'static str>>> =
+    std::sync::LazyLock::new(Default::default);
+
+fn array_sql_type(element_type: &'static str) -> &'static str {
+    let mut types = ARRAY_SQL_TYPES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    types
+        .entry(element_type)
+        .or_insert_with(|| Box::leak(format!("{}[]", element_type).into_boxed_str()))
+}
+
+impl<T: PersistValue> PersistValue for Vec<T> {
+    fn sql_type() -> &'static str {
+        array_sql_type(T::sql_type())
+    }
+
+    fn to_sql_literal(&self) -> String {
+        let items: Vec<String> = self.iter().map(PersistValue::to_sql_literal).collect();
+        format!("ARRAY[{}]", items.join(", "))
+    }
 }
 
 pub fn serde_to_db_error(context: &str, err: serde_json::Error) -> DbError {
     DbError::ExecutionError(format!("{}: {}", context, err))
 }
 
+/// Tracks `#[command(min_interval_ms = ...)]` limits per (entity type,
+/// command, persist id). Kept in process memory only, so the limits start
+/// fresh after a restart and are not shared between processes.
+#[derive(Debug, Default)]
+pub struct PersistCommandRateLimiter {
+    // Instant from which each key may run again.
+    invocations: std::sync::Mutex<HashMap<String, std::time::Instant>>,
+}
+
+/// An invocation reserved by `PersistCommandRateLimiter::try_acquire`.
+#[derive(Debug)]
+pub struct PersistCommandReservation {
+    key: String,
+    until: std::time::Instant,
+}
+
+impl PersistCommandRateLimiter {
+    /// Checks the limit and reserves the invocation under one lock, so two
+    /// concurrent callers cannot both pass. Hand the reservation back to
+    /// `release` when the command then fails.
+    pub fn try_acquire(
+        &self,
+        type_name: &str,
+        command_name: &str,
+        persist_id: &str,
+        min_interval_ms: u64,
+    ) -> Result<PersistCommandReservation> {
+        let key = format!("{}:{}:{}", type_name, command_name, persist_id);
+        let now = std::time::Instant::now();
+        let mut invocations = self.invocations.lock()?;
+        invocations.retain(|_, until| *until > now);
+        if let Some(until) = invocations.get(&key) {
+            return Err(DbError::ExecutionError(format!(
+                "Command rate limited: '{}' for {} '{}' may run again in {} ms",
+                command_name,
+                type_name,
+                persist_id,
+                (*until - now).as_millis().max(1)
+            )));
+        }
+
+        let until = now + std::time::Duration::from_millis(min_interval_ms);
+        invocations.insert(key.clone(), until);
+        Ok(PersistCommandReservation { key, until })
+    }
+
+    /// Gives back an invocation whose command did not complete.
+    pub fn release(&self, reservation: PersistCommandReservation) {
+        if let Ok(mut invocations) = self.invocations.lock()
+            && invocations.get(&reservation.key) == Some(&reservation.until)
+        {
+            invocations.remove(&reservation.key);
+        }
+    }
+
+    /// Number of limits still in force.
+    pub fn active_len(&self) -> usize {
+        let now = std::time::Instant::now();
+        self.invocations
+            .lock()
+            .map(|invocations| invocations.values().filter(|until| **until > now).count())
+            .unwrap_or(0)
+    }
+}
+
+pub fn is_command_rate_limited_error(err: &DbError) -> bool {
+    matches!(err, DbError::ExecutionError(message) if message.starts_with("Command rate limited:"))
+}
+
+// Per-(entity type, persist id) locks for `#[command(serialize_per_entity)]`.
+// Like the command rate limits they are process-local and do not coordinate
+// separate processes sharing the same storage.
+static ENTITY_COMMAND_LOCKS: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, std::sync::Arc<tokio::sync::Mutex<()>>>>,
+> = std::sync::LazyLock::new(Default::default);
+
+/// Waits for the command lock of one entity. Waiters are granted the lock in
+/// FIFO order. There is no built-in timeout: wrap the call in
+/// `tokio::time::timeout` to bound the wait, dropping the future gives up the
+/// place in the queue without side effects.
+pub async fn lock_entity_commands(
+    type_name: &str,
+    persist_id: &str,
+) -> tokio::sync::OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = ENTITY_COMMAND_LOCKS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        locks.retain(|_, lock| std::sync::Arc::strong_count(lock) > 1);
+        locks
+            .entry(format!("{}:{}", type_name, persist_id))
+            .or_default()
+            .clone()
+    };
+    lock.lock_owned().await
+}
+
+/// Validation for `#[command]` arguments, run before the domain method mutates
+/// state. Argument types that do not implement it are not validated.
+pub trait PersistValidate {
+    fn validate(&self) -> std::result::Result<(), String>;
+}
+
+// Autoref specialization used by `#[persistent_impl]`: a `PersistValidate`
+// argument resolves to the `PersistValidateProbe` impl, anything else falls
+// back to the no-op impl on `&PersistValidateProbe`.
+#[doc(hidden)]
+pub struct PersistValidateProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait PersistValidateViaTrait {
+    fn persist_validate(&self) -> std::result::Result<(), String>;
+}
+
+impl<T: PersistValidate> PersistValidateViaTrait for PersistValidateProbe<'_, T> {
+    fn persist_validate(&self) -> std::result::Result<(), String> {
+        self.0.validate()
+    }
+}
+
+#[doc(hidden)]
+pub trait PersistValidateFallback {
+    fn persist_validate(&self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<T> PersistValidateFallback for &PersistValidateProbe<'_, T> {}
+
+pub fn command_input_error(
+    type_name: &str,
+    command_name: &str,
+    field_name: &str,
+    message: &str,
+) -> DbError {
+    DbError::ExecutionError(format!(
+        "Command input invalid: '{}' for {} field '{}': {}",
+        command_name, type_name, field_name, message
+    ))
+}
+
+pub fn is_command_input_error(err: &DbError) -> bool {
+    matches!(err, DbError::ExecutionError(message) if message.starts_with("Command input invalid:"))
+}
+
 pub fn value_to_sql_literal(value: &Value) -> String {
     match value {
         Value::Null => "NULL".to_string(),
@@ -1562,6 +2662,40 @@ pub fn value_to_sql_literal(value: &Value) -> String {
             let json = v.to_string();
             format!("'{}'", sql_escape_string(&json))
         }
+        Value::Interval(v) => format!("INTERVAL '{}'", interval_text(*v)),
+    }
+}
+
+pub fn persist_labels_sql_literal(labels: &BTreeMap<String, String>) -> String {
+    let json = serde_json::to_string(labels).unwrap_or_else(|_| "{}".to_string());
+    format!("'{}'", sql_escape_string(&json))
+}
+
+pub fn persist_labels_from_value(value: &Value) -> BTreeMap<String, String> {
+    match value {
+        Value::Json(json) => serde_json::from_value(json.clone()).unwrap_or_default(),
+        Value::Text(text) => serde_json::from_str(text).unwrap_or_default(),
+        _ => BTreeMap::new(),
+    }
+}
+
+pub fn value_to_state_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(v) => serde_json::Value::from(*v),
+        Value::Float(v) => serde_json::Number::from_f64(*v)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::Text(v) => serde_json::Value::String(v.clone()),
+        Value::Boolean(v) => serde_json::Value::Bool(*v),
+        Value::Timestamp(v) => serde_json::Value::String(v.to_rfc3339()),
+        Value::Date(v) => serde_json::Value::String(v.to_string()),
+        Value::Uuid(v) => serde_json::Value::String(v.to_string()),
+        Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(value_to_state_json).collect())
+        }
+        Value::Json(v) => v.clone(),
+        Value::Interval(v) => serde_json::Value::String(interval_text(*v)),
     }
 }
 
@@ -1571,6 +2705,15 @@ pub fn value_matches_sql_type(value: &Value, sql_type: &str) -> bool {
     }
 
     let upper = sql_type.to_ascii_uppercase();
+    if let Some(element_type) = upper.trim().strip_suffix("[]") {
+        return match value {
+            Value::Array(items) => items
+                .iter()
+                .all(|item| value_matches_sql_type(item, element_type)),
+            Value::Text(_) => true,
+            _ => false,
+        };
+    }
     let base = upper
         .split(['(', ' ', '\t'])
         .next()
@@ -1588,6 +2731,7 @@ pub fn value_matches_sql_type(value: &Value, sql_type: &str) -> bool {
         "DATE" => matches!(value, Value::Date(_) | Value::Text(_)),
         "UUID" => matches!(value, Value::Uuid(_) | Value::Text(_)),
         "JSON" | "JSONB" => matches!(value, Value::Json(_) | Value::Text(_)),
+        "ENUM" => matches!(value, Value::Text(_)),
         _ => true,
     }
 }
@@ -1,9 +1,10 @@
-use super::{PersistMetadata, PersistState, new_persist_id};
+use super::{PersistCommandRateLimiter, PersistIdStrategy, PersistMetadata, PersistState};
 use crate::core::{DbError, Result, Value};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
 use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -19,6 +20,7 @@ use uuid::Uuid;
 const RUNTIME_SNAPSHOT_FILE: &str = "runtime_snapshot.json";
 const RUNTIME_JOURNAL_FILE: &str = "runtime_journal.log";
 const RUNTIME_FORMAT_VERSION: u16 = 1;
+const RUNTIME_CREATE_COMMAND_NAME: &str = "create";
 
 pub type DeterministicCommandHandler =
     Arc<dyn Fn(&mut PersistState, &serde_json::Value) -> Result<()> + Send + Sync>;
@@ -38,6 +40,28 @@ pub type DeterministicContextCommandHandler = Arc<
 >;
 pub type RuntimeClosureHandler =
     Arc<dyn Fn(&mut PersistState, Vec<Value>) -> Result<Value> + Send + Sync>;
+pub type RuntimeModelRegistrar = fn(&mut PersistEntityRuntime) -> Result<()>;
+
+#[derive(Debug, Clone)]
+pub struct RuntimeModelRegistration {
+    pub entity_type: String,
+    pub table_name: String,
+    pub register: RuntimeModelRegistrar,
+}
+
+impl RuntimeModelRegistration {
+    pub fn new(
+        entity_type: impl Into<String>,
+        table_name: impl Into<String>,
+        register: RuntimeModelRegistrar,
+    ) -> Self {
+        Self {
+            entity_type: entity_type.into(),
+            table_name: table_name.into(),
+            register,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RuntimeCommandEnvelope {
@@ -554,6 +578,10 @@ pub struct RuntimeProjectionField {
     pub column_name: String,
     pub payload_type: RuntimePayloadType,
     pub indexed: bool,
+    /// Rejects a second entity with the same non-null value. Unique columns
+    /// are always indexed.
+    #[serde(default)]
+    pub unique: bool,
 }
 
 impl RuntimeProjectionField {
@@ -567,6 +595,7 @@ impl RuntimeProjectionField {
             column_name: column_name.into(),
             payload_type,
             indexed: false,
+            unique: false,
         }
     }
 
@@ -574,6 +603,12 @@ impl RuntimeProjectionField {
         self.indexed = indexed;
         self
     }
+
+    pub fn unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self.indexed |= unique;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -652,6 +687,12 @@ impl RuntimeProjectionContract {
                     self.entity_type, field.column_name
                 )));
             }
+            if field.unique && !field.indexed {
+                return Err(DbError::ExecutionError(format!(
+                    "Projection contract '{}' column '{}' is unique but not indexed",
+                    self.entity_type, field.column_name
+                )));
+            }
         }
 
         Ok(())
@@ -670,14 +711,70 @@ struct RuntimeProjectionTable {
     contract: RuntimeProjectionContract,
     rows: HashMap<String, RuntimeProjectionRow>,
     indexes: HashMap<String, HashMap<String, HashSet<String>>>,
+    range_indexes: HashMap<String, BTreeMap<ProjectionRangeKey, BTreeSet<String>>>,
+}
+
+/// Ordered key of a scalar projection value; numbers compare numerically and
+/// text lexicographically (so RFC 3339 timestamps sort chronologically).
+#[derive(Debug, Clone)]
+enum ProjectionRangeKey {
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl ProjectionRangeKey {
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Bool(value) => Some(Self::Boolean(*value)),
+            serde_json::Value::Number(value) => value.as_f64().map(Self::Number),
+            serde_json::Value::String(value) => Some(Self::Text(value.clone())),
+            _ => None,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Boolean(_) => 0,
+            Self::Number(_) => 1,
+            Self::Text(_) => 2,
+        }
+    }
+}
+
+impl Ord for ProjectionRangeKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Self::Boolean(a), Self::Boolean(b)) => a.cmp(b),
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for ProjectionRangeKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ProjectionRangeKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
 }
 
+impl Eq for ProjectionRangeKey {}
+
 impl RuntimeProjectionTable {
     fn new(contract: RuntimeProjectionContract) -> Self {
         let mut indexes = HashMap::new();
+        let mut range_indexes = HashMap::new();
         for field in &contract.fields {
             if field.indexed {
                 indexes.insert(field.column_name.clone(), HashMap::new());
+                range_indexes.insert(field.column_name.clone(), BTreeMap::new());
             }
         }
 
@@ -685,12 +782,32 @@ impl RuntimeProjectionTable {
             contract,
             rows: HashMap::new(),
             indexes,
+            range_indexes,
         }
     }
 
     fn upsert_state(&mut self, state: &PersistState) -> Result<Option<RuntimeProjectionRow>> {
         let row = build_projection_row(&self.contract, state)?;
         let entity_id = state.persist_id.clone();
+        for field in self.contract.fields.iter().filter(|field| field.unique) {
+            let Some(value) = row
+                .values
+                .get(field.column_name.as_str())
+                .filter(|value| !value.is_null())
+            else {
+                continue;
+            };
+            let owner = self
+                .find_entity_ids_by_index(&field.column_name, value)
+                .into_iter()
+                .find(|owner| *owner != entity_id);
+            if let Some(owner) = owner {
+                return Err(DbError::ConstraintViolation(format!(
+                    "Projection column '{}.{}' is unique: value {} is already used by entity '{}'",
+                    self.contract.entity_type, field.column_name, value, owner
+                )));
+            }
+        }
         let previous = self.rows.insert(entity_id.clone(), row.clone());
         if let Some(prev) = &previous {
             self.remove_from_indexes(prev);
@@ -733,6 +850,39 @@ impl RuntimeProjectionTable {
         ids
     }
 
+    fn find_entity_ids_by_range(
+        &self,
+        column: &str,
+        lower: Option<&ProjectionRangeKey>,
+        upper: Option<&ProjectionRangeKey>,
+        inclusive: bool,
+    ) -> Vec<String> {
+        let bound = |key: Option<&ProjectionRangeKey>| match key {
+            Some(key) if inclusive => Bound::Included(key.clone()),
+            Some(key) => Bound::Excluded(key.clone()),
+            None => Bound::Unbounded,
+        };
+        let (lower, upper) = (bound(lower), bound(upper));
+        if let (
+            Bound::Included(low) | Bound::Excluded(low),
+            Bound::Included(high) | Bound::Excluded(high),
+        ) = (&lower, &upper)
+            && (low > high || (low == high && !inclusive))
+        {
+            return Vec::new();
+        }
+
+        self.range_indexes
+            .get(column)
+            .map(|entries| {
+                entries
+                    .range((lower, upper))
+                    .flat_map(|(_, ids)| ids.iter().cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn add_to_indexes(&mut self, row: &RuntimeProjectionRow) {
         for field in &self.contract.fields {
             if !field.indexed {
@@ -741,6 +891,14 @@ impl RuntimeProjectionTable {
             let Some(value) = row.values.get(field.column_name.as_str()) else {
                 continue;
             };
+            if let Some(range_key) = ProjectionRangeKey::from_json(value) {
+                self.range_indexes
+                    .entry(field.column_name.clone())
+                    .or_default()
+                    .entry(range_key)
+                    .or_default()
+                    .insert(row.entity_id.clone());
+            }
             let key = projection_index_key(value);
             let bucket = self
                 .indexes
@@ -760,6 +918,15 @@ impl RuntimeProjectionTable {
             let Some(value) = row.values.get(field.column_name.as_str()) else {
                 continue;
             };
+            if let Some(range_key) = ProjectionRangeKey::from_json(value)
+                && let Some(entries) = self.range_indexes.get_mut(field.column_name.as_str())
+                && let Some(bucket) = entries.get_mut(&range_key)
+            {
+                bucket.remove(row.entity_id.as_str());
+                if bucket.is_empty() {
+                    entries.remove(&range_key);
+                }
+            }
             let key = projection_index_key(value);
             if let Some(entries) = self.indexes.get_mut(field.column_name.as_str()) {
                 if let Some(bucket) = entries.get_mut(&key) {
@@ -871,8 +1038,12 @@ pub struct PersistEntityRuntime {
     cold_entities: HashMap<RuntimeEntityKey, RuntimeStoredEntity>,
     deterministic_registry: HashMap<String, HashMap<String, RegisteredDeterministicCommand>>,
     runtime_closure_registry: HashMap<String, HashMap<String, RuntimeClosureHandler>>,
+    command_min_intervals: HashMap<String, HashMap<String, u64>>,
+    command_limits: PersistCommandRateLimiter,
+    id_strategy: PersistIdStrategy,
     projection_registry: HashMap<String, RuntimeProjectionContract>,
     projection_tables: HashMap<String, RuntimeProjectionTable>,
+    model_tables: HashMap<String, String>,
     entity_mailboxes: HashMap<RuntimeEntityKey, RuntimeEntityMailbox>,
     outbox_records: HashMap<String, RuntimeOutboxRecord>,
     idempotency_index: HashMap<String, RuntimeIdempotencyReceipt>,
@@ -916,8 +1087,12 @@ impl PersistEntityRuntime {
             cold_entities: HashMap::new(),
             deterministic_registry: HashMap::new(),
             runtime_closure_registry: HashMap::new(),
+            command_min_intervals: HashMap::new(),
+            command_limits: PersistCommandRateLimiter::default(),
+            id_strategy: PersistIdStrategy::default(),
             projection_registry: HashMap::new(),
             projection_tables: HashMap::new(),
+            model_tables: HashMap::new(),
             entity_mailboxes: HashMap::new(),
             outbox_records: HashMap::new(),
             idempotency_index: HashMap::new(),
@@ -1045,6 +1220,26 @@ impl PersistEntityRuntime {
         }
     }
 
+    /// Generates the ids of entities created afterwards; existing ids are never
+    /// rewritten.
+    pub fn set_id_strategy(&mut self, id_strategy: PersistIdStrategy) {
+        self.id_strategy = id_strategy;
+    }
+
+    /// Enforces `#[command(min_interval_ms = ...)]` for `command` when it is
+    /// applied through this runtime. The limits live as long as the runtime.
+    pub fn register_command_min_interval(
+        &mut self,
+        entity_type: impl Into<String>,
+        command: impl Into<String>,
+        min_interval_ms: u64,
+    ) {
+        self.command_min_intervals
+            .entry(entity_type.into())
+            .or_default()
+            .insert(command.into(), min_interval_ms);
+    }
+
     pub fn register_deterministic_command(
         &mut self,
         entity_type: impl Into<String>,
@@ -1162,6 +1357,62 @@ impl PersistEntityRuntime {
         );
     }
 
+    /// Registers every model in order, stopping at the first error.
+    /// Fails before registering anything if two models claim the same table.
+    pub fn register_all(
+        &mut self,
+        models: impl IntoIterator<Item = RuntimeModelRegistration>,
+    ) -> Result<()> {
+        let models = models.into_iter().collect::<Vec<_>>();
+        let mut claimed_tables = self
+            .model_tables
+            .iter()
+            .map(|(table, entity_type)| (table.as_str(), entity_type.as_str()))
+            .collect::<HashMap<_, _>>();
+        for model in &models {
+            if let Some(existing) =
+                claimed_tables.insert(model.table_name.as_str(), model.entity_type.as_str())
+                && existing != model.entity_type
+            {
+                return Err(table_collision_error(
+                    existing,
+                    &model.entity_type,
+                    &model.table_name,
+                ));
+            }
+        }
+
+        for model in &models {
+            (model.register)(self)?;
+        }
+        Ok(())
+    }
+
+    /// Records that `entity_type` owns `table_name`; a second model claiming
+    /// the same table would silently share (and corrupt) its rows.
+    pub fn claim_model_table(
+        &mut self,
+        entity_type: impl Into<String>,
+        table_name: impl Into<String>,
+    ) -> Result<()> {
+        let entity_type = entity_type.into();
+        let table_name = table_name.into();
+        match self.model_tables.get(&table_name) {
+            Some(existing) if *existing != entity_type => {
+                Err(table_collision_error(existing, &entity_type, &table_name))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.model_tables.insert(table_name, entity_type);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn model_table_owner(&self, table_name: &str) -> Option<&str> {
+        self.model_tables.get(table_name).map(String::as_str)
+    }
+
     pub fn register_runtime_closure(
         &mut self,
         entity_type: impl Into<String>,
@@ -1181,11 +1432,20 @@ impl PersistEntityRuntime {
     ) -> Result<()> {
         contract.validate()?;
         let entity_type = contract.entity_type.clone();
+        if let Some(existing) = self.projection_registry.values().find(|existing| {
+            existing.table_name == contract.table_name && existing.entity_type != entity_type
+        }) {
+            return Err(table_collision_error(
+                &existing.entity_type,
+                &entity_type,
+                &contract.table_name,
+            ));
+        }
+        let table = self.build_projection_table(contract.clone())?;
         self.projection_registry
-            .insert(entity_type.clone(), contract.clone());
-        self.projection_tables
-            .insert(entity_type.clone(), RuntimeProjectionTable::new(contract));
-        self.rebuild_projection_for_entity_type(&entity_type)
+            .insert(entity_type.clone(), contract);
+        self.projection_tables.insert(entity_type, table);
+        Ok(())
     }
 
     pub fn projection_contract(&self, entity_type: &str) -> Option<&RuntimeProjectionContract> {
@@ -1258,6 +1518,215 @@ impl PersistEntityRuntime {
         Ok(rows)
     }
 
+    /// Rows whose indexed `column` lies between `lower` and `upper` (either bound
+    /// may be omitted), ordered by the column value. `inclusive` applies to both
+    /// bounds. Bounds must be scalar JSON values.
+    pub fn find_projection_rows_by_range(
+        &self,
+        entity_type: &str,
+        column: &str,
+        lower: Option<&serde_json::Value>,
+        upper: Option<&serde_json::Value>,
+        inclusive: bool,
+    ) -> Result<Vec<RuntimeProjectionRow>> {
+        let contract = self.projection_registry.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection contract is not registered for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        let indexed = contract
+            .fields
+            .iter()
+            .any(|field| field.column_name == column && field.indexed);
+        if !indexed {
+            return Err(DbError::ExecutionError(format!(
+                "Projection column '{}.{}' is not indexed",
+                entity_type, column
+            )));
+        }
+
+        let range_key = |bound: Option<&serde_json::Value>| {
+            bound
+                .map(|value| {
+                    ProjectionRangeKey::from_json(value).ok_or_else(|| {
+                        DbError::ExecutionError(format!(
+                            "Projection range bound for '{}.{}' must be a number, string or boolean, got {}",
+                            entity_type,
+                            column,
+                            json_type_name(value)
+                        ))
+                    })
+                })
+                .transpose()
+        };
+        let lower = range_key(lower)?;
+        let upper = range_key(upper)?;
+
+        let table = self.projection_tables.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection table is not initialized for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        Ok(table
+            .find_entity_ids_by_range(column, lower.as_ref(), upper.as_ref(), inclusive)
+            .into_iter()
+            .filter_map(|entity_id| table.rows.get(&entity_id).cloned())
+            .collect())
+    }
+
+    pub fn count_projection_rows(&self, entity_type: &str) -> Result<usize> {
+        let table = self.projection_tables.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection table is not initialized for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        Ok(table.rows.len())
+    }
+
+    /// Counts projection rows whose columns equal every `(column, value)`
+    /// filter without cloning them; an empty filter counts all rows. The first
+    /// indexed filter column narrows the scan through its index. Every column
+    /// must be part of the projection contract.
+    pub fn count_projection_rows_where(
+        &self,
+        entity_type: &str,
+        filters: &[(&str, serde_json::Value)],
+    ) -> Result<usize> {
+        let contract = self.projection_registry.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection contract is not registered for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        let mut indexed_filter = None;
+        for (column, value) in filters {
+            let field = contract
+                .fields
+                .iter()
+                .find(|field| field.column_name == *column)
+                .ok_or_else(|| {
+                    DbError::ExecutionError(format!(
+                        "Projection column '{}.{}' does not exist",
+                        entity_type, column
+                    ))
+                })?;
+            if field.indexed && indexed_filter.is_none() {
+                indexed_filter = Some((*column, value));
+            }
+        }
+
+        let table = self.projection_tables.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection table is not initialized for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        let matches = |row: &RuntimeProjectionRow| {
+            filters.iter().all(|(column, value)| {
+                row.values.get(*column).unwrap_or(&serde_json::Value::Null) == value
+            })
+        };
+        Ok(match indexed_filter {
+            Some((column, value)) => table
+                .find_entity_ids_by_index(column, value)
+                .iter()
+                .filter_map(|entity_id| table.rows.get(entity_id))
+                .filter(|row| matches(row))
+                .count(),
+            None => table.rows.values().filter(|row| matches(row)).count(),
+        })
+    }
+
+    /// Removes projection rows whose entity no longer exists and returns how
+    /// many were removed. Takes `&mut self`, so no command can upsert a row
+    /// while the scan runs.
+    pub fn delete_projection_orphans(&mut self, entity_type: &str) -> Result<usize> {
+        let live_ids = self
+            .hot_entities
+            .keys()
+            .chain(self.cold_entities.keys())
+            .filter(|key| key.entity_type == entity_type)
+            .map(|key| key.persist_id.clone())
+            .collect::<HashSet<_>>();
+
+        let table = self.projection_tables.get_mut(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection table is not initialized for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        let orphans = table
+            .rows
+            .keys()
+            .filter(|entity_id| !live_ids.contains(*entity_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for entity_id in &orphans {
+            table.remove_entity(entity_id);
+        }
+        Ok(orphans.len())
+    }
+
+    /// Sum of a numeric projection column; NULL values are ignored.
+    pub fn sum_projection_column(&self, entity_type: &str, column: &str) -> Result<f64> {
+        Ok(self
+            .numeric_projection_values(entity_type, column)?
+            .into_iter()
+            .sum())
+    }
+
+    /// Average of a numeric projection column, `None` when it has no values.
+    pub fn avg_projection_column(&self, entity_type: &str, column: &str) -> Result<Option<f64>> {
+        let values = self.numeric_projection_values(entity_type, column)?;
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(values.iter().sum::<f64>() / values.len() as f64))
+    }
+
+    fn numeric_projection_values(&self, entity_type: &str, column: &str) -> Result<Vec<f64>> {
+        let contract = self.projection_registry.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection contract is not registered for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        let field = contract
+            .fields
+            .iter()
+            .find(|field| field.column_name == column)
+            .ok_or_else(|| {
+                DbError::ExecutionError(format!(
+                    "Projection column '{}.{}' does not exist",
+                    entity_type, column
+                ))
+            })?;
+        if !matches!(
+            field.payload_type,
+            RuntimePayloadType::Integer | RuntimePayloadType::Float
+        ) {
+            return Err(DbError::ExecutionError(format!(
+                "Projection column '{}.{}' is not numeric",
+                entity_type, column
+            )));
+        }
+
+        let table = self.projection_tables.get(entity_type).ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Projection table is not initialized for entity type '{}'",
+                entity_type
+            ))
+        })?;
+        Ok(table
+            .rows
+            .values()
+            .filter_map(|row| row.values.get(column).and_then(serde_json::Value::as_f64))
+            .collect())
+    }
+
     pub fn rebuild_registered_projections(&mut self) -> Result<()> {
         let entity_types = self.projection_registry.keys().cloned().collect::<Vec<_>>();
         for entity_type in entity_types {
@@ -1277,7 +1746,7 @@ impl PersistEntityRuntime {
         let _keep_permit_until_drop = permit;
 
         let now = Utc::now();
-        let persist_id = new_persist_id();
+        let persist_id = self.id_strategy.generate();
         let mut metadata = PersistMetadata::new(now);
         metadata.schema_version = schema_version.max(1);
         metadata.version = 1;
@@ -1297,6 +1766,97 @@ impl PersistEntityRuntime {
         Ok(persist_id)
     }
 
+    /// Like `create_entity`, but a repeated `idempotency_key` for the same
+    /// entity type returns the originally created state instead of creating
+    /// another entity.
+    pub async fn create_entity_idempotent(
+        &mut self,
+        entity_type: impl Into<String>,
+        table_name: impl Into<String>,
+        fields: serde_json::Value,
+        schema_version: u32,
+        idempotency_key: impl Into<String>,
+    ) -> Result<RuntimeEnvelopeApplyResult> {
+        let permit = self.acquire_inflight_permit().await?;
+        let _keep_permit_until_drop = permit;
+
+        let entity_type = entity_type.into();
+        let idempotency_key = idempotency_key.into();
+        let scope_key = build_create_idempotency_scope_key(&entity_type, &idempotency_key);
+        if let Some(existing) = self.idempotency_index.get(&scope_key) {
+            event!(Level::INFO, "runtime create idempotent replay");
+            return Ok(RuntimeEnvelopeApplyResult {
+                envelope_id: existing.envelope_id,
+                state: existing.state.clone(),
+                idempotent_replay: true,
+                outbox: existing.outbox.clone(),
+            });
+        }
+
+        let now = Utc::now();
+        let persist_id = self.id_strategy.generate();
+        let mut metadata = PersistMetadata::new(now);
+        metadata.schema_version = schema_version.max(1);
+        metadata.version = 1;
+        metadata.touch_count = 1;
+        metadata.persisted = true;
+
+        let envelope = RuntimeCommandEnvelope::new(
+            entity_type.clone(),
+            persist_id.clone(),
+            RUNTIME_CREATE_COMMAND_NAME,
+            fields.clone(),
+        )
+        .with_idempotency_key(idempotency_key);
+        let state = PersistState {
+            persist_id,
+            type_name: entity_type,
+            table_name: table_name.into(),
+            metadata,
+            fields,
+        };
+        let managed = RuntimeStoredEntity::new(state, true);
+
+        let projection_undo = self.apply_projection_upsert(&managed.state)?;
+        let append_result = self
+            .append_record(RuntimeJournalOp::Upsert {
+                entity: managed.clone(),
+                reason: "create".to_string(),
+                command: None,
+                envelope: Some(envelope.clone()),
+                outbox: Vec::new(),
+                idempotency_scope_key: Some(scope_key.clone()),
+            })
+            .await;
+        if let Err(err) = append_result {
+            self.rollback_projection_undo(projection_undo);
+            return Err(err);
+        }
+
+        let key = RuntimeEntityKey::from_state(&managed.state);
+        self.cold_entities.remove(&key);
+        self.hot_entities.insert(key, managed.clone());
+        self.idempotency_index.insert(
+            scope_key,
+            RuntimeIdempotencyReceipt {
+                envelope_id: envelope.envelope_id,
+                entity_type: envelope.entity_type,
+                entity_id: envelope.entity_id,
+                command_name: envelope.command_name,
+                state: managed.state.clone(),
+                outbox: Vec::new(),
+            },
+        );
+
+        self.maybe_snapshot_and_compact().await?;
+        Ok(RuntimeEnvelopeApplyResult {
+            envelope_id: envelope.envelope_id,
+            state: managed.state,
+            idempotent_replay: false,
+            outbox: Vec::new(),
+        })
+    }
+
     pub async fn upsert_state(
         &mut self,
         state: PersistState,
@@ -1512,14 +2072,39 @@ impl PersistEntityRuntime {
             )));
         }
 
+        let reservation = match self
+            .command_min_intervals
+            .get(envelope.entity_type.as_str())
+            .and_then(|commands| commands.get(envelope.command_name.as_str()))
+        {
+            Some(min_interval_ms) => Some(self.command_limits.try_acquire(
+                &envelope.entity_type,
+                &envelope.command_name,
+                &envelope.entity_id,
+                *min_interval_ms,
+            )?),
+            None => None,
+        };
+
         let key = RuntimeEntityKey::new(envelope.entity_type.clone(), envelope.entity_id.clone());
-        let base = self.take_entity_for_mutation(&key)?;
+        let base = match self.take_entity_for_mutation(&key) {
+            Ok(base) => base,
+            Err(err) => {
+                if let Some(reservation) = reservation {
+                    self.command_limits.release(reservation);
+                }
+                return Err(err);
+            }
+        };
         self.mailbox_start_command(&key);
         if let Some(expected_version) = envelope.expected_version {
             let actual_version = base.state.metadata.version.max(0) as u64;
             if expected_version != actual_version {
                 self.hot_entities.insert(key.clone(), base);
                 self.mailbox_complete_command(&key);
+                if let Some(reservation) = reservation {
+                    self.command_limits.release(reservation);
+                }
                 event!(
                     Level::WARN,
                     expected_version,
@@ -1652,6 +2237,9 @@ impl PersistEntityRuntime {
 
         self.hot_entities.insert(key.clone(), base);
         self.mailbox_complete_command(&key);
+        if let Some(reservation) = reservation {
+            self.command_limits.release(reservation);
+        }
         let err = last_err.unwrap_or_else(|| {
             DbError::ExecutionError("Failed to apply deterministic command".to_string())
         });
@@ -1659,6 +2247,74 @@ impl PersistEntityRuntime {
         Err(err)
     }
 
+    /// Rebuilds an entity from the journal: starts at its last non-command
+    /// write (create or `upsert_state`) and re-applies every later command
+    /// through the registered deterministic handlers. The live entity is not
+    /// modified; pass the result to `upsert_state` to restore it.
+    pub async fn replay_entity_from_journal(
+        &self,
+        entity_type: &str,
+        persist_id: &str,
+    ) -> Result<PersistState> {
+        let key = RuntimeEntityKey::new(entity_type, persist_id);
+        let mut state: Option<PersistState> = None;
+
+        for record in self.read_journal_records(0).await? {
+            match record.op {
+                RuntimeJournalOp::Upsert {
+                    entity,
+                    command,
+                    envelope,
+                    ..
+                } if RuntimeEntityKey::from_state(&entity.state) == key => {
+                    let Some(invocation) = command else {
+                        state = Some(entity.state);
+                        continue;
+                    };
+                    let current = state.as_mut().ok_or_else(|| {
+                        DbError::ExecutionError(format!(
+                            "Journal for {}:{} starts with command '{}' (earlier history was compacted into a snapshot)",
+                            entity_type, persist_id, invocation.command
+                        ))
+                    })?;
+
+                    let envelope = envelope.unwrap_or_else(|| {
+                        RuntimeCommandEnvelope::new(
+                            entity_type,
+                            persist_id,
+                            invocation.command.clone(),
+                            invocation.payload.clone(),
+                        )
+                    });
+                    let handler = self
+                        .deterministic_registry
+                        .get(entity_type)
+                        .and_then(|commands| commands.get(invocation.command.as_str()))
+                        .ok_or_else(|| {
+                            DbError::ExecutionError(format!(
+                                "Deterministic command '{}' is not registered for entity type '{}'",
+                                invocation.command, entity_type
+                            ))
+                        })?;
+                    let context = RuntimeDeterministicContext::from_envelope(&envelope);
+                    invoke_registered_handler(&handler.handler, current, &envelope, &context)?;
+                    current.metadata.persisted = true;
+                }
+                RuntimeJournalOp::Delete { key: deleted, .. } if deleted == key => {
+                    state = None;
+                }
+                _ => {}
+            }
+        }
+
+        state.ok_or_else(|| {
+            DbError::ExecutionError(format!(
+                "Journal has no history for {}:{}",
+                entity_type, persist_id
+            ))
+        })
+    }
+
     pub async fn invoke_runtime_closure(
         &mut self,
         entity_type: &str,
@@ -1990,29 +2646,29 @@ impl PersistEntityRuntime {
                 ))
             })?;
 
-        let states = self
+        let table = self.build_projection_table(contract)?;
+        self.projection_tables
+            .insert(entity_type.to_string(), table);
+        Ok(())
+    }
+
+    /// Fills a fresh projection table from the runtime's entities, so a
+    /// failing row leaves the currently registered table untouched.
+    fn build_projection_table(
+        &self,
+        contract: RuntimeProjectionContract,
+    ) -> Result<RuntimeProjectionTable> {
+        let entity_type = contract.entity_type.clone();
+        let mut table = RuntimeProjectionTable::new(contract);
+        for entity in self
             .hot_entities
             .values()
             .chain(self.cold_entities.values())
             .filter(|entity| entity.state.type_name == entity_type)
-            .map(|entity| entity.state.clone())
-            .collect::<Vec<_>>();
-
-        self.projection_tables.insert(
-            entity_type.to_string(),
-            RuntimeProjectionTable::new(contract),
-        );
-        let table = self.projection_tables.get_mut(entity_type).ok_or_else(|| {
-            DbError::ExecutionError(format!(
-                "Projection table is not initialized for entity type '{}'",
-                entity_type
-            ))
-        })?;
-
-        for state in states {
-            table.upsert_state(&state)?;
+        {
+            table.upsert_state(&entity.state)?;
         }
-        Ok(())
+        Ok(table)
     }
 
     fn apply_projection_upsert(
@@ -2683,15 +3339,41 @@ fn validate_command_envelope(envelope: &RuntimeCommandEnvelope) -> Result<()> {
     Ok(())
 }
 
+/// Idempotency keys are scoped to `(entity_type, entity_id, command, key)`, so
+/// a client key reused on another entity or command executes normally.
 fn build_idempotency_scope_key(envelope: &RuntimeCommandEnvelope) -> Option<String> {
     envelope.idempotency_key.as_ref().map(|key| {
         format!(
             "{}:{}:{}:{}",
-            envelope.entity_type, envelope.entity_id, envelope.command_name, key
+            escape_idempotency_scope_part(&envelope.entity_type),
+            escape_idempotency_scope_part(&envelope.entity_id),
+            escape_idempotency_scope_part(&envelope.command_name),
+            key
         )
     })
 }
 
+fn build_create_idempotency_scope_key(entity_type: &str, idempotency_key: &str) -> String {
+    format!(
+        "{}:*:{}:{}",
+        escape_idempotency_scope_part(entity_type),
+        RUNTIME_CREATE_COMMAND_NAME,
+        idempotency_key
+    )
+}
+
+// Escapes the scope delimiter so ids containing ':' (or an entity id of '*')
+// cannot collide with another scope. Plain ids map to themselves, which keeps
+// keys recorded by earlier versions valid.
+fn escape_idempotency_scope_part(part: &str) -> String {
+    if !part.contains(['%', ':', '*']) {
+        return part.to_string();
+    }
+    part.replace('%', "%25")
+        .replace(':', "%3A")
+        .replace('*', "%2A")
+}
+
 fn build_projection_row(
     contract: &RuntimeProjectionContract,
     state: &PersistState,
@@ -2805,6 +3487,13 @@ fn runtime_replica_targets(root_dir: &Path, replica_roots: &[PathBuf]) -> Vec<Ru
     result
 }
 
+fn table_collision_error(existing: &str, entity_type: &str, table_name: &str) -> DbError {
+    DbError::ExecutionError(format!(
+        "Table '{}' is already registered by model '{}'; model '{}' must use a different table (check #[persist_model(table = ...)])",
+        table_name, existing, entity_type
+    ))
+}
+
 pub struct RuntimeSnapshotWorker {
     runtime: Arc<Mutex<PersistEntityRuntime>>,
     stop_tx: Option<oneshot::Sender<()>>,
@@ -2938,3 +3627,97 @@ pub struct RuntimeCompatReport {
     pub compatible: bool,
     pub issues: Vec<RuntimeCompatIssue>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn delete_projection_orphans_removes_rows_without_entity() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut runtime =
+            PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+                .await
+                .unwrap();
+        runtime
+            .register_projection_contract(
+                RuntimeProjectionContract::new("User", "user_projection").with_field(
+                    RuntimeProjectionField::new("email", "email", RuntimePayloadType::Text)
+                        .indexed(true),
+                ),
+            )
+            .unwrap();
+        let alice = runtime
+            .create_entity(
+                "User",
+                "user_state",
+                json!({ "email": "alice@example.com" }),
+                1,
+            )
+            .await
+            .unwrap();
+
+        let mut orphan = runtime.get_state("User", &alice).unwrap();
+        orphan.persist_id = "orphan".to_string();
+        orphan.fields = json!({ "email": "ghost@example.com" });
+        runtime
+            .projection_tables
+            .get_mut("User")
+            .unwrap()
+            .upsert_state(&orphan)
+            .unwrap();
+        assert_eq!(runtime.list_projection_rows("User").unwrap().len(), 2);
+
+        assert_eq!(runtime.delete_projection_orphans("User").unwrap(), 1);
+        let rows = runtime.list_projection_rows("User").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].entity_id, alice);
+        assert!(
+            runtime
+                .find_projection_rows_by_index("User", "email", &json!("ghost@example.com"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_projection_registration_keeps_previous_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut runtime =
+            PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+                .await
+                .unwrap();
+        for _ in 0..2 {
+            runtime
+                .create_entity(
+                    "User",
+                    "user_state",
+                    json!({ "email": "same@example.com" }),
+                    1,
+                )
+                .await
+                .unwrap();
+        }
+        let email = || RuntimeProjectionField::new("email", "email", RuntimePayloadType::Text);
+
+        let unique = RuntimeProjectionContract::new("User", "user_projection")
+            .with_field(email().unique(true));
+        assert!(
+            runtime
+                .register_projection_contract(unique.clone())
+                .is_err()
+        );
+        assert!(runtime.projection_contract("User").is_none());
+        assert!(runtime.list_projection_rows("User").is_err());
+
+        runtime
+            .register_projection_contract(
+                RuntimeProjectionContract::new("User", "user_projection").with_field(email()),
+            )
+            .unwrap();
+        assert!(runtime.register_projection_contract(unique).is_err());
+        assert!(!runtime.projection_contract("User").unwrap().fields[0].unique);
+        assert_eq!(runtime.list_projection_rows("User").unwrap().len(), 2);
+    }
+}
@@ -361,7 +361,7 @@ impl QueryPlanner {
 
     fn build_aggregate_schema(
         &self,
-        _input_schema: &Schema,
+        input_schema: &Schema,
         group_by: &[Expr],
         aggrs: &[Expr],
     ) -> Schema {
@@ -369,25 +369,14 @@ impl QueryPlanner {
 
         // Group columns
         for expr in group_by {
-            // In a real DB, we'd infer type. For MVP, Text.
-            let name = format!("{}", expr); // Need Expr::Display or manual format
-            columns.push(Column::new(name, crate::core::DataType::Text));
+            let name = format!("{}", expr);
+            columns.push(Column::new(name, self.infer_expr_type(expr, input_schema)));
         }
 
         // Aggregate columns
         for expr in aggrs {
-            // Aggregate result type depends on function.
-            // COUNT -> Integer, others -> depends on input.
-            // For MVP, simplistic inference.
             let name = self.format_aggregate(expr);
-
-            let data_type = if name.to_uppercase().starts_with("COUNT") {
-                crate::core::DataType::Integer
-            } else {
-                crate::core::DataType::Float // Sum/Avg usually Float
-            };
-
-            columns.push(Column::new(name, data_type));
+            columns.push(Column::new(name, self.infer_expr_type(expr, input_schema)));
         }
 
         Schema::new(columns)
@@ -592,6 +581,9 @@ impl QueryPlanner {
                     // Infer column name and type
                     let name = alias.clone().unwrap_or_else(|| match expr {
                         Expr::Column(name) => name.clone(),
+                        Expr::CompoundIdentifier(parts) if !parts.is_empty() => {
+                            parts[parts.len() - 1].clone()
+                        }
                         _ => format!("col_{}", columns.len()),
                     });
 
@@ -797,6 +789,10 @@ impl QueryPlanner {
                 .get_column(name)
                 .map(|c| c.data_type.clone())
                 .unwrap_or(DataType::Text),
+            Expr::CompoundIdentifier(parts) => schema
+                .get_column(&parts.join("."))
+                .map(|c| c.data_type.clone())
+                .unwrap_or(DataType::Text),
             Expr::Literal(val) => match val {
                 Value::Integer(_) => DataType::Integer,
                 Value::Float(_) => DataType::Float,
@@ -805,14 +801,59 @@ impl QueryPlanner {
                 Value::Date(_) => DataType::Date,
                 Value::Uuid(_) => DataType::Uuid,
                 Value::Json(_) => DataType::Json,
+                Value::Interval(_) => DataType::Interval,
                 _ => DataType::Text,
             },
-            Expr::BinaryOp { left, .. } => self.infer_expr_type(left, schema),
-            Expr::UnaryOp { expr, .. } => self.infer_expr_type(expr, schema),
-            Expr::Function { name, .. } => match name.to_uppercase().as_str() {
+            Expr::BinaryOp { left, op, right } => match op {
+                BinaryOp::Add
+                | BinaryOp::Subtract
+                | BinaryOp::Multiply
+                | BinaryOp::Divide
+                | BinaryOp::Modulo => {
+                    let left_type = self.infer_expr_type(left, schema);
+                    let right_type = self.infer_expr_type(right, schema);
+                    if left_type == DataType::Timestamp && right_type == DataType::Timestamp {
+                        DataType::Interval
+                    } else if left_type == DataType::Timestamp || right_type == DataType::Timestamp
+                    {
+                        DataType::Timestamp
+                    } else if left_type == DataType::Float || right_type == DataType::Float {
+                        DataType::Float
+                    } else {
+                        left_type
+                    }
+                }
+                BinaryOp::Arrow => DataType::Json,
+                BinaryOp::LongArrow => DataType::Text,
+                _ => DataType::Boolean,
+            },
+            Expr::UnaryOp { op, expr } => match op {
+                crate::parser::ast::UnaryOp::Not => DataType::Boolean,
+                _ => self.infer_expr_type(expr, schema),
+            },
+            Expr::Like { .. }
+            | Expr::Between { .. }
+            | Expr::In { .. }
+            | Expr::InSubquery { .. }
+            | Expr::Exists { .. }
+            | Expr::IsNull { .. }
+            | Expr::Not { .. }
+            | Expr::AnyOp { .. } => DataType::Boolean,
+            Expr::Cast { data_type, .. } => data_type.clone(),
+            Expr::Array(items) => DataType::Array(Box::new(
+                items
+                    .first()
+                    .map(|item| self.infer_expr_type(item, schema))
+                    .unwrap_or(DataType::Unknown),
+            )),
+            Expr::Function { name, args, .. } => match name.to_uppercase().as_str() {
                 "COUNT" | "ROW_NUMBER" | "RANK" | "LENGTH" => DataType::Integer,
-                "SUM" | "AVG" => DataType::Float,
-                "NOW" => DataType::Timestamp,
+                "AVG" => DataType::Float,
+                "SUM" | "MIN" | "MAX" | "COALESCE" => args
+                    .first()
+                    .map(|arg| self.infer_expr_type(arg, schema))
+                    .unwrap_or(DataType::Float),
+                "NOW" | "CURRENT_TIMESTAMP" => DataType::Timestamp,
                 _ => DataType::Text,
             },
             _ => DataType::Text,
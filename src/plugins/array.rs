@@ -0,0 +1,64 @@
+use super::{ExpressionConverter, ExpressionPlugin, QueryConverter};
+use crate::core::{DbError, Result};
+use crate::parser::ast::{BinaryOp, Expr};
+use sqlparser::ast as sql_ast;
+
+/// Плагин для операций над массивами: x = ANY(arr) и arr @> arr
+pub struct ArrayPlugin;
+
+impl ExpressionPlugin for ArrayPlugin {
+    fn name(&self) -> &'static str {
+        "ARRAY"
+    }
+
+    fn can_handle(&self, expr: &sql_ast::Expr) -> bool {
+        match expr {
+            sql_ast::Expr::AnyOp { right, .. } => !matches!(**right, sql_ast::Expr::Subquery(_)),
+            sql_ast::Expr::BinaryOp { op, .. } => matches!(op, sql_ast::BinaryOperator::AtArrow),
+            _ => false,
+        }
+    }
+
+    fn convert(
+        &self,
+        expr: sql_ast::Expr,
+        converter: &ExpressionConverter,
+        query_converter: &dyn QueryConverter,
+    ) -> Result<Expr> {
+        match expr {
+            sql_ast::Expr::AnyOp {
+                left,
+                compare_op,
+                right,
+                ..
+            } => {
+                let op = converter.convert_binary_op(&compare_op)?;
+                if !matches!(
+                    op,
+                    BinaryOp::Eq
+                        | BinaryOp::NotEq
+                        | BinaryOp::Lt
+                        | BinaryOp::LtEq
+                        | BinaryOp::Gt
+                        | BinaryOp::GtEq
+                ) {
+                    return Err(DbError::UnsupportedOperation(format!(
+                        "Unsupported operator for ANY: {}",
+                        op
+                    )));
+                }
+                Ok(Expr::AnyOp {
+                    left: Box::new(converter.convert(*left, query_converter)?),
+                    op,
+                    right: Box::new(converter.convert(*right, query_converter)?),
+                })
+            }
+            sql_ast::Expr::BinaryOp { left, op, right } => Ok(Expr::BinaryOp {
+                left: Box::new(converter.convert(*left, query_converter)?),
+                op: converter.convert_binary_op(&op)?,
+                right: Box::new(converter.convert(*right, query_converter)?),
+            }),
+            _ => unreachable!("ArrayPlugin called with non-array expression"),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod arithmetic;
+pub mod array;
 pub mod between;
 mod boolean;
 pub mod comparison;
@@ -70,6 +71,7 @@ impl ExpressionPluginRegistry {
         registry.register(Box::new(in_list::InListPlugin));
         registry.register(Box::new(boolean::BooleanPlugin));
         registry.register(Box::new(json::JsonPlugin));
+        registry.register(Box::new(array::ArrayPlugin));
 
         registry
     }
@@ -166,6 +168,9 @@ impl ExpressionConverter {
                     data_type: target_type,
                 });
             }
+            sql_ast::Expr::Interval(interval) => {
+                return Ok(Expr::Literal(self.convert_interval(interval)?));
+            }
             sql_ast::Expr::Array(sql_ast::Array { elem, .. }) => {
                 let list = elem
                     .iter()
@@ -180,6 +185,16 @@ impl ExpressionConverter {
                         expr: Box::new(self.convert(*expr.clone(), query_converter)?),
                     });
                 }
+                // Fold negative numeric literals so i64::MIN stays an integer.
+                if matches!(op, sql_ast::UnaryOperator::Minus)
+                    && let sql_ast::Expr::Value(val_with_span) = expr.as_ref()
+                    && let sql_ast::Value::Number(n, _) = &val_with_span.value
+                {
+                    return Ok(Expr::Literal(crate::core::Value::parse_number(&format!(
+                        "-{}",
+                        n
+                    ))?));
+                }
                 let converted = self.convert(*expr.clone(), query_converter)?;
                 let op = match op {
                     sql_ast::UnaryOperator::Minus => AstUnary::Minus,
@@ -239,6 +254,40 @@ impl ExpressionConverter {
         }
     }
 
+    /// `INTERVAL '7 days'` or `INTERVAL '7' DAY`; see `parse_interval` for the units.
+    fn convert_interval(&self, interval: &sql_ast::Interval) -> Result<crate::core::Value> {
+        if interval.last_field.is_some() {
+            return Err(crate::core::DbError::UnsupportedOperation(format!(
+                "Unsupported interval: {}",
+                interval
+            )));
+        }
+        let text = match interval.value.as_ref() {
+            sql_ast::Expr::Value(value) => match &value.value {
+                sql_ast::Value::SingleQuotedString(s) | sql_ast::Value::Number(s, _) => s.clone(),
+                _ => {
+                    return Err(crate::core::DbError::UnsupportedOperation(format!(
+                        "Interval value must be a literal: {}",
+                        interval
+                    )));
+                }
+            },
+            _ => {
+                return Err(crate::core::DbError::UnsupportedOperation(format!(
+                    "Interval value must be a literal: {}",
+                    interval
+                )));
+            }
+        };
+        let text = match &interval.leading_field {
+            Some(field) => format!("{} {}", text, field),
+            None => text,
+        };
+        Ok(crate::core::Value::Interval(
+            crate::core::value::parse_interval(&text)?,
+        ))
+    }
+
     /// Helper для конвертации бинарных операторов
     pub fn convert_binary_op(
         &self,
@@ -267,6 +316,8 @@ impl ExpressionConverter {
             SqlOp::Arrow => Ok(BinaryOp::Arrow),
             SqlOp::LongArrow => Ok(BinaryOp::LongArrow),
 
+            SqlOp::AtArrow => Ok(BinaryOp::Contains),
+
             _ => Err(crate::core::DbError::UnsupportedOperation(format!(
                 "Unsupported binary operator: {:?}",
                 op
@@ -286,19 +337,35 @@ fn convert_sql_data_type(dt: &sql_ast::DataType) -> Result<crate::core::DataType
     match dt {
         sql_ast::DataType::Int(_)
         | sql_ast::DataType::Integer(_)
+        | sql_ast::DataType::SmallInt(_)
         | sql_ast::DataType::BigInt(_) => Ok(DataType::Integer),
         sql_ast::DataType::Float(_) | sql_ast::DataType::Double(_) | sql_ast::DataType::Real => {
             Ok(DataType::Float)
         }
-        sql_ast::DataType::Text
-        | sql_ast::DataType::Varchar(_)
-        | sql_ast::DataType::Char(_)
-        | sql_ast::DataType::String(_) => Ok(DataType::Text),
+        sql_ast::DataType::Text | sql_ast::DataType::Char(_) | sql_ast::DataType::String(_) => {
+            Ok(DataType::Text)
+        }
+        sql_ast::DataType::Varchar(Some(sql_ast::CharacterLength::IntegerLength {
+            length,
+            ..
+        })) => Ok(DataType::Varchar(*length as usize)),
+        sql_ast::DataType::Varchar(_) => Ok(DataType::Text),
         sql_ast::DataType::Boolean | sql_ast::DataType::Bool => Ok(DataType::Boolean),
         sql_ast::DataType::Timestamp(_, _) => Ok(DataType::Timestamp),
         sql_ast::DataType::Date => Ok(DataType::Date),
+        sql_ast::DataType::Interval { .. } => Ok(DataType::Interval),
         sql_ast::DataType::Uuid => Ok(DataType::Uuid),
         sql_ast::DataType::JSON | sql_ast::DataType::JSONB => Ok(DataType::Json),
+        sql_ast::DataType::Enum(members, _) => Ok(DataType::Enum(
+            members
+                .iter()
+                .map(|member| match member {
+                    sql_ast::EnumMember::Name(name) | sql_ast::EnumMember::NamedValue(name, _) => {
+                        name.clone()
+                    }
+                })
+                .collect(),
+        )),
         sql_ast::DataType::Array(elem) => match elem {
             sql_ast::ArrayElemTypeDef::AngleBracket(inner)
             | sql_ast::ArrayElemTypeDef::SquareBracket(inner, _)
@@ -0,0 +1,226 @@
+use crate::core::value::interval_text;
+use crate::core::{DataType, DbError, Result, Value};
+
+/// Options for `Client::import_csv`
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    /// Field delimiter
+    pub delimiter: char,
+
+    /// Treat unquoted empty fields as NULL
+    pub empty_as_null: bool,
+
+    /// Number of rows sent per INSERT statement
+    pub batch_size: usize,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            empty_as_null: true,
+            batch_size: 500,
+        }
+    }
+}
+
+impl CsvImportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the field delimiter
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set whether unquoted empty fields become NULL
+    pub fn empty_as_null(mut self, empty_as_null: bool) -> Self {
+        self.empty_as_null = empty_as_null;
+        self
+    }
+
+    /// Set the number of rows per INSERT statement
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CsvField {
+    pub value: String,
+    pub quoted: bool,
+}
+
+/// A parsed record with its 1-based row number (the header is row 1)
+#[derive(Debug, Clone)]
+pub(crate) struct CsvRecord {
+    pub row: usize,
+    pub fields: Vec<CsvField>,
+}
+
+/// Parse RFC 4180 CSV text into records, skipping blank lines
+pub(crate) fn parse_csv(input: &str, delimiter: char) -> Result<Vec<CsvRecord>> {
+    let mut records = Vec::new();
+    let mut fields: Vec<CsvField> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut after_quote = false;
+    let mut row = 1;
+    let mut chars = input.chars().peekable();
+
+    let mut end_record =
+        |fields: &mut Vec<CsvField>, field: &mut String, quoted: &mut bool, row: &mut usize| {
+            let blank = fields.is_empty() && field.is_empty() && !*quoted;
+            if !blank {
+                fields.push(CsvField {
+                    value: std::mem::take(field),
+                    quoted: *quoted,
+                });
+                records.push(CsvRecord {
+                    row: *row,
+                    fields: std::mem::take(fields),
+                });
+                *row += 1;
+            }
+            *quoted = false;
+        };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                    after_quote = true;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+
+        if c == delimiter {
+            fields.push(CsvField {
+                value: std::mem::take(&mut field),
+                quoted,
+            });
+            quoted = false;
+            after_quote = false;
+        } else if c == '\r' || c == '\n' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            end_record(&mut fields, &mut field, &mut quoted, &mut row);
+            after_quote = false;
+        } else if after_quote {
+            return Err(DbError::ParseError(format!(
+                "CSV row {}: unexpected character '{}' after closing quote",
+                row, c
+            )));
+        } else if c == '"' {
+            if !field.is_empty() {
+                return Err(DbError::ParseError(format!(
+                    "CSV row {}: quote inside unquoted field",
+                    row
+                )));
+            }
+            in_quotes = true;
+            quoted = true;
+        } else {
+            field.push(c);
+        }
+    }
+
+    if in_quotes {
+        return Err(DbError::ParseError(format!(
+            "CSV row {}: unterminated quoted field",
+            row
+        )));
+    }
+    end_record(&mut fields, &mut field, &mut quoted, &mut row);
+
+    Ok(records)
+}
+
+/// Append a field to `out`, quoting it when required by RFC 4180
+pub(crate) fn write_csv_field(out: &mut String, value: &str, delimiter: char) {
+    let needs_quotes = value
+        .chars()
+        .any(|c| c == delimiter || c == '"' || c == '\n' || c == '\r');
+    if needs_quotes {
+        out.push('"');
+        out.push_str(&value.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(value);
+    }
+}
+
+/// Render a value as CSV text (NULL becomes an empty field)
+pub(crate) fn value_to_csv_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Timestamp(ts) => ts.to_rfc3339(),
+        Value::Json(json) => json.to_string(),
+        Value::Interval(micros) => interval_text(*micros),
+        other => other.to_string(),
+    }
+}
+
+/// Coerce a CSV field into a value of the target column type
+pub(crate) fn coerce_csv_field(
+    field: &CsvField,
+    data_type: &DataType,
+    empty_as_null: bool,
+) -> Result<Value> {
+    if field.value.is_empty() && !field.quoted && empty_as_null {
+        return Ok(Value::Null);
+    }
+    coerce_csv_text(&field.value, data_type)
+}
+
+fn coerce_csv_text(text: &str, data_type: &DataType) -> Result<Value> {
+    let trimmed = text.trim();
+    match data_type {
+        DataType::Integer => trimmed
+            .parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| DbError::TypeMismatch(format!("Invalid INTEGER value: '{}'", text))),
+        DataType::Float => trimmed
+            .parse::<f64>()
+            .map(Value::Float)
+            .map_err(|_| DbError::TypeMismatch(format!("Invalid FLOAT value: '{}'", text))),
+        DataType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "t" | "1" | "yes" => Ok(Value::Boolean(true)),
+            "false" | "f" | "0" | "no" => Ok(Value::Boolean(false)),
+            _ => Err(DbError::TypeMismatch(format!(
+                "Invalid BOOLEAN value: '{}'",
+                text
+            ))),
+        },
+        DataType::Array(inner) => {
+            let content = trimmed
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .or_else(|| trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')))
+                .ok_or_else(|| DbError::TypeMismatch(format!("Invalid Array format: {}", text)))?;
+            if content.trim().is_empty() {
+                return Ok(Value::Array(Vec::new()));
+            }
+            content
+                .split(',')
+                .map(|item| coerce_csv_text(item.trim().trim_matches('"'), inner))
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array)
+        }
+        DataType::Text | DataType::Unknown => Ok(Value::Text(text.to_string())),
+        DataType::Varchar(_) => data_type.cast_value(&Value::Text(text.to_string())),
+        other => other.cast_value(&Value::Text(trimmed.to_string())),
+    }
+}
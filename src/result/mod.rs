@@ -1,4 +1,8 @@
 #![allow(clippy::module_inception)]
+pub(crate) mod csv;
 pub mod result;
+pub mod stream;
 
+pub use csv::CsvImportOptions;
 pub use result::QueryResult;
+pub use stream::RowStream;
@@ -1,10 +1,13 @@
-use crate::core::{Column, Row};
+use super::csv::{value_to_csv_text, write_csv_field};
+use crate::core::{Column, DbError, Result, Row, Value};
+use chrono::FixedOffset;
 
 #[derive(Debug, Clone)]
 pub struct QueryResult {
     columns: Vec<Column>,
     rows: Vec<Row>,
-    affected_rows: Option<usize>, // For UPDATE/DELETE operations
+    affected_rows: Option<usize>, // For INSERT/UPDATE/DELETE operations
+    timezone: FixedOffset,        // Offset used to render timestamps
 }
 
 impl QueryResult {
@@ -13,6 +16,7 @@ impl QueryResult {
             columns: Vec::new(),
             rows: Vec::new(),
             affected_rows: None,
+            timezone: utc_offset(),
         }
     }
 
@@ -27,6 +31,16 @@ impl QueryResult {
             columns,
             rows,
             affected_rows: None,
+            timezone: utc_offset(),
+        }
+    }
+
+    pub fn inserted(count: usize) -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            affected_rows: Some(count),
+            timezone: utc_offset(),
         }
     }
 
@@ -35,6 +49,7 @@ impl QueryResult {
             columns: Vec::new(),
             rows: Vec::new(),
             affected_rows: Some(count),
+            timezone: utc_offset(),
         }
     }
 
@@ -43,9 +58,13 @@ impl QueryResult {
             columns: Vec::new(),
             rows: Vec::new(),
             affected_rows: Some(count),
+            timezone: utc_offset(),
         }
     }
 
+    /// Rows written by a DML statement: inserted rows for INSERT, rows matched
+    /// by the WHERE clause for UPDATE (even if no value changed) and removed
+    /// rows for DELETE. `None` for SELECT, DDL and transaction control.
     pub fn affected_rows(&self) -> Option<usize> {
         self.affected_rows
     }
@@ -72,6 +91,22 @@ impl QueryResult {
         self.rows.iter()
     }
 
+    /// Render timestamps at `timezone` (set by `Connection` from `SET timezone`)
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Offset used to render timestamps, UTC by default
+    pub fn timezone(&self) -> FixedOffset {
+        self.timezone
+    }
+
+    /// Render a value for display, converting timestamps to the result's timezone
+    pub fn render_value(&self, value: &Value) -> String {
+        render_value_at(value, self.timezone)
+    }
+
     pub fn print(&self) {
         if self.columns.is_empty() {
             println!("Empty result set");
@@ -87,13 +122,53 @@ impl QueryResult {
         println!("\n{} row(s)", self.rows.len());
     }
 
+    /// Write the result as RFC 4180 CSV with a header row of column names
+    ///
+    /// NULL values are written as empty fields and timestamps as RFC 3339 at
+    /// the result's timezone.
+    pub fn to_csv<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        let mut line = String::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            let name = column.name.rsplit('.').next().unwrap_or(&column.name);
+            write_csv_field(&mut line, name, ',');
+        }
+        line.push_str("\r\n");
+
+        for row in &self.rows {
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    line.push(',');
+                }
+                match value {
+                    // Quoted so an empty string reads back differently from NULL
+                    Value::Text(text) if text.is_empty() => line.push_str("\"\""),
+                    Value::Timestamp(ts) => write_csv_field(
+                        &mut line,
+                        &ts.with_timezone(&self.timezone).to_rfc3339(),
+                        ',',
+                    ),
+                    _ => write_csv_field(&mut line, &value_to_csv_text(value), ','),
+                }
+            }
+            line.push_str("\r\n");
+        }
+
+        writer
+            .write_all(line.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|e| DbError::IoError(e.to_string()))
+    }
+
     fn calculate_column_widths(&self) -> Vec<usize> {
         let mut widths: Vec<usize> = self.columns.iter().map(|c| c.name.len()).collect();
 
         for row in &self.rows {
             for (i, value) in row.iter().enumerate() {
                 if let Some(width) = widths.get_mut(i) {
-                    *width = (*width).max(value.to_string().len());
+                    *width = (*width).max(self.render_value(value).len());
                 }
             }
         }
@@ -127,7 +202,7 @@ impl QueryResult {
             let row_str: Vec<String> = row
                 .iter()
                 .enumerate()
-                .map(|(i, val)| format!("{:width$}", val, width = widths[i]))
+                .map(|(i, val)| format!("{:width$}", self.render_value(val), width = widths[i]))
                 .collect();
 
             println!("{}", row_str.join(" | "));
@@ -135,6 +210,22 @@ impl QueryResult {
     }
 }
 
+pub(crate) fn utc_offset() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("zero offset is valid")
+}
+
+/// Timestamps are shown as `YYYY-MM-DD HH:MM:SS` at `timezone`; other values
+/// use their `Display` form.
+pub(crate) fn render_value_at(value: &Value, timezone: FixedOffset) -> String {
+    match value {
+        Value::Timestamp(t) => t
+            .with_timezone(&timezone)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        other => other.to_string(),
+    }
+}
+
 impl IntoIterator for QueryResult {
     type Item = Row;
     type IntoIter = std::vec::IntoIter<Row>;
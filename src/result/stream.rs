@@ -0,0 +1,58 @@
+use super::result::{render_value_at, utc_offset};
+use crate::core::{Column, Result, Row, Value};
+use chrono::FixedOffset;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Rows of a SELECT produced incrementally, see `Connection::execute_returning_stream`.
+///
+/// Dropping the stream cancels the query.
+pub struct RowStream {
+    columns: Vec<Column>,
+    rows: BoxStream<'static, Result<Row>>,
+    timezone: FixedOffset,
+}
+
+impl RowStream {
+    pub(crate) fn new(columns: Vec<Column>, rows: BoxStream<'static, Result<Row>>) -> Self {
+        Self {
+            columns,
+            rows,
+            timezone: utc_offset(),
+        }
+    }
+
+    /// Render timestamps at `timezone`, as `QueryResult::with_timezone` does
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Offset used to render timestamps, UTC by default
+    pub fn timezone(&self) -> FixedOffset {
+        self.timezone
+    }
+
+    /// Render a value for display, converting timestamps to the stream's timezone
+    pub fn render_value(&self, value: &Value) -> String {
+        render_value_at(value, self.timezone)
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Next row, or `None` once the result is exhausted.
+    pub async fn next_row(&mut self) -> Result<Option<Row>> {
+        self.rows.next().await.transpose()
+    }
+}
+
+impl Stream for RowStream {
+    type Item = Result<Row>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rows.poll_next_unpin(cx)
+    }
+}
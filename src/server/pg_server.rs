@@ -621,7 +621,9 @@ fn create_field_infos(columns: &[Column], default_format: FieldFormat) -> Vec<Fi
             let (pg_type, format) = match col.data_type {
                 DataType::Integer => (Type::INT8, default_format),
                 DataType::Float => (Type::FLOAT8, default_format),
-                DataType::Text => (Type::TEXT, default_format),
+                DataType::Text | DataType::Enum(_) | DataType::Varchar(_) => {
+                    (Type::TEXT, default_format)
+                }
                 DataType::Boolean => (Type::BOOL, default_format),
                 DataType::Timestamp => (Type::TIMESTAMP, default_format),
                 DataType::Date => (Type::DATE, default_format),
@@ -630,6 +632,7 @@ fn create_field_infos(columns: &[Column], default_format: FieldFormat) -> Vec<Fi
                 DataType::Array(_) => (Type::TEXT, FieldFormat::Text),
                 DataType::Json => (Type::TEXT, FieldFormat::Text),
                 DataType::Unknown => (Type::UNKNOWN, FieldFormat::Text),
+                DataType::Interval => (Type::INTERVAL, FieldFormat::Text),
             };
             FieldInfo::new(col.name.clone(), None, None, pg_type, format)
         })
@@ -659,7 +662,7 @@ fn encode_value(
         Value::Date(d) => encoder.encode_field(d),
         Value::Uuid(u) => encoder.encode_field(u.as_bytes()),
 
-        Value::Array(_) | Value::Json(_) => {
+        Value::Array(_) | Value::Json(_) | Value::Interval(_) => {
             // These should be handled by Text format check above because create_field_infos forces Text.
             // But if we reached here with Binary, fallback to string bytes.
             let s = format!("{}", value);
@@ -244,6 +244,23 @@ impl InMemoryStorage {
         Ok(tables)
     }
 
+    /// Roll back a transaction's writes to a savepoint captured by get_all_tables
+    pub async fn rollback_tx_to_savepoint(
+        &self,
+        saved_tables: &std::collections::HashMap<String, Table>,
+        tx_id: u64,
+    ) -> Result<()> {
+        for (name, saved) in saved_tables {
+            let Some(table_handle) = self.tables.get(name) else {
+                continue;
+            };
+            let mut table = table_handle.write().await;
+            table.rollback_tx_versions_since(saved, tx_id);
+        }
+
+        Ok(())
+    }
+
     /// Restore tables from a snapshot (for crash recovery)
     pub async fn restore_tables(
         &mut self,
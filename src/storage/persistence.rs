@@ -79,6 +79,14 @@ pub enum WalEntry {
         table_name: String,
         operation: AlterTableOperation,
     },
+    Savepoint {
+        tx_id: u64,
+        name: String,
+    },
+    RollbackToSavepoint {
+        tx_id: u64,
+        name: String,
+    },
 }
 
 impl WalEntry {
@@ -709,8 +717,10 @@ impl PersistenceManager {
 
         let mut committed = HashSet::new();
         let mut aborted = HashSet::new();
+        let mut savepoints: HashMap<u64, Vec<(String, usize)>> = HashMap::new();
+        let mut discarded = HashSet::new();
 
-        for entry in &wal_entries {
+        for (position, entry) in wal_entries.iter().enumerate() {
             match entry {
                 WalEntry::BeginTransaction(_tx_id) => {}
                 WalEntry::Commit(tx_id) => {
@@ -719,6 +729,29 @@ impl PersistenceManager {
                 WalEntry::Rollback(tx_id) => {
                     aborted.insert(*tx_id);
                 }
+                WalEntry::Savepoint { tx_id, name } => {
+                    savepoints
+                        .entry(*tx_id)
+                        .or_default()
+                        .push((name.clone(), position));
+                }
+                WalEntry::RollbackToSavepoint { tx_id, name } => {
+                    let Some(stack) = savepoints.get_mut(tx_id) else {
+                        continue;
+                    };
+                    let Some(index) = stack.iter().rposition(|(saved, _)| saved == name) else {
+                        continue;
+                    };
+                    let start = stack[index].1;
+                    stack.truncate(index + 1);
+                    for (discard, earlier) in
+                        wal_entries.iter().enumerate().take(position).skip(start)
+                    {
+                        if dml_entry_tx_id(earlier) == Some(*tx_id) {
+                            discarded.insert(discard);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
@@ -730,7 +763,10 @@ impl PersistenceManager {
             max_tx_id: u64::MAX,
         };
 
-        for entry in wal_entries {
+        for (position, entry) in wal_entries.into_iter().enumerate() {
+            if discarded.contains(&position) {
+                continue;
+            }
             match entry {
                 WalEntry::Insert { tx_id, table, row } => {
                     if committed.contains(&tx_id) && !aborted.contains(&tx_id) {
@@ -834,7 +870,11 @@ impl PersistenceManager {
                         }
                     }
                 }
-                WalEntry::BeginTransaction(_) | WalEntry::Commit(_) | WalEntry::Rollback(_) => {}
+                WalEntry::BeginTransaction(_)
+                | WalEntry::Commit(_)
+                | WalEntry::Rollback(_)
+                | WalEntry::Savepoint { .. }
+                | WalEntry::RollbackToSavepoint { .. } => {}
             }
         }
         Ok(Some(DatabaseSnapshot::new(tables, views)))
@@ -854,6 +894,15 @@ impl PersistenceManager {
     }
 }
 
+fn dml_entry_tx_id(entry: &WalEntry) -> Option<u64> {
+    match entry {
+        WalEntry::Insert { tx_id, .. }
+        | WalEntry::Update { tx_id, .. }
+        | WalEntry::Delete { tx_id, .. } => Some(*tx_id),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1039,6 +1088,68 @@ mod tests {
         assert_eq!(rows[0][0], crate::core::Value::Integer(2));
     }
 
+    #[test]
+    fn test_recovery_discards_entries_rolled_back_to_savepoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut persistence =
+            PersistenceManager::new(temp_dir.path(), DurabilityMode::Sync).unwrap();
+        let schema = TableSchema::new("users", vec![Column::new("id", DataType::Integer)]);
+
+        persistence.log(&WalEntry::BeginTransaction(1)).unwrap();
+        persistence
+            .log(&WalEntry::CreateTable {
+                tx_id: 1,
+                name: "users".to_string(),
+                schema,
+            })
+            .unwrap();
+        persistence.log(&WalEntry::Commit(1)).unwrap();
+
+        let insert = |tx_id: u64, id: i64| WalEntry::Insert {
+            tx_id,
+            table: "users".to_string(),
+            row: vec![crate::core::Value::Integer(id)],
+        };
+        persistence.log(&WalEntry::BeginTransaction(2)).unwrap();
+        persistence.log(&insert(2, 1)).unwrap();
+        persistence
+            .log(&WalEntry::Savepoint {
+                tx_id: 2,
+                name: "sp".to_string(),
+            })
+            .unwrap();
+        persistence.log(&insert(2, 2)).unwrap();
+        persistence
+            .log(&WalEntry::RollbackToSavepoint {
+                tx_id: 2,
+                name: "sp".to_string(),
+            })
+            .unwrap();
+        persistence.log(&insert(2, 3)).unwrap();
+        persistence.log(&WalEntry::Commit(2)).unwrap();
+
+        let recovered = persistence.recover().unwrap().unwrap();
+        let table = recovered.tables.get("users").unwrap();
+        let snapshot = Snapshot {
+            tx_id: 0,
+            active: Arc::new(HashSet::new()),
+            aborted: Arc::new(HashSet::new()),
+            max_tx_id: u64::MAX,
+        };
+        let ids: Vec<_> = table
+            .scan(&snapshot)
+            .into_iter()
+            .map(|row| row[0].clone())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                crate::core::Value::Integer(1),
+                crate::core::Value::Integer(3)
+            ]
+        );
+    }
+
     #[test]
     fn test_recovery_views() {
         let temp_dir = TempDir::new().unwrap();
@@ -311,6 +311,30 @@ impl Table {
         })
     }
 
+    /// Visible rows with id >= `start_id`, at most `limit` of them, plus the
+    /// id to resume from (`None` once the table is exhausted).
+    pub fn scan_batch(
+        &self,
+        snapshot: &Snapshot,
+        start_id: usize,
+        limit: usize,
+    ) -> (Vec<Row>, Option<usize>) {
+        let mut rows = Vec::with_capacity(limit.min(self.rows.len()));
+        for (id, versions) in self.rows.range(start_id..) {
+            if rows.len() >= limit {
+                return (rows, Some(*id));
+            }
+            if let Some(version) = versions
+                .iter()
+                .rev()
+                .find(|version| self.is_visible(version, snapshot))
+            {
+                rows.push(version.row.clone());
+            }
+        }
+        (rows, None)
+    }
+
     pub fn scan(&self, snapshot: &Snapshot) -> Vec<Row> {
         self.scan_iter(snapshot).cloned().collect()
     }
@@ -393,6 +417,10 @@ impl Table {
         }
 
         if let Some(xmax) = row.xmax {
+            // Deleted earlier in this transaction, so the key may be reused.
+            if xmax == snapshot.tx_id {
+                return false;
+            }
             if snapshot.aborted.contains(&xmax) {
                 return true;
             }
@@ -687,6 +715,66 @@ impl Table {
         }
     }
 
+    pub fn rollback_tx_versions_since(&mut self, saved: &Table, tx_id: u64) -> bool {
+        // Drop versions `tx_id` appended after `saved` was cloned and clear the
+        // xmax marks it placed since then; other transactions' versions stay.
+        // Vacuum may have removed other versions in between, so versions are
+        // matched by owner rather than by position: the transaction's own
+        // versions are never vacuumed while it runs and keep their order, and
+        // it can only have marked the one version of another transaction that
+        // was visible to it.
+        let mut updates = Vec::new();
+
+        for (id, versions) in &self.rows {
+            let saved_versions = saved.rows.get(id).map(Vec::as_slice).unwrap_or_default();
+            let mut saved_own = saved_versions
+                .iter()
+                .filter(|version| version.xmin == tx_id);
+            let marked_before_savepoint = saved_versions
+                .iter()
+                .any(|version| version.xmin != tx_id && version.xmax == Some(tx_id));
+            let mut changed = false;
+            let mut new_versions = Vec::with_capacity(versions.len());
+
+            for version in versions {
+                let mut version = version.clone();
+                if version.xmin == tx_id {
+                    let Some(saved_version) = saved_own.next() else {
+                        changed = true;
+                        continue;
+                    };
+                    if version.xmax != saved_version.xmax {
+                        version.xmax = saved_version.xmax;
+                        changed = true;
+                    }
+                } else if version.xmax == Some(tx_id) && !marked_before_savepoint {
+                    // update and delete only mark versions whose xmax is unset
+                    version.xmax = None;
+                    changed = true;
+                }
+                new_versions.push(version);
+            }
+
+            if changed {
+                updates.push((*id, new_versions));
+            }
+        }
+
+        if updates.is_empty() {
+            return false;
+        }
+
+        for (id, new_versions) in updates {
+            if new_versions.is_empty() {
+                self.rows.remove(&id);
+            } else {
+                self.rows.insert(id, new_versions);
+            }
+        }
+        self.rebuild_indexes();
+        true
+    }
+
     pub fn vacuum(&mut self, min_active_tx_id: u64, aborted: &HashSet<u64>) -> usize {
         let mut freed_versions = 0;
         let _empty_rows: Vec<usize> = Vec::new();
@@ -820,4 +908,49 @@ mod tests {
             "expected stale index entries to be removed on vacuum"
         );
     }
+
+    #[test]
+    fn rollback_to_savepoint_survives_vacuum_of_older_versions() {
+        let schema = TableSchema::new("t", vec![Column::new("v", DataType::Integer)]);
+        let mut table = Table::new(schema);
+        let untouched = table.insert(vec![Value::Integer(1)], &snapshot(1)).unwrap();
+        let rewritten = table
+            .insert(vec![Value::Integer(10)], &snapshot(1))
+            .unwrap();
+        for row_id in [untouched, rewritten] {
+            table
+                .update(row_id, vec![Value::Integer(2)], &snapshot(2))
+                .unwrap();
+        }
+
+        // tx 5 rewrites one row, takes a savepoint, then vacuum drops the
+        // versions tx 2 replaced and tx 5 writes both rows again.
+        let tx5 = snapshot(5);
+        table
+            .update(rewritten, vec![Value::Integer(50)], &tx5)
+            .unwrap();
+        let saved = table.clone();
+        assert_eq!(table.vacuum(5, &HashSet::new()), 2);
+        table
+            .update(untouched, vec![Value::Integer(51)], &tx5)
+            .unwrap();
+        table
+            .update(rewritten, vec![Value::Integer(52)], &tx5)
+            .unwrap();
+
+        assert!(table.rollback_tx_versions_since(&saved, 5));
+        assert_eq!(
+            table.get_visible_row(untouched, &tx5),
+            Some(vec![Value::Integer(2)])
+        );
+        assert_eq!(
+            table.get_visible_row(rewritten, &tx5),
+            Some(vec![Value::Integer(50)])
+        );
+        assert_eq!(
+            table.get_visible_row(untouched, &snapshot(6)),
+            Some(vec![Value::Integer(2)])
+        );
+        assert_eq!(table.version_count(), 3);
+    }
 }
@@ -0,0 +1,137 @@
+//! Reusable contract assertions for applications built on managed collections
+//! and the entity runtime.
+
+use crate::core::Result;
+use crate::persist::PersistEntity;
+use crate::persist::app::{
+    ManagedConflictKind, ManagedPersistVec, PersistIndexedCollection, classify_managed_conflict,
+};
+use crate::persist::runtime::{
+    PersistEntityRuntime, RuntimeCommandEnvelope, RuntimeEnvelopeApplyResult,
+};
+
+/// Creates `item` and asserts it is listed, persisted and starts at version 1.
+/// Returns the persist id of the created item.
+pub async fn assert_create_contract<V>(
+    collection: &mut ManagedPersistVec<V>,
+    item: V::Item,
+) -> Result<String>
+where
+    V: PersistIndexedCollection,
+{
+    let persist_id = item.persist_id().to_string();
+    let before = collection.list().len();
+    collection.create(item).await?;
+
+    assert_eq!(
+        collection.list().len(),
+        before + 1,
+        "create contract: collection size did not grow by one"
+    );
+    let created = collection
+        .get(&persist_id)
+        .unwrap_or_else(|| panic!("create contract: '{}' is not readable", persist_id));
+    assert!(
+        created.metadata().persisted,
+        "create contract: '{}' is not persisted",
+        persist_id
+    );
+    assert_eq!(
+        created.metadata().version,
+        1,
+        "create contract: '{}' did not start at version 1",
+        persist_id
+    );
+    Ok(persist_id)
+}
+
+/// Asserts that a stale version is rejected as a conflict without mutating the
+/// item, and that the current version is accepted and bumped.
+pub async fn assert_optimistic_lock<V, F>(
+    collection: &mut ManagedPersistVec<V>,
+    persist_id: &str,
+    mutator: F,
+) -> Result<()>
+where
+    V: PersistIndexedCollection,
+    F: Fn(&mut V::Item) -> Result<()>,
+{
+    let current_version = collection
+        .get(persist_id)
+        .unwrap_or_else(|| panic!("optimistic lock: '{}' does not exist", persist_id))
+        .metadata()
+        .version;
+
+    let err = collection
+        .update_if_version(persist_id, current_version + 1, &mutator)
+        .await
+        .expect_err("optimistic lock: stale version was accepted");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::VersionConflict),
+        "optimistic lock: unexpected error {}",
+        err
+    );
+    assert_eq!(
+        collection
+            .get(persist_id)
+            .map(|item| item.metadata().version),
+        Some(current_version),
+        "optimistic lock: rejected update changed the version"
+    );
+
+    let updated = collection
+        .update_if_version(persist_id, current_version, &mutator)
+        .await?;
+    assert!(updated, "optimistic lock: current version was not applied");
+    assert_eq!(
+        collection
+            .get(persist_id)
+            .map(|item| item.metadata().version),
+        Some(current_version + 1),
+        "optimistic lock: accepted update did not bump the version"
+    );
+    Ok(())
+}
+
+/// Applies `envelope` twice and asserts the second apply is an idempotent
+/// replay returning the original state. The envelope must carry an
+/// idempotency key.
+pub async fn assert_idempotency_replay(
+    runtime: &mut PersistEntityRuntime,
+    envelope: RuntimeCommandEnvelope,
+) -> Result<RuntimeEnvelopeApplyResult> {
+    assert!(
+        envelope.idempotency_key.is_some(),
+        "idempotency replay: envelope has no idempotency key"
+    );
+
+    let entity_type = envelope.entity_type.clone();
+    let entity_id = envelope.entity_id.clone();
+    let first = runtime.apply_command_envelope(envelope.clone()).await?;
+    assert!(
+        !first.idempotent_replay,
+        "idempotency replay: first apply was reported as a replay"
+    );
+
+    let replay = runtime.apply_command_envelope(envelope).await?;
+    assert!(
+        replay.idempotent_replay,
+        "idempotency replay: second apply was executed again"
+    );
+    assert_eq!(
+        replay.envelope_id, first.envelope_id,
+        "idempotency replay: replay returned a different envelope"
+    );
+    assert_eq!(
+        replay.state, first.state,
+        "idempotency replay: replay returned a different state"
+    );
+
+    let current = runtime.get_state(&entity_type, &entity_id)?;
+    assert_eq!(
+        current.metadata.version, first.state.metadata.version,
+        "idempotency replay: stored entity was mutated by the replay"
+    );
+    Ok(first)
+}
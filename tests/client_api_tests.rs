@@ -3,7 +3,7 @@ use rustmemodb::core::Value;
 ///
 /// Tests for the high-level Client API (PostgreSQL/MySQL-like interface)
 /// Run with: cargo test --test client_api_tests
-use rustmemodb::{Client, ConnectionConfig};
+use rustmemodb::{Client, ConnectionConfig, CsvImportOptions};
 use std::time::Duration;
 
 #[tokio::test]
@@ -390,3 +390,398 @@ async fn test_client_invalid_url() {
         assert!(result.is_err(), "Should fail for invalid URL: {}", url);
     }
 }
+
+#[tokio::test]
+async fn test_client_csv_round_trip_keeps_intervals() {
+    let client = Client::connect_local("admin", "adminpass").await.unwrap();
+    for table in ["csv_intervals", "csv_intervals_copy"] {
+        client
+            .execute(&format!(
+                "CREATE TABLE {} (id INTEGER, wait INTERVAL)",
+                table
+            ))
+            .await
+            .unwrap();
+    }
+    client
+        .execute("INSERT INTO csv_intervals VALUES (1, INTERVAL '1 day 2 hours'), (2, '-1500 ms')")
+        .await
+        .unwrap();
+
+    let source = client
+        .query("SELECT id, wait FROM csv_intervals ORDER BY id")
+        .await
+        .unwrap();
+    let mut out = Vec::new();
+    source.to_csv(&mut out).unwrap();
+    let imported = client
+        .import_csv(
+            "csv_intervals_copy",
+            out.as_slice(),
+            CsvImportOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(imported, 2);
+
+    let copy = client
+        .query("SELECT id, wait FROM csv_intervals_copy ORDER BY id")
+        .await
+        .unwrap();
+    assert_eq!(copy.rows(), source.rows());
+    assert_eq!(copy.rows()[1][1], Value::Interval(-1_500_000));
+}
+
+#[tokio::test]
+async fn test_client_csv_round_trip() {
+    let client = Client::connect_local("admin", "adminpass").await.unwrap();
+    client
+        .execute("CREATE TABLE csv_items (id INTEGER, name TEXT, price FLOAT, active BOOLEAN)")
+        .await
+        .unwrap();
+
+    let csv = "name,id,price,active\r\n\"Widget, large\",1,9.5,true\r\n\"Say \"\"hi\"\"\nthere\",2,,0\r\n\"\",3,1,f\r\n";
+    let imported = client
+        .import_csv("csv_items", csv.as_bytes(), CsvImportOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(imported, 3);
+
+    let result = client
+        .query("SELECT id, name, price, active FROM csv_items ORDER BY id")
+        .await
+        .unwrap();
+    assert_eq!(result.rows()[0][1], Value::Text("Widget, large".into()));
+    assert_eq!(result.rows()[1][1], Value::Text("Say \"hi\"\nthere".into()));
+    assert_eq!(result.rows()[1][2], Value::Null);
+    assert_eq!(result.rows()[1][3], Value::Boolean(false));
+    assert_eq!(result.rows()[2][1], Value::Text(String::new()));
+
+    let mut out = Vec::new();
+    result.to_csv(&mut out).unwrap();
+    let exported = String::from_utf8(out).unwrap();
+    assert_eq!(
+        exported,
+        "id,name,price,active\r\n1,\"Widget, large\",9.5,true\r\n2,\"Say \"\"hi\"\"\nthere\",,false\r\n3,\"\",1,false\r\n"
+    );
+
+    let copy = Client::connect_local("admin", "adminpass").await.unwrap();
+    copy.execute("CREATE TABLE csv_items (id INTEGER, name TEXT, price FLOAT, active BOOLEAN)")
+        .await
+        .unwrap();
+    let reimported = copy
+        .import_csv(
+            "csv_items",
+            exported.as_bytes(),
+            CsvImportOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(reimported, 3);
+}
+
+#[tokio::test]
+async fn test_client_csv_import_reports_row_and_rolls_back() {
+    let client = Client::connect_local("admin", "adminpass").await.unwrap();
+    client
+        .execute("CREATE TABLE csv_scores (id INTEGER, score INTEGER)")
+        .await
+        .unwrap();
+
+    let csv = "id;score\n1;10\n2;ten\n";
+    let err = client
+        .import_csv(
+            "csv_scores",
+            csv.as_bytes(),
+            CsvImportOptions::new().delimiter(';'),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("CSV row 3"), "{}", err);
+    assert!(err.to_string().contains("score"), "{}", err);
+
+    let unterminated = "id,score\n1,\"10\n";
+    let err = client
+        .import_csv(
+            "csv_scores",
+            unterminated.as_bytes(),
+            CsvImportOptions::default(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unterminated"), "{}", err);
+
+    let count = client.query("SELECT * FROM csv_scores").await.unwrap();
+    assert_eq!(count.row_count(), 0);
+}
+
+#[tokio::test]
+async fn test_client_csv_import_quotes_table_and_column_names() {
+    let client = Client::connect_local("admin", "adminpass").await.unwrap();
+    client
+        .execute("CREATE TABLE \"csv order\" (id INTEGER, \"select\" TEXT)")
+        .await
+        .unwrap();
+    client
+        .execute("CREATE TABLE csv_keep (id INTEGER)")
+        .await
+        .unwrap();
+
+    let imported = client
+        .import_csv(
+            "csv order",
+            "id,select\n1,first\n".as_bytes(),
+            CsvImportOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(imported, 1);
+    let result = client
+        .query("SELECT \"select\" FROM \"csv order\"")
+        .await
+        .unwrap();
+    assert_eq!(result.rows()[0][0], Value::Text("first".into()));
+
+    // The table argument is an identifier, never spliced in as SQL
+    assert!(
+        client
+            .import_csv(
+                "csv_keep; DROP TABLE csv_keep",
+                "id\n1\n".as_bytes(),
+                CsvImportOptions::default(),
+            )
+            .await
+            .is_err()
+    );
+    assert!(client.query("SELECT * FROM csv_keep").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_connection_execute_returning_stream() {
+    use futures::TryStreamExt;
+
+    let client = Client::connect_local("admin", "adminpass").await.unwrap();
+    client
+        .execute("CREATE TABLE stream_events (id INTEGER, kind TEXT)")
+        .await
+        .unwrap();
+    for chunk in 0..25 {
+        let values = (0..100)
+            .map(|offset| {
+                let id = chunk * 100 + offset;
+                let kind = if id % 2 == 0 { "even" } else { "odd" };
+                format!("({}, '{}')", id, kind)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        client
+            .execute(&format!("INSERT INTO stream_events VALUES {}", values))
+            .await
+            .unwrap();
+    }
+
+    let mut conn = client.get_connection().await.unwrap();
+    let mut stream = conn
+        .connection()
+        .execute_returning_stream("SELECT id FROM stream_events WHERE kind = 'even'")
+        .await
+        .unwrap();
+    assert_eq!(stream.columns().len(), 1);
+    assert_eq!(stream.columns()[0].name, "id");
+
+    let first = stream.next_row().await.unwrap().unwrap();
+    assert_eq!(first, vec![Value::Integer(0)]);
+
+    // Rows written after the stream was opened are not visible to it.
+    client
+        .execute("INSERT INTO stream_events VALUES (5000, 'even')")
+        .await
+        .unwrap();
+    let rest: Vec<_> = stream.try_collect().await.unwrap();
+    assert_eq!(rest.len(), 1249);
+    assert_eq!(rest.last().unwrap(), &vec![Value::Integer(2498)]);
+
+    let ordered: Vec<_> = conn
+        .connection()
+        .execute_returning_stream("SELECT id FROM stream_events ORDER BY id DESC LIMIT 2")
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(
+        ordered,
+        vec![vec![Value::Integer(5000)], vec![Value::Integer(2499)]]
+    );
+
+    assert!(
+        conn.connection()
+            .execute_returning_stream("DELETE FROM stream_events")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_connection_create_and_use_isolated_databases() {
+    let config = ConnectionConfig::new("admin", "adminpass").database("main");
+    let client = Client::connect_with_config(config).await.unwrap();
+    client
+        .execute("CREATE TABLE db_scoped_accounts (id INTEGER)")
+        .await
+        .unwrap();
+    client
+        .execute("CREATE DATABASE db_scoped_tenant")
+        .await
+        .unwrap();
+    assert!(
+        client
+            .execute("CREATE DATABASE db_scoped_tenant")
+            .await
+            .is_err()
+    );
+    client
+        .execute("CREATE DATABASE IF NOT EXISTS db_scoped_tenant")
+        .await
+        .unwrap();
+
+    let mut guard = client.get_connection().await.unwrap();
+    let conn = guard.connection();
+    assert_eq!(conn.current_database(), "main");
+    conn.execute("USE db_scoped_tenant").await.unwrap();
+    assert_eq!(conn.current_database(), "db_scoped_tenant");
+    assert!(
+        conn.execute("SELECT * FROM db_scoped_accounts")
+            .await
+            .is_err()
+    );
+    conn.execute("CREATE TABLE db_scoped_accounts (id INTEGER, name TEXT)")
+        .await
+        .unwrap();
+    conn.execute("INSERT INTO db_scoped_accounts VALUES (1, 'a')")
+        .await
+        .unwrap();
+    assert!(
+        conn.execute("DROP DATABASE db_scoped_tenant")
+            .await
+            .is_err()
+    );
+    assert!(conn.execute("USE missing").await.is_err());
+
+    conn.execute("USE main").await.unwrap();
+    let main_rows = conn
+        .execute("SELECT * FROM db_scoped_accounts")
+        .await
+        .unwrap();
+    assert_eq!(main_rows.row_count(), 0);
+    conn.execute("USE db_scoped_tenant").await.unwrap();
+    guard.close().await.unwrap();
+
+    // Pooled connections go back to the default database.
+    let mut guard = client.get_connection().await.unwrap();
+    assert_eq!(guard.connection().current_database(), "main");
+    guard.close().await.unwrap();
+
+    client
+        .execute("DROP DATABASE db_scoped_tenant")
+        .await
+        .unwrap();
+    client
+        .execute("DROP DATABASE IF EXISTS db_scoped_tenant")
+        .await
+        .unwrap();
+    assert!(
+        client
+            .execute("DROP DATABASE db_scoped_tenant")
+            .await
+            .is_err()
+    );
+    assert!(client.execute("DROP DATABASE main").await.is_err());
+}
+
+#[tokio::test]
+async fn test_connection_session_variables() {
+    let config = ConnectionConfig::new("admin", "adminpass").database("main");
+    let client = Client::connect_with_config(config).await.unwrap();
+    client
+        .execute("CREATE DATABASE IF NOT EXISTS session_vars_tenant")
+        .await
+        .unwrap();
+
+    let mut guard = client.get_connection().await.unwrap();
+    let conn = guard.connection();
+    let shown = conn.execute("SHOW timezone").await.unwrap();
+    assert_eq!(shown.rows()[0][0], Value::Text("UTC".to_string()));
+
+    conn.execute("SET timezone = '+03:00'").await.unwrap();
+    let noon = chrono::DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    assert_eq!(
+        conn.render_value(&Value::Timestamp(noon)),
+        "2024-01-01 15:00:00"
+    );
+    assert_eq!(conn.session_variable("TimeZone").unwrap(), "+03:00");
+
+    conn.execute("CREATE TABLE session_vars_events (at TIMESTAMP)")
+        .await
+        .unwrap();
+    conn.execute("INSERT INTO session_vars_events VALUES ('2024-01-01 12:00:00')")
+        .await
+        .unwrap();
+    let events = conn
+        .execute("SELECT at FROM session_vars_events")
+        .await
+        .unwrap();
+    assert_eq!(events.rows()[0][0], Value::Timestamp(noon));
+    assert_eq!(events.timezone().local_minus_utc(), 3 * 3600);
+    assert_eq!(
+        events.render_value(&events.rows()[0][0]),
+        "2024-01-01 15:00:00"
+    );
+    let mut csv = Vec::new();
+    events.to_csv(&mut csv).unwrap();
+    assert!(
+        String::from_utf8(csv)
+            .unwrap()
+            .contains("2024-01-01T15:00:00+03:00")
+    );
+    let mut stream = conn
+        .execute_returning_stream("SELECT at FROM session_vars_events")
+        .await
+        .unwrap();
+    assert_eq!(stream.timezone().local_minus_utc(), 3 * 3600);
+    let row = stream.next_row().await.unwrap().unwrap();
+    assert_eq!(row[0], Value::Timestamp(noon));
+    assert_eq!(stream.render_value(&row[0]), "2024-01-01 15:00:00");
+    drop(stream);
+    conn.execute("SET timezone TO utc").await.unwrap();
+    assert_eq!(conn.timezone().local_minus_utc(), 0);
+    assert!(conn.execute("SET timezone = 'Mars/Olympus'").await.is_err());
+
+    conn.execute("SET search_path = session_vars_tenant;")
+        .await
+        .unwrap();
+    assert_eq!(conn.current_database(), "session_vars_tenant");
+    assert!(conn.execute("SET search_path = missing").await.is_err());
+
+    // Only SESSION_VARIABLES are intercepted; other SET/SHOW reach the SQL parser.
+    assert!(conn.execute("SET work_mem = 64").await.is_err());
+    if let Err(err) = conn.execute("SHOW TABLES").await {
+        assert!(!err.to_string().contains("session variable"), "{err}");
+    }
+    let err = conn.session_variable("work_mem").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unknown session variable 'work_mem'. Supported: timezone, search_path"),
+        "{err}"
+    );
+    conn.execute("SET timezone = '-05:30'").await.unwrap();
+    guard.close().await.unwrap();
+
+    // Pooled connections go back to the session defaults.
+    let mut guard = client.get_connection().await.unwrap();
+    assert_eq!(guard.connection().current_database(), "main");
+    assert_eq!(guard.connection().timezone().local_minus_utc(), 0);
+    guard.close().await.unwrap();
+}
@@ -1,6 +1,6 @@
 use chrono::{NaiveDate, Utc};
 use rustmemodb::Client;
-use rustmemodb::core::{Result, Value};
+use rustmemodb::core::{DataType, Result, Value};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -77,3 +77,315 @@ async fn test_uuid_type() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_array_type_validation_and_operators() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+    client
+        .execute("CREATE TABLE posts (id INTEGER, tags TEXT[])")
+        .await?;
+
+    client
+        .execute("INSERT INTO posts VALUES (1, ARRAY['rust', 'db'])")
+        .await?;
+    client
+        .execute("INSERT INTO posts VALUES (2, ARRAY['go'])")
+        .await?;
+
+    let err = client
+        .execute("INSERT INTO posts VALUES (3, ARRAY['rust', 7])")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Array element"), "{}", err);
+
+    let result = client
+        .query("SELECT id FROM posts WHERE 'rust' = ANY(tags)")
+        .await?;
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows()[0][0], Value::Integer(1));
+
+    let result = client
+        .query("SELECT id FROM posts WHERE tags @> ARRAY['db', 'rust']")
+        .await?;
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows()[0][0], Value::Integer(1));
+
+    let result = client.query("SELECT tags FROM posts WHERE id = 2").await?;
+    assert_eq!(
+        result.rows()[0][0],
+        Value::Array(vec![Value::Text("go".to_string())])
+    );
+
+    // Array literals keep their JSON text form and read back into array columns.
+    let tags = Value::Array(vec![Value::Text("a, b".to_string())]);
+    let literal = rustmemodb::persist::value_to_sql_literal(&tags);
+    assert!(literal.starts_with("'["), "{}", literal);
+    client
+        .execute(&format!("INSERT INTO posts VALUES (4, {})", literal))
+        .await?;
+    let result = client.query("SELECT tags FROM posts WHERE id = 4").await?;
+    assert_eq!(result.rows()[0][0], tags);
+
+    // The uncast element path is local to INSERT; UNKNOWN is no cast target.
+    assert!(DataType::Unknown.cast_value(&Value::Integer(1)).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_integer_extremes_stay_integers() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+
+    client
+        .execute("CREATE TABLE extremes (id INTEGER, amount INTEGER, samples INTEGER[])")
+        .await?;
+    client
+        .execute(
+            "INSERT INTO extremes VALUES (1, -9223372036854775808, [9223372036854775807, -9223372036854775808])",
+        )
+        .await?;
+
+    let result = client
+        .query("SELECT amount, samples FROM extremes WHERE amount = -9223372036854775808")
+        .await?;
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows()[0][0], Value::Integer(i64::MIN));
+    assert_eq!(
+        result.rows()[0][1],
+        Value::Array(vec![Value::Integer(i64::MAX), Value::Integer(i64::MIN)])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_enum_column_rejects_unknown_values() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+
+    client
+        .execute("CREATE TABLE tickets (id INTEGER, status ENUM('open', 'closed'))")
+        .await?;
+    client
+        .execute("INSERT INTO tickets VALUES (1, 'open'), (2, NULL)")
+        .await?;
+
+    let err = client
+        .execute("INSERT INTO tickets VALUES (3, 'archived')")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("'archived'"), "{err}");
+
+    let err = client
+        .execute("UPDATE tickets SET status = 'pending' WHERE id = 1")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("'pending'"), "{err}");
+
+    client
+        .execute("UPDATE tickets SET status = 'closed' WHERE id = 1")
+        .await?;
+    let result = client
+        .query("SELECT status FROM tickets WHERE id = 1")
+        .await?;
+    assert_eq!(result.rows()[0][0], Value::Text("closed".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_varchar_length_is_enforced() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+
+    client
+        .execute("CREATE TABLE handles (id INTEGER, handle VARCHAR(5))")
+        .await?;
+    client
+        .execute("INSERT INTO handles VALUES (1, 'alice'), (2, 'ñandú'), (3, NULL)")
+        .await?;
+
+    let err = client
+        .execute("INSERT INTO handles VALUES (4, 'mallory')")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("longer than 5"), "{err}");
+
+    let err = client
+        .execute("UPDATE handles SET handle = 'bobbyby' WHERE id = 1")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("longer than 5"), "{err}");
+
+    let result = client
+        .query("SELECT id FROM handles WHERE handle = 'ñandú'")
+        .await?;
+    assert_eq!(result.rows()[0][0], Value::Integer(2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interval_arithmetic_filters_time_windows() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+    client
+        .execute("CREATE TABLE interval_events (id INTEGER, created_at TIMESTAMP)")
+        .await?;
+    let recent = Utc::now() - chrono::Duration::days(1);
+    let old = Utc::now() - chrono::Duration::days(10);
+    client
+        .execute(&format!(
+            "INSERT INTO interval_events VALUES (1, '{}'), (2, '{}')",
+            recent.to_rfc3339(),
+            old.to_rfc3339()
+        ))
+        .await?;
+
+    for predicate in [
+        "created_at > NOW() - INTERVAL '7 days'",
+        "created_at > CURRENT_TIMESTAMP - INTERVAL '7' DAY",
+        "created_at + INTERVAL '1 week' > NOW()",
+    ] {
+        let result = client
+            .query(&format!(
+                "SELECT id FROM interval_events WHERE {}",
+                predicate
+            ))
+            .await?;
+        assert_eq!(result.rows().len(), 1, "{}", predicate);
+        assert_eq!(result.rows()[0][0], Value::Integer(1), "{}", predicate);
+    }
+
+    let result = client
+        .query("SELECT created_at + INTERVAL '1 day 2 hours', NOW() - created_at FROM interval_events WHERE id = 1")
+        .await?;
+    let row = &result.rows()[0];
+    assert_eq!(
+        row[0],
+        Value::Timestamp(recent + chrono::Duration::hours(26))
+    );
+    match &row[1] {
+        Value::Interval(micros) => assert!(*micros >= 86_400_000_000, "{}", micros),
+        other => panic!("Expected Interval, got {:?}", other),
+    }
+
+    let err = client
+        .query("SELECT id FROM interval_events WHERE created_at > NOW() - INTERVAL '1 month'")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("no fixed length"), "{}", err);
+    let err = client
+        .query("SELECT id FROM interval_events WHERE created_at > NOW() - INTERVAL '9223372036854775807 weeks'")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Interval out of range"), "{}", err);
+
+    assert_eq!(
+        Value::Interval(rustmemodb::core::value::parse_interval(
+            "1 day 2 hours 30 minutes"
+        )?)
+        .to_string(),
+        "1 day 02:30:00"
+    );
+    assert_eq!(
+        Value::Interval(rustmemodb::core::value::parse_interval("-90 seconds")?).to_string(),
+        "-00:01:30"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interval_column_stores_and_reads_back() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+    client
+        .execute("CREATE TABLE interval_jobs (id INTEGER, timeout INTERVAL)")
+        .await?;
+    client
+        .execute(
+            "INSERT INTO interval_jobs VALUES (1, '1 day 2 hours'), (2, INTERVAL '90 seconds'), (3, NULL)",
+        )
+        .await?;
+    client
+        .execute("UPDATE interval_jobs SET timeout = '45 minutes' WHERE id = 3")
+        .await?;
+
+    let result = client
+        .query("SELECT timeout FROM interval_jobs ORDER BY id")
+        .await?;
+    let timeouts = result
+        .rows()
+        .iter()
+        .map(|row| row[0].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        timeouts,
+        vec![
+            Value::Interval(rustmemodb::core::value::parse_interval("26 hours")?),
+            Value::Interval(90_000_000),
+            Value::Interval(45 * 60_000_000),
+        ]
+    );
+
+    let err = client
+        .execute("INSERT INTO interval_jobs VALUES (4, '1 month')")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("no fixed length"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_interval_state_json_round_trips() -> Result<()> {
+    for micros in [0, 1_500_000, -93_784_000_001, i64::MAX] {
+        let state = rustmemodb::persist::value_to_state_json(&Value::Interval(micros));
+        let text = state.as_str().expect("interval state is a string");
+        assert_eq!(rustmemodb::core::value::parse_interval(text)?, micros);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_null_boolean_round_trips_as_unknown() -> Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+
+    client
+        .execute("CREATE TABLE flags (id INTEGER, enabled BOOLEAN)")
+        .await?;
+    client
+        .execute("INSERT INTO flags VALUES (1, TRUE), (2, FALSE), (3, NULL)")
+        .await?;
+
+    let result = client
+        .query("SELECT enabled FROM flags ORDER BY id")
+        .await?;
+    let flags: Vec<Option<bool>> = result
+        .rows()
+        .iter()
+        .map(|row| row[0].as_nullable_bool())
+        .collect();
+    assert_eq!(flags, vec![Some(true), Some(false), None]);
+    assert_eq!(result.rows()[2][0], Value::Null);
+
+    // NULL is neither TRUE nor FALSE.
+    let result = client
+        .query("SELECT id FROM flags WHERE enabled = FALSE")
+        .await?;
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows()[0][0], Value::Integer(2));
+    let result = client
+        .query("SELECT id FROM flags WHERE NOT enabled")
+        .await?;
+    assert_eq!(result.row_count(), 1);
+    let result = client
+        .query("SELECT id FROM flags WHERE enabled IS NULL")
+        .await?;
+    assert_eq!(result.rows()[0][0], Value::Integer(3));
+
+    client
+        .execute("UPDATE flags SET enabled = NULL WHERE id = 1")
+        .await?;
+    let result = client
+        .query("SELECT enabled FROM flags WHERE id = 1")
+        .await?;
+    assert_eq!(result.rows()[0][0].as_nullable_bool(), None);
+
+    Ok(())
+}
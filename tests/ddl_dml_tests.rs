@@ -655,3 +655,58 @@ async fn test_update_load_mixed_operations() {
         (iterations * 2) as f64 / duration.as_secs_f64()
     );
 }
+
+#[tokio::test]
+async fn test_affected_rows_per_statement_kind() {
+    let client = Client::connect("admin", "adminpass").await.unwrap();
+
+    let result = client
+        .execute("CREATE TABLE test_affected (id INTEGER, name TEXT)")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), None);
+
+    let result = client
+        .execute("INSERT INTO test_affected VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol')")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(3));
+
+    let result = client
+        .execute("INSERT INTO test_affected SELECT id + 10, name FROM test_affected WHERE id > 1")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(2));
+
+    // UPDATE counts matched rows, including ones whose values do not change.
+    let result = client
+        .execute("UPDATE test_affected SET name = 'Bob' WHERE id = 2 OR id = 3")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(2));
+
+    let result = client
+        .execute("UPDATE test_affected SET name = 'Nobody' WHERE id = 99")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(0));
+
+    let result = client
+        .execute("DELETE FROM test_affected WHERE id > 10")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(2));
+
+    let result = client
+        .execute("DELETE FROM test_affected WHERE id = 99")
+        .await
+        .unwrap();
+    assert_eq!(result.affected_rows(), Some(0));
+
+    let result = client.query("SELECT * FROM test_affected").await.unwrap();
+    assert_eq!(result.row_count(), 3);
+    assert_eq!(result.affected_rows(), None);
+
+    let result = client.execute("DROP TABLE test_affected").await.unwrap();
+    assert_eq!(result.affected_rows(), None);
+}
@@ -59,3 +59,56 @@ async fn test_json() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_json_extract() -> anyhow::Result<()> {
+    let client = Client::connect_local("admin", "adminpass").await?;
+
+    let res = client
+        .query("SELECT JSON_EXTRACT('{\"a\": {\"b\": 2}}', '$.a.b')")
+        .await?;
+    assert_eq!(res.rows()[0][0], Value::Integer(2));
+
+    let res = client
+        .query("SELECT JSON_EXTRACT('{\"items\": [{\"name\": \"x\"}, {\"name\": \"y\"}]}', '$.items[1].name')")
+        .await?;
+    assert_eq!(res.rows()[0][0].as_str().unwrap(), "y");
+
+    let res = client
+        .query("SELECT JSON_EXTRACT('{\"a\": [1, 2]}', '$.a')")
+        .await?;
+    match &res.rows()[0][0] {
+        Value::Json(j) => assert_eq!(j, &serde_json::json!([1, 2])),
+        _ => panic!("Expected JSON"),
+    }
+
+    let res = client
+        .query("SELECT JSON_EXTRACT('{\"a\": 1}', '$.missing')")
+        .await?;
+    assert_eq!(res.rows()[0][0], Value::Null);
+
+    client
+        .execute("CREATE TABLE json_docs (id INTEGER, doc JSON)")
+        .await?;
+    client
+        .execute("INSERT INTO json_docs VALUES (1, '{\"user\": {\"tags\": [\"a\", \"b\"]}}')")
+        .await?;
+    client
+        .execute("INSERT INTO json_docs VALUES (2, '{\"user\": {\"tags\": [\"c\"]}}')")
+        .await?;
+
+    let res = client
+        .query("SELECT id FROM json_docs WHERE JSON_EXTRACT(doc, '$.user.tags[0]') = 'c'")
+        .await?;
+    assert_eq!(res.rows().len(), 1);
+    assert_eq!(res.rows()[0][0], Value::Integer(2));
+
+    assert!(
+        client
+            .query("SELECT JSON_EXTRACT('{}', 'a.b')")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
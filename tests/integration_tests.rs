@@ -2,6 +2,7 @@
 ///
 /// These tests verify that all components work together correctly.
 /// Run with: cargo test --test integration_tests
+use rustmemodb::core::DataType;
 use rustmemodb::{InMemoryDB, Value};
 
 #[tokio::test]
@@ -514,6 +515,66 @@ async fn test_projection_specific_columns() {
     assert_eq!(result.columns()[1].name, "age");
 }
 
+#[tokio::test]
+async fn test_projection_column_metadata_for_expressions_and_aliases() {
+    let mut db = InMemoryDB::new();
+
+    db.execute("CREATE TABLE items (id INTEGER, name TEXT, price FLOAT)")
+        .await
+        .unwrap();
+    db.execute("INSERT INTO items VALUES (1, 'pen', 2.5)")
+        .await
+        .unwrap();
+
+    let result = db
+        .execute("SELECT id + 1 AS next_id, price * 2, id = 1 AS is_first, UPPER(name) FROM items")
+        .await
+        .unwrap();
+    let columns: Vec<(&str, DataType)> = result
+        .columns()
+        .iter()
+        .map(|c| (c.name.as_str(), c.data_type.clone()))
+        .collect();
+    assert_eq!(
+        columns,
+        vec![
+            ("next_id", DataType::Integer),
+            ("col_1", DataType::Float),
+            ("is_first", DataType::Boolean),
+            ("col_3", DataType::Text),
+        ]
+    );
+
+    let result = db
+        .execute("SELECT id, MAX(name), SUM(price), COUNT(*) AS total FROM items GROUP BY id")
+        .await
+        .unwrap();
+    let types: Vec<DataType> = result
+        .columns()
+        .iter()
+        .map(|c| c.data_type.clone())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            DataType::Integer,
+            DataType::Text,
+            DataType::Float,
+            DataType::Integer
+        ]
+    );
+    assert_eq!(result.columns()[3].name, "total");
+
+    let result = db
+        .execute("SELECT a.id, b.name FROM items AS a JOIN items AS b ON a.id = b.id")
+        .await
+        .unwrap();
+    assert_eq!(result.columns()[0].name, "id");
+    assert_eq!(result.columns()[0].data_type, DataType::Integer);
+    assert_eq!(result.columns()[1].name, "name");
+    assert_eq!(result.columns()[1].data_type, DataType::Text);
+}
+
 #[tokio::test]
 async fn test_complex_nested_conditions() {
     let mut db = InMemoryDB::new();
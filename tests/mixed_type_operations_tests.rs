@@ -3,6 +3,7 @@
 /// Tests for mixed Integer/Float comparisons and arithmetic operations
 /// Run with: cargo test --test mixed_type_operations_tests
 use rustmemodb::Client;
+use rustmemodb::core::{Result, Value};
 
 #[tokio::test]
 async fn test_boolean_literal_insert() {
@@ -206,3 +207,105 @@ async fn test_mixed_type_arithmetic() {
     println!("Expected: 1 (20.3 * 2 = 40.6 > 30)");
     assert_eq!(result.row_count(), 1);
 }
+
+#[tokio::test]
+async fn test_integer_float_comparison_is_exact() -> Result<()> {
+    let client = Client::connect("admin", "adminpass").await?;
+
+    // 2^53 + 1 is the first integer that does not round-trip through f64.
+    for table in ["mixed_prices", "mixed_prices_indexed"] {
+        client
+            .execute(&format!("CREATE TABLE {table} (id INTEGER, cents INTEGER)"))
+            .await?;
+        client
+            .execute(&format!(
+                "INSERT INTO {table} VALUES (1, 9), (2, 10), (3, 9007199254740993), (4, {})",
+                i64::MAX
+            ))
+            .await?;
+    }
+    client
+        .execute("CREATE INDEX idx_mixed_prices_cents ON mixed_prices_indexed (cents)")
+        .await?;
+
+    let ids = |sql: String| {
+        let client = &client;
+        async move {
+            let result = client.query(&sql).await?;
+            let mut ids = result
+                .rows()
+                .iter()
+                .map(|row| match row[0] {
+                    Value::Integer(id) => id,
+                    ref other => panic!("unexpected id {other:?}"),
+                })
+                .collect::<Vec<_>>();
+            ids.sort();
+            Ok::<_, rustmemodb::DbError>(ids)
+        }
+    };
+
+    for table in ["mixed_prices", "mixed_prices_indexed"] {
+        let select = |predicate: &str| format!("SELECT id FROM {table} WHERE {predicate}");
+        assert_eq!(ids(select("cents > 9.5")).await?, vec![2, 3, 4], "{table}");
+        assert_eq!(ids(select("cents >= 9.5")).await?, vec![2, 3, 4], "{table}");
+        assert_eq!(ids(select("cents < 9.5")).await?, vec![1], "{table}");
+        assert_eq!(
+            ids(select("cents = 9.5")).await?,
+            Vec::<i64>::new(),
+            "{table}"
+        );
+        assert_eq!(ids(select("cents = 10.0")).await?, vec![2], "{table}");
+        assert_eq!(
+            ids(select("cents BETWEEN 8.5 AND 9.5")).await?,
+            vec![1],
+            "{table}"
+        );
+        // 9007199254740992.0 is 2^53; an f64 round-trip would make id 3 equal to it.
+        assert_eq!(
+            ids(select("cents = 9007199254740992.0")).await?,
+            Vec::<i64>::new(),
+            "{table}"
+        );
+        assert_eq!(
+            ids(select("cents > 9007199254740992.0")).await?,
+            vec![3, 4],
+            "{table}"
+        );
+        // i64::MAX is just below 2^63 = 9223372036854775808.0.
+        assert_eq!(
+            ids(select("cents < 9223372036854775808.0")).await?,
+            vec![1, 2, 3, 4],
+            "{table}"
+        );
+        assert_eq!(
+            ids(select("cents >= 9223372036854775808.0")).await?,
+            Vec::<i64>::new(),
+            "{table}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_integer_float_value_ordering_keeps_precision_and_nan() {
+    let big = Value::Integer((1 << 53) + 1);
+    let rounded = Value::Float(9007199254740992.0);
+    assert_ne!(big, rounded);
+    assert_eq!(big.compare(&rounded).unwrap(), std::cmp::Ordering::Greater);
+    assert_eq!(big.cmp(&rounded), std::cmp::Ordering::Greater);
+    assert_eq!(Value::Integer(10), Value::Float(10.0));
+
+    // NaN sorts after every number but is never equal to one.
+    let nan = Value::Float(f64::NAN);
+    assert_ne!(Value::Integer(0), nan);
+    assert_eq!(
+        Value::Integer(i64::MAX).compare(&nan).unwrap(),
+        std::cmp::Ordering::Less
+    );
+    assert_eq!(
+        nan.cmp(&Value::Integer(i64::MAX)),
+        std::cmp::Ordering::Greater
+    );
+}
@@ -1,7 +1,19 @@
+use rustmemodb::testing::{
+    assert_create_contract, assert_idempotency_replay, assert_optimistic_lock,
+};
 use rustmemodb::{
-    DbError, PersistApp, PersistAppPolicy, PersistEntity, PersistEntityFactory,
-    PersistReplicationMode, PersistReplicationPolicy, Value, persist_struct, persist_vec,
+    DbError, InMemoryDB, ManagedConflictKind, PERSIST_SNAPSHOT_FORMAT_VERSION, PersistApp,
+    PersistAppPolicy, PersistChangeKind, PersistCollectionPolicy, PersistEntity,
+    PersistEntityFactory, PersistEntityRuntime, PersistIdStrategy, PersistImportPolicy,
+    PersistIntegrityIssueKind, PersistMigrationPlan, PersistMigrationStep, PersistPendingMigration,
+    PersistReplicationMode, PersistReplicationOverflow, PersistReplicationPolicy, PersistSession,
+    PersistSnapshotEncryption, PersistTableDefinition, RestoreConflictPolicy,
+    RuntimeCommandEnvelope, RuntimeOperationalPolicy, SnapshotMode, Value,
+    classify_managed_conflict, persist_struct, persist_vec, spawn_managed_snapshot_scheduler,
 };
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 persist_struct! {
     pub struct AppTodo {
@@ -18,6 +30,15 @@ persist_struct! {
 
 persist_vec!(pub DynamicAppTodoVec, DynamicAppTodo);
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, rustmemodb::PersistModel)]
+pub struct AppAccount {
+    #[persist(unique_index)]
+    email: String,
+    name: String,
+}
+
+persist_vec!(pub AppAccountVec, AppAccountPersisted);
+
 #[tokio::test]
 async fn persist_app_open_vec_mutate_and_recover_from_snapshot() {
     let temp = tempfile::tempdir().expect("temp dir");
@@ -35,6 +56,8 @@ async fn persist_app_open_vec_mutate_and_recover_from_snapshot() {
         .open_vec::<AppTodoVec>("todo_app")
         .await
         .expect("open vec #1");
+    assert!(!todos.recovery_report().snapshot_loaded);
+    assert_eq!(todos.recovery_report().ops_replayed, 0);
 
     todos
         .mutate(|vec| {
@@ -55,6 +78,234 @@ async fn persist_app_open_vec_mutate_and_recover_from_snapshot() {
 
     assert_eq!(restored.collection().items().len(), 1);
     assert_eq!(restored.collection().items()[0].title(), "Write tests");
+
+    let reports = app_restarted.recovery_reports();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].collection, "todo_app");
+    assert!(reports[0].snapshot_loaded);
+    assert_eq!(reports[0].ops_replayed, 1);
+    assert_eq!(reports[0].last_version, 1);
+    assert_eq!(restored.recovery_report().ops_replayed, 1);
+
+    drop(restored);
+    app_restarted
+        .open_vec::<AppTodoVec>("todo_app")
+        .await
+        .expect("reopen vec");
+    assert_eq!(app_restarted.recovery_reports().len(), 1);
+}
+
+#[tokio::test]
+async fn persist_app_ephemeral_collection_skips_snapshots() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_ephemeral");
+
+    let policy = PersistAppPolicy {
+        snapshot_every_ops: 1,
+        ..PersistAppPolicy::default()
+    };
+
+    let app = PersistApp::open(root.clone(), policy.clone())
+        .await
+        .expect("open app #1");
+    let mut cache = app
+        .open_vec_with_policy::<AppTodoVec>("todo_cache", PersistCollectionPolicy::ephemeral())
+        .await
+        .expect("open ephemeral vec");
+    let mut durable = app
+        .open_vec::<AppTodoVec>("todo_durable")
+        .await
+        .expect("open durable vec");
+
+    for vec in [&mut cache, &mut durable] {
+        vec.mutate(|vec| {
+            vec.add_one(AppTodo::new("Cached".to_string(), false));
+            Ok(())
+        })
+        .await
+        .expect("mutate");
+    }
+
+    assert_eq!(cache.stats().snapshots_written, 0);
+    assert_eq!(cache.stats().last_snapshot_duration_ms, None);
+    assert_eq!(durable.stats().snapshots_written, 1);
+    let durable_stats = durable.stats();
+    let last_ms = durable_stats
+        .last_snapshot_duration_ms
+        .expect("snapshot duration recorded");
+    assert_eq!(durable_stats.avg_snapshot_duration_ms, Some(last_ms as f64));
+    assert!(!root.join("todo_cache.snapshot.json").exists());
+
+    let app_restarted = PersistApp::open(root, policy).await.expect("open app #2");
+    let cache = app_restarted
+        .open_vec_with_policy::<AppTodoVec>("todo_cache", PersistCollectionPolicy::ephemeral())
+        .await
+        .expect("reopen ephemeral vec");
+    let durable = app_restarted
+        .open_vec::<AppTodoVec>("todo_durable")
+        .await
+        .expect("reopen durable vec");
+    assert!(cache.collection().items().is_empty());
+    assert_eq!(durable.collection().items().len(), 1);
+}
+
+#[tokio::test]
+async fn persist_app_encrypts_snapshots_and_reads_plaintext_and_retired_keys() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_encrypted");
+    let snapshot_path = root.join("todos.snapshot.json");
+    let plain_policy = PersistAppPolicy {
+        snapshot_every_ops: 1,
+        ..PersistAppPolicy::default()
+    };
+
+    let app = PersistApp::open(root.clone(), plain_policy.clone())
+        .await
+        .expect("open plaintext app");
+    let mut todos = app.open_vec::<AppTodoVec>("todos").await.expect("open vec");
+    todos
+        .mutate(|vec| {
+            vec.add_one(AppTodo::new("Secret plan".to_string(), false));
+            Ok(())
+        })
+        .await
+        .expect("mutate plaintext");
+    drop(todos);
+
+    // A plaintext snapshot loads under encryption and is re-written encrypted.
+    let app = PersistApp::open(root.clone(), plain_policy.clone())
+        .await
+        .expect("open encrypted app")
+        .with_encryption(PersistSnapshotEncryption::new("k1", [1; 32]));
+    let mut todos = app.open_vec::<AppTodoVec>("todos").await.expect("open vec");
+    assert_eq!(todos.collection().items().len(), 1);
+    todos.force_snapshot().await.expect("encrypted snapshot");
+    drop(todos);
+
+    let bytes = std::fs::read(&snapshot_path).expect("read snapshot");
+    assert_eq!(
+        PersistSnapshotEncryption::key_id_of(&bytes).as_deref(),
+        Some("k1")
+    );
+    assert!(!String::from_utf8_lossy(&bytes).contains("Secret plan"));
+
+    let Err(err) = PersistApp::open(root.clone(), plain_policy.clone())
+        .await
+        .expect("open plaintext app")
+        .open_vec::<AppTodoVec>("todos")
+        .await
+    else {
+        panic!("encrypted snapshot needs a key");
+    };
+    assert!(err.to_string().contains("'k1'"), "{err}");
+
+    let Err(err) = PersistApp::open(root.clone(), plain_policy.clone())
+        .await
+        .expect("open app with wrong key")
+        .with_encryption(PersistSnapshotEncryption::new("k1", [2; 32]))
+        .open_vec::<AppTodoVec>("todos")
+        .await
+    else {
+        panic!("wrong key must not decrypt");
+    };
+    assert!(err.to_string().contains("Failed to decrypt"), "{err}");
+
+    let rotated = PersistSnapshotEncryption::new("k2", [2; 32]).with_retired_key("k1", [1; 32]);
+    let app = PersistApp::open(root.clone(), plain_policy.clone())
+        .await
+        .expect("open rotated app")
+        .with_encryption(rotated);
+    let mut todos = app.open_vec::<AppTodoVec>("todos").await.expect("open vec");
+    assert_eq!(todos.collection().items()[0].title(), "Secret plan");
+    todos.force_snapshot().await.expect("re-encrypt snapshot");
+
+    let bytes = std::fs::read(&snapshot_path).expect("read snapshot");
+    assert_eq!(
+        PersistSnapshotEncryption::key_id_of(&bytes).as_deref(),
+        Some("k2")
+    );
+}
+
+#[tokio::test]
+async fn managed_reload_from_storage_resyncs_with_external_snapshot() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_reload");
+    let policy = PersistAppPolicy {
+        snapshot_every_ops: 1,
+        ..PersistAppPolicy::default()
+    };
+
+    let app = PersistApp::open(root.clone(), policy.clone())
+        .await
+        .expect("open app");
+    let mut todos = app.open_vec::<AppTodoVec>("todos").await.expect("open vec");
+    let kept = AppTodo::new("Kept".to_string(), false);
+    let kept_id = kept.persist_id().to_string();
+    let removed = AppTodo::new("Removed".to_string(), false);
+    let removed_id = removed.persist_id().to_string();
+    todos.create(kept).await.expect("create kept");
+    todos.create(removed).await.expect("create removed");
+    let mut changes = todos.subscribe_changes();
+
+    // Another process owning the same root rewrites the snapshot.
+    let external = PersistApp::open(root.clone(), policy.clone())
+        .await
+        .expect("open external app");
+    let mut external_todos = external
+        .open_vec::<AppTodoVec>("todos")
+        .await
+        .expect("open external vec");
+    external_todos
+        .update(&kept_id, |todo| {
+            todo.set_done(true);
+            Ok(())
+        })
+        .await
+        .expect("external update");
+    external_todos
+        .delete(&removed_id)
+        .await
+        .expect("external delete");
+    let added = AppTodo::new("Added".to_string(), false);
+    let added_id = added.persist_id().to_string();
+    external_todos.create(added).await.expect("external create");
+
+    todos.reload_from_storage().await.expect("reload");
+    assert_eq!(todos.list().len(), 2);
+    assert!(*todos.get(&kept_id).expect("kept item").done());
+    assert!(todos.get(&removed_id).is_none());
+    assert!(todos.get(&added_id).is_some());
+
+    let mut kinds = Vec::new();
+    while let Ok(event) = changes.try_recv() {
+        kinds.push((event.persist_id, event.kind));
+    }
+    assert!(kinds.contains(&(removed_id.clone(), PersistChangeKind::Deleted)));
+    assert!(kinds.contains(&(added_id, PersistChangeKind::Created)));
+
+    // Reloading would discard mutations that are not snapshotted yet.
+    let lazy_app = PersistApp::open(
+        root,
+        PersistAppPolicy {
+            snapshot_every_ops: 10,
+            ..policy
+        },
+    )
+    .await
+    .expect("open lazy app");
+    let mut lazy = lazy_app
+        .open_vec::<AppTodoVec>("todos")
+        .await
+        .expect("open lazy vec");
+    lazy.delete(&kept_id).await.expect("pending delete");
+    let err = lazy
+        .reload_from_storage()
+        .await
+        .expect_err("dirty reload must fail");
+    assert!(err.to_string().contains("not snapshotted"), "{err}");
+    lazy.force_snapshot().await.expect("snapshot pending ops");
+    lazy.reload_from_storage().await.expect("clean reload");
+    assert_eq!(lazy.list().len(), 1);
 }
 
 #[tokio::test]
@@ -218,6 +469,21 @@ async fn managed_command_first_api_works_with_draft_patch_and_command() {
         .expect("patch");
     assert!(found);
 
+    let stats_before = todos.stats();
+    let preview = todos
+        .preview_command(&draft_id, AppTodoCommand::SetTitle("Preview".to_string()))
+        .expect("preview command")
+        .expect("previewed todo");
+    assert_eq!(preview.title(), "Preview");
+    assert_eq!(todos.get(&draft_id).expect("todo").title(), "From draft");
+    assert_eq!(todos.stats().total_ops, stats_before.total_ops);
+    assert!(
+        todos
+            .preview_command("missing", AppTodoCommand::SetDone(true))
+            .expect("preview missing")
+            .is_none()
+    );
+
     let found = todos
         .apply_command(
             &draft_id,
@@ -435,3 +701,1088 @@ async fn managed_update_exposes_explicit_optimistic_conflict_for_stale_collectio
         "failed stale update must not leave partial in-memory mutations"
     );
 }
+
+#[tokio::test]
+async fn managed_inserts_reject_duplicate_unique_index_value() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_unique_index");
+
+    let app = PersistApp::open_auto(root).await.expect("open app");
+    let mut accounts = app
+        .open_vec::<AppAccountVec>("accounts_unique")
+        .await
+        .expect("open vec");
+
+    accounts
+        .create_from_draft(AppAccountPersistedDraft::new(
+            "alice@example.com".to_string(),
+            "Alice".to_string(),
+        ))
+        .await
+        .expect("first create");
+
+    let err = accounts
+        .create_from_draft(AppAccountPersistedDraft::new(
+            "alice@example.com".to_string(),
+            "Alice Again".to_string(),
+        ))
+        .await
+        .expect_err("duplicate email must fail");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::UniqueConstraint)
+    );
+    assert!(err.to_string().contains("email"), "unexpected error: {err}");
+    assert_eq!(accounts.list().len(), 1);
+
+    accounts
+        .create_from_draft(AppAccountPersistedDraft::new(
+            "bob@example.com".to_string(),
+            "Bob".to_string(),
+        ))
+        .await
+        .expect("distinct email create");
+    assert_eq!(accounts.list().len(), 2);
+
+    let account = |email: &str| {
+        AppAccount {
+            email: email.to_string(),
+            name: "Other".to_string(),
+        }
+        .into_persisted()
+    };
+    let err = accounts
+        .put(account("alice@example.com"), None)
+        .await
+        .expect_err("put must enforce the unique index");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::UniqueConstraint)
+    );
+    let err = accounts
+        .create_many(vec![
+            account("carol@example.com"),
+            account("carol@example.com"),
+        ])
+        .await
+        .expect_err("duplicates within one batch must fail");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::UniqueConstraint)
+    );
+    let err = accounts
+        .transaction(|tx| {
+            Box::pin(async move {
+                tx.create(account("bob@example.com")).await?;
+                Ok(())
+            })
+        })
+        .await
+        .expect_err("transaction create must enforce the unique index");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::UniqueConstraint)
+    );
+    assert_eq!(accounts.list().len(), 2);
+}
+
+#[tokio::test]
+async fn persist_app_open_in_memory_works_without_filesystem() {
+    let app = PersistApp::open_in_memory();
+    assert!(app.is_in_memory());
+
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_in_memory")
+        .await
+        .expect("open vec");
+
+    let id = todos
+        .create_from_draft(AppTodoDraft::new("In memory".to_string(), false))
+        .await
+        .expect("create from draft");
+    todos.force_snapshot().await.expect("snapshot is a no-op");
+
+    assert_eq!(
+        todos.get(&id).map(|todo| todo.title().as_str()),
+        Some("In memory")
+    );
+    let stats = todos.stats();
+    assert!(stats.snapshot_path.is_empty());
+    assert_eq!(stats.ops_since_snapshot, 0);
+    assert!(stats.last_snapshot_at.is_none());
+}
+
+#[tokio::test]
+async fn persist_app_and_runtime_assign_ids_from_their_id_strategy() {
+    let counter = Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let next = counter.clone();
+    let strategy = PersistIdStrategy::custom(move || {
+        format!(
+            "todo-{}",
+            next.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        )
+    });
+
+    let app = PersistApp::open_in_memory().with_id_strategy(strategy.clone());
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_custom_ids")
+        .await
+        .expect("open vec");
+    let id = todos
+        .create_from_draft(AppTodoDraft::new("Custom id".to_string(), false))
+        .await
+        .expect("create from draft");
+    assert_eq!(id, "todo-0");
+    assert_eq!(
+        todos.get(&id).map(|todo| todo.title().as_str()),
+        Some("Custom id")
+    );
+
+    let mut defaults = PersistApp::open_in_memory()
+        .open_vec::<AppTodoVec>("todo_default_ids")
+        .await
+        .expect("open vec");
+    let id = defaults
+        .create_from_draft(AppTodoDraft::new("Default id".to_string(), false))
+        .await
+        .expect("create from draft");
+    assert!(uuid::Uuid::parse_str(&id).is_ok());
+
+    let temp = tempfile::tempdir().expect("temp dir");
+    let mut runtime = PersistEntityRuntime::open(temp.path(), RuntimeOperationalPolicy::default())
+        .await
+        .expect("open runtime");
+    runtime.set_id_strategy(strategy);
+    let id = runtime
+        .create_entity("Counter", "custom_id_counter", serde_json::json!({}), 1)
+        .await
+        .expect("create entity");
+    assert_eq!(id, "todo-1");
+}
+
+#[tokio::test]
+async fn managed_import_models_and_json_file_create_entities_via_drafts() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let app = PersistApp::open_in_memory();
+    let mut accounts = app
+        .open_vec::<AppAccountVec>("accounts_import")
+        .await
+        .expect("open vec");
+
+    let ids = accounts
+        .import_models(
+            vec![AppAccount {
+                email: "ann@example.com".to_string(),
+                name: "Ann".to_string(),
+            }],
+            PersistImportPolicy::FailFast,
+        )
+        .await
+        .expect("import models");
+    assert_eq!(ids.len(), 1);
+    assert_eq!(
+        accounts.get(&ids[0]).map(|a| a.name().as_str()),
+        Some("Ann")
+    );
+
+    let fixtures = temp.path().join("fixtures.json");
+    tokio::fs::write(
+        &fixtures,
+        serde_json::json!([
+            {"email": "bob@example.com", "name": "Bob"},
+            {"email": "broken"},
+            {"email": "ann@example.com", "name": "Duplicate Ann"},
+            {"email": "cid@example.com", "name": "Cid"}
+        ])
+        .to_string(),
+    )
+    .await
+    .expect("write fixtures");
+
+    let err = accounts
+        .import_json_file::<AppAccount>(&fixtures, PersistImportPolicy::FailFast)
+        .await
+        .expect_err("malformed record must fail");
+    assert!(
+        err.to_string().contains("record #1"),
+        "unexpected error: {err}"
+    );
+    assert_eq!(accounts.list().len(), 1);
+
+    let ids = accounts
+        .import_json_file::<AppAccount>(&fixtures, PersistImportPolicy::SkipInvalid)
+        .await
+        .expect("import skipping invalid records");
+    assert_eq!(ids.len(), 2);
+    assert_eq!(accounts.list().len(), 3);
+}
+
+#[tokio::test]
+async fn managed_subscribe_changes_emits_events_after_commit() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_changes")
+        .await
+        .expect("open vec");
+    let mut changes = todos.subscribe_changes();
+
+    let todo = AppTodo::new("Watch me".to_string(), false);
+    let todo_id = todo.persist_id().to_string();
+    todos.create(todo).await.expect("create");
+    todos
+        .update(&todo_id, |todo| {
+            todo.set_done(true);
+            Ok(())
+        })
+        .await
+        .expect("update");
+    assert!(!todos.update("missing", |_| Ok(())).await.expect("noop"));
+    todos.delete(&todo_id).await.expect("delete");
+
+    let created = changes.try_recv().expect("created event");
+    assert_eq!(created.kind, PersistChangeKind::Created);
+    assert_eq!(created.persist_id, todo_id);
+    assert_eq!(created.vec_name, "todo_changes");
+    assert_eq!(created.old_version, None);
+    assert_eq!(created.new_version, Some(1));
+
+    let updated = changes.try_recv().expect("updated event");
+    assert_eq!(updated.kind, PersistChangeKind::Updated);
+    assert_eq!(updated.old_version, Some(1));
+    assert_eq!(updated.new_version, Some(2));
+    let state = updated.state.expect("updated state");
+    assert_eq!(state.fields["done"], serde_json::Value::Bool(true));
+
+    let deleted = changes.try_recv().expect("deleted event");
+    assert_eq!(deleted.kind, PersistChangeKind::Deleted);
+    assert_eq!(deleted.old_version, Some(2));
+    assert_eq!(deleted.new_version, None);
+    assert!(deleted.state.is_none());
+    assert!(changes.try_recv().is_err(), "no further events expected");
+}
+
+#[tokio::test]
+async fn managed_update_if_version_rejects_stale_expected_version() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_if_version")
+        .await
+        .expect("open vec");
+
+    let todo = AppTodo::new("Versioned".to_string(), false);
+    let todo_id = todo.persist_id().to_string();
+    todos.create(todo).await.expect("create");
+    let version = todos.get(&todo_id).expect("todo").metadata().version;
+
+    let updated = todos
+        .update_if_version(&todo_id, version, |todo| {
+            todo.set_done(true);
+            Ok(())
+        })
+        .await
+        .expect("matching version must update");
+    assert!(updated);
+
+    let err = todos
+        .update_if_version(&todo_id, version, |todo| {
+            todo.set_title("Stale write".to_string());
+            Ok(())
+        })
+        .await
+        .expect_err("stale version must conflict");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::VersionConflict)
+    );
+    assert_eq!(todos.get(&todo_id).expect("todo").title(), "Versioned");
+
+    let missing = todos
+        .update_if_version("missing", version, |_| Ok(()))
+        .await
+        .expect("missing id is not a conflict");
+    assert!(!missing);
+}
+
+#[tokio::test]
+async fn managed_snapshot_to_file_and_restore_from_file_round_trip() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let backup_path = temp.path().join("backups").join("todos.json");
+
+    let source_app = PersistApp::open_in_memory();
+    let mut source = source_app
+        .open_vec::<AppTodoVec>("todo_backup")
+        .await
+        .expect("open source vec");
+    source
+        .create(AppTodo::new("Back me up".to_string(), true))
+        .await
+        .expect("create");
+    source
+        .snapshot_to_file(&backup_path)
+        .await
+        .expect("snapshot to file");
+
+    let target_app = PersistApp::open_in_memory();
+    let mut target = target_app
+        .open_vec::<AppTodoVec>("todo_backup")
+        .await
+        .expect("open target vec");
+    target
+        .restore_from_file(&backup_path, RestoreConflictPolicy::FailFast)
+        .await
+        .expect("restore from file");
+    assert_eq!(target.list().len(), 1);
+    assert_eq!(target.list()[0].title(), "Back me up");
+
+    let mut snapshot: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&backup_path).expect("read backup"))
+            .expect("decode backup");
+    snapshot["schema_version"] = serde_json::json!(99);
+    let future_path = temp.path().join("future.json");
+    std::fs::write(&future_path, serde_json::to_vec(&snapshot).unwrap()).expect("write");
+
+    let err = target
+        .restore_from_file(&future_path, RestoreConflictPolicy::OverwriteExisting)
+        .await
+        .expect_err("newer schema version must be refused");
+    assert!(err.to_string().contains("incompatible"), "{}", err);
+    assert_eq!(target.list().len(), 1);
+}
+
+#[tokio::test]
+async fn persist_app_open_upgrades_legacy_snapshot_format_and_rejects_newer() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("legacy_root");
+    std::fs::create_dir_all(&root).expect("create root");
+    let snapshot_path = root.join("todo_legacy.snapshot.json");
+    std::fs::write(
+        &snapshot_path,
+        include_str!("fixtures/persist_snapshot_v0.json"),
+    )
+    .expect("write fixture");
+
+    let app = PersistApp::open_auto(root.clone()).await.expect("open app");
+    let todos = app
+        .open_vec::<AppTodoVec>("todo_legacy")
+        .await
+        .expect("open legacy vec");
+    assert_eq!(todos.list().len(), 1);
+    assert_eq!(todos.list()[0].title(), "Migrated from v0");
+
+    let rewritten: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&snapshot_path).expect("read snapshot"))
+            .expect("decode snapshot");
+    assert_eq!(
+        rewritten["format_version"],
+        serde_json::json!(PERSIST_SNAPSHOT_FORMAT_VERSION)
+    );
+
+    let mut future = rewritten;
+    future["format_version"] = serde_json::json!(PERSIST_SNAPSHOT_FORMAT_VERSION + 1);
+    std::fs::write(&snapshot_path, serde_json::to_vec(&future).unwrap()).expect("write");
+
+    let err = match PersistApp::open_auto(root)
+        .await
+        .expect("reopen app")
+        .open_vec::<AppTodoVec>("todo_legacy")
+        .await
+    {
+        Ok(_) => panic!("newer snapshot format must be rejected"),
+        Err(err) => err,
+    };
+    assert!(err.to_string().contains("only understands"), "{}", err);
+}
+
+#[tokio::test]
+async fn managed_get_many_returns_found_items_in_request_order() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_get_many")
+        .await
+        .expect("open vec");
+
+    let first = todos
+        .create_from_draft(AppTodoDraft::new("First".to_string(), false))
+        .await
+        .expect("create first");
+    let second = todos
+        .create_from_draft(AppTodoDraft::new("Second".to_string(), true))
+        .await
+        .expect("create second");
+
+    let found = todos.get_many(&[
+        second.clone(),
+        "missing".to_string(),
+        first.clone(),
+        second.clone(),
+    ]);
+    let titles = found
+        .iter()
+        .map(|todo| todo.title().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["Second", "First"]);
+
+    assert!(todos.get_many(&["missing".to_string()]).is_empty());
+}
+
+#[tokio::test]
+async fn managed_patch_if_version_rejects_stale_expected_version() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_patch_if_version")
+        .await
+        .expect("open vec");
+
+    let todo_id = todos
+        .create_from_draft(AppTodoDraft::new("Versioned".to_string(), false))
+        .await
+        .expect("create from draft");
+    let version = todos.get(&todo_id).expect("todo").metadata().version;
+
+    let found = todos
+        .patch_if_version(
+            &todo_id,
+            version,
+            AppTodoPatch {
+                done: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("matching version must patch");
+    assert!(found);
+
+    let err = todos
+        .patch_if_version(
+            &todo_id,
+            version,
+            AppTodoPatch {
+                title: Some("Stale write".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("stale version must conflict");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::VersionConflict)
+    );
+    assert_eq!(todos.get(&todo_id).expect("todo").title(), "Versioned");
+
+    let missing = todos
+        .patch_if_version(
+            "missing",
+            version,
+            AppTodoPatch {
+                done: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("missing id is not a conflict");
+    assert!(!missing);
+}
+
+#[tokio::test]
+async fn persist_app_tenants_isolate_data_and_snapshots() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_tenants");
+    let policy = PersistAppPolicy {
+        snapshot_every_ops: 1,
+        replication: PersistReplicationPolicy::default(),
+    };
+
+    let app = PersistApp::open(root.clone(), policy.clone())
+        .await
+        .expect("open app");
+    let acme = app.tenant("acme").expect("tenant acme");
+    let globex = app.tenant("globex").expect("tenant globex");
+    assert_eq!(acme.tenant_id(), Some("acme"));
+
+    let mut acme_todos = acme.open_vec::<AppTodoVec>("todos").await.expect("acme");
+    let mut globex_todos = globex
+        .open_vec::<AppTodoVec>("todos")
+        .await
+        .expect("globex");
+
+    let acme_id = acme_todos
+        .create_from_draft(AppTodoDraft::new("Acme task".to_string(), false))
+        .await
+        .expect("acme create");
+    globex_todos
+        .create_from_draft(AppTodoDraft::new("Globex task".to_string(), true))
+        .await
+        .expect("globex create");
+
+    assert_eq!(acme_todos.list().len(), 1);
+    assert_eq!(globex_todos.list().len(), 1);
+    assert!(globex_todos.get(&acme_id).is_none());
+    assert!(root.join("tenants/acme/todos.snapshot.json").exists());
+    assert!(root.join("tenants/globex/todos.snapshot.json").exists());
+    assert!(!root.join("todos.snapshot.json").exists());
+
+    let restarted = PersistApp::open(root, policy).await.expect("reopen app");
+    let acme_restored = restarted
+        .tenant("acme")
+        .expect("tenant acme")
+        .open_vec::<AppTodoVec>("todos")
+        .await
+        .expect("acme reopen");
+    let titles = acme_restored
+        .list()
+        .iter()
+        .map(|todo| todo.title().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["Acme task"]);
+
+    assert!(app.tenant("../escape").is_err());
+    assert!(app.tenant("").is_err());
+    assert!(acme.tenant("nested").is_err());
+}
+
+#[tokio::test]
+async fn persist_app_interval_scheduler_snapshots_idle_vec() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let root = temp.path().join("persist_app_interval");
+    let policy = PersistAppPolicy {
+        snapshot_every_ops: 1_000,
+        ..PersistAppPolicy::default()
+    };
+
+    let app = PersistApp::open(root.clone(), policy.clone())
+        .await
+        .expect("open app");
+    assert!(app.clone().with_snapshot_interval(0, 10).is_err());
+    let app = app.with_snapshot_interval(20, 10).expect("interval");
+    let mut todos = app
+        .open_vec::<AppTodoVec>("interval_todos")
+        .await
+        .expect("open vec");
+    todos
+        .mutate(|vec| {
+            vec.add_one(AppTodo::new("Checkpoint me".to_string(), false));
+            Ok(())
+        })
+        .await
+        .expect("mutate");
+    assert_eq!(todos.stats().ops_since_snapshot, 1);
+    assert!(todos.stats().last_interval_snapshot_at.is_none());
+
+    let todos = Arc::new(Mutex::new(todos));
+    let scheduler = spawn_managed_snapshot_scheduler(todos.clone())
+        .await
+        .expect("spawn scheduler");
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    scheduler.stop().await.expect("stop scheduler");
+
+    let stats = todos.lock().await.stats();
+    assert_eq!(stats.ops_since_snapshot, 0);
+    assert_eq!(stats.snapshot_interval_ms, Some(20));
+    assert!(stats.last_interval_snapshot_at.is_some());
+
+    let restarted = PersistApp::open(root, policy).await.expect("reopen app");
+    let restored = restarted
+        .open_vec::<AppTodoVec>("interval_todos")
+        .await
+        .expect("reopen vec");
+    assert_eq!(restored.collection().items().len(), 1);
+
+    let unscheduled = Arc::new(Mutex::new(
+        restarted
+            .open_vec::<AppTodoVec>("no_interval")
+            .await
+            .expect("open vec without interval"),
+    ));
+    assert!(spawn_managed_snapshot_scheduler(unscheduled).await.is_err());
+
+    // An interval cannot be honoured without a snapshot file.
+    let in_memory = Arc::new(Mutex::new(
+        PersistApp::open_in_memory()
+            .with_snapshot_interval(20, 0)
+            .expect("interval")
+            .open_vec::<AppTodoVec>("in_memory")
+            .await
+            .expect("open in-memory vec"),
+    ));
+    assert!(spawn_managed_snapshot_scheduler(in_memory).await.is_err());
+}
+
+#[tokio::test]
+async fn managed_transaction_commits_or_rolls_back_all_operations() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_transaction")
+        .await
+        .expect("open vec");
+    let first = AppTodo::new("Order".to_string(), false);
+    let first_id = first.persist_id().to_string();
+    todos.create(first).await.expect("create first");
+    let ops_before = todos.stats().ops_since_snapshot;
+
+    let created_id = todos
+        .transaction(|tx| {
+            Box::pin(async move {
+                let item = AppTodo::new("Reserve stock".to_string(), false);
+                let id = item.persist_id().to_string();
+                tx.create(item).await?;
+                tx.update(&first_id, |todo| {
+                    todo.set_done(true);
+                    Ok(())
+                })
+                .await?;
+                Ok(id)
+            })
+        })
+        .await
+        .expect("commit transaction");
+    assert_eq!(todos.list().len(), 2);
+    assert!(todos.get(&created_id).is_some());
+    assert_eq!(todos.stats().ops_since_snapshot, ops_before + 1);
+
+    let first_id = todos.list()[0].persist_id().to_string();
+    let result: rustmemodb::Result<()> = todos
+        .transaction(|tx| {
+            Box::pin(async move {
+                tx.create(AppTodo::new("Never stored".to_string(), false))
+                    .await?;
+                assert!(tx.delete(&first_id).await?);
+                Err(DbError::ExecutionError("out of stock".to_string()))
+            })
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(todos.list().len(), 2);
+    assert!(
+        todos
+            .list()
+            .iter()
+            .all(|todo| todo.title() != "Never stored")
+    );
+    assert!(
+        todos
+            .list()
+            .iter()
+            .any(|todo| todo.title() == "Order" && *todo.done())
+    );
+    assert_eq!(todos.stats().ops_since_snapshot, ops_before + 1);
+}
+
+#[tokio::test]
+async fn testing_contract_helpers_pass_for_managed_vec_and_runtime() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_contracts")
+        .await
+        .expect("open vec");
+
+    let todo_id = assert_create_contract(&mut todos, AppTodo::new("Contract".to_string(), false))
+        .await
+        .expect("create contract");
+    assert_optimistic_lock(&mut todos, &todo_id, |todo| {
+        todo.set_done(!*todo.done());
+        Ok(())
+    })
+    .await
+    .expect("optimistic lock contract");
+    assert_eq!(todos.get(&todo_id).expect("todo").metadata().version, 2);
+
+    let temp = tempfile::tempdir().expect("temp dir");
+    let mut runtime = PersistEntityRuntime::open(temp.path(), RuntimeOperationalPolicy::default())
+        .await
+        .expect("open runtime");
+    runtime.register_deterministic_command(
+        "Counter",
+        "increment",
+        Arc::new(|state, payload| {
+            let delta = payload.get("delta").and_then(|v| v.as_i64()).unwrap_or(1);
+            let fields = state.fields_object_mut()?;
+            let current = fields
+                .get("count")
+                .and_then(|v| v.as_i64())
+                .unwrap_or_default();
+            fields.insert("count".to_string(), serde_json::json!(current + delta));
+            Ok(())
+        }),
+    );
+    let counter_id = runtime
+        .create_entity(
+            "Counter",
+            "contract_counter",
+            serde_json::json!({"count": 0}),
+            1,
+        )
+        .await
+        .expect("create counter");
+
+    let envelope = RuntimeCommandEnvelope::new(
+        "Counter",
+        &counter_id,
+        "increment",
+        serde_json::json!({"delta": 5}),
+    )
+    .with_idempotency_key("contract-op-1");
+    let applied = assert_idempotency_replay(&mut runtime, envelope)
+        .await
+        .expect("idempotency contract");
+    assert_eq!(applied.state.fields["count"], serde_json::json!(5));
+}
+
+#[tokio::test]
+async fn managed_integrity_check_reports_and_repairs_row_drift() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let app = PersistApp::open_auto(temp.path().join("persist_app_integrity"))
+        .await
+        .expect("open app");
+    let mut primary = app
+        .open_vec::<AppTodoVec>("todo_integrity")
+        .await
+        .expect("open primary");
+
+    let updated = AppTodo::new("Updated elsewhere".to_string(), false);
+    let updated_id = updated.persist_id().to_string();
+    let deleted = AppTodo::new("Deleted elsewhere".to_string(), false);
+    let deleted_id = deleted.persist_id().to_string();
+    let ahead = AppTodo::new("Ahead in replica".to_string(), false);
+    let ahead_id = ahead.persist_id().to_string();
+    primary.create(updated).await.expect("create updated");
+    primary.create(deleted).await.expect("create deleted");
+    primary.create(ahead).await.expect("create ahead");
+
+    let mut replica = app
+        .open_vec::<AppTodoVec>("todo_integrity")
+        .await
+        .expect("open replica");
+    assert!(
+        app.integrity_check(&mut replica, false)
+            .await
+            .expect("clean check")
+            .is_clean()
+    );
+
+    primary
+        .update(&updated_id, |todo| {
+            todo.set_done(true);
+            Ok(())
+        })
+        .await
+        .expect("update");
+    primary.delete(&deleted_id).await.expect("delete");
+    let orphan = AppTodo::new("Only in primary".to_string(), false);
+    let orphan_id = orphan.persist_id().to_string();
+    primary.create(orphan).await.expect("create orphan");
+    let ahead_item = replica
+        .collection_mut()
+        .items_mut()
+        .iter_mut()
+        .find(|todo| todo.persist_id() == ahead_id)
+        .expect("ahead item");
+    ahead_item.set_done(true);
+    ahead_item.metadata_mut().version += 1;
+
+    let report = app
+        .integrity_check(&mut replica, false)
+        .await
+        .expect("check");
+    assert_eq!(report.checked_items, 3);
+    assert_eq!(report.issues.len(), 4);
+    let mut stale = report
+        .issues_of_kind(PersistIntegrityIssueKind::StaleRow)
+        .into_iter()
+        .map(|issue| issue.persist_id.clone())
+        .collect::<Vec<_>>();
+    stale.sort();
+    let mut expected_stale = vec![updated_id.clone(), ahead_id.clone()];
+    expected_stale.sort();
+    assert_eq!(stale, expected_stale);
+    let missing = report.issues_of_kind(PersistIntegrityIssueKind::MissingRow);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].persist_id, deleted_id);
+    let orphans = report.issues_of_kind(PersistIntegrityIssueKind::OrphanRow);
+    assert_eq!(orphans.len(), 1);
+    assert_eq!(orphans[0].persist_id, orphan_id);
+    assert_eq!(report.unrepaired_count(), 4);
+
+    // Only the row the replica is ahead of is rewritten; the newer row and the
+    // row deleted by the primary are left alone.
+    let repaired = replica.integrity_check(true).await.expect("repair");
+    assert_eq!(repaired.unrepaired_count(), 3);
+    let repaired_ids = repaired
+        .issues
+        .iter()
+        .filter(|issue| issue.repaired)
+        .map(|issue| issue.persist_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(repaired_ids, vec![ahead_id.as_str()]);
+
+    let after = replica.integrity_check(false).await.expect("recheck");
+    assert_eq!(after.issues.len(), 3);
+    assert!(
+        after
+            .issues
+            .iter()
+            .all(|issue| issue.persist_id != ahead_id)
+    );
+
+    let primary_view = primary.integrity_check(false).await.expect("primary check");
+    assert!(
+        primary_view
+            .issues
+            .iter()
+            .all(|issue| issue.persist_id != updated_id && issue.persist_id != deleted_id),
+        "rows written by the primary must survive the replica repair: {:?}",
+        primary_view.issues
+    );
+}
+
+#[tokio::test]
+async fn persist_app_async_replication_queue_is_bounded() {
+    for overflow in [
+        PersistReplicationOverflow::Block,
+        PersistReplicationOverflow::DropOldest,
+    ] {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let replica_roots = (0..8)
+            .map(|index| temp.path().join(format!("replica_{index}")))
+            .collect::<Vec<_>>();
+        let policy = PersistAppPolicy {
+            snapshot_every_ops: 1,
+            replication: PersistReplicationPolicy {
+                mode: PersistReplicationMode::AsyncBestEffort,
+                replica_roots: replica_roots.clone(),
+            },
+        };
+        let app = PersistApp::open(temp.path().join("primary"), policy)
+            .await
+            .expect("open app")
+            .with_replication_queue(1, overflow)
+            .expect("queue limits");
+        assert!(app.clone().with_replication_queue(0, overflow).is_err());
+        let mut todos = app
+            .open_vec::<AppTodoVec>("todo_async_replica")
+            .await
+            .expect("open vec");
+
+        for index in 0..20 {
+            todos
+                .create(AppTodo::new(format!("Todo {index}"), false))
+                .await
+                .expect("create");
+            assert!(todos.stats().replication_queue_depth <= 1);
+        }
+        todos.flush_replication().await;
+
+        let stats = todos.stats();
+        assert_eq!(stats.replication_queue_depth, 0);
+        assert_eq!(stats.replication_failures, 0);
+        if overflow == PersistReplicationOverflow::Block {
+            assert_eq!(stats.replication_dropped, 0);
+        }
+
+        for replica_root in replica_roots {
+            let replica = PersistApp::open_auto(replica_root)
+                .await
+                .expect("open replica");
+            let restored = replica
+                .open_vec::<AppTodoVec>("todo_async_replica")
+                .await
+                .expect("open replica vec");
+            assert_eq!(restored.collection().items().len(), 20);
+        }
+    }
+}
+
+#[tokio::test]
+async fn persist_app_metrics_snapshot_rolls_up_open_collections() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let policy = PersistAppPolicy {
+        snapshot_every_ops: 2,
+        ..PersistAppPolicy::default()
+    };
+    let app = PersistApp::open(temp.path().join("metrics"), policy)
+        .await
+        .expect("open app");
+    let mut todos = app
+        .open_vec::<AppTodoVec>("metrics_todos")
+        .await
+        .expect("open todos");
+    let mut archive = app
+        .open_vec::<AppTodoVec>("metrics_archive")
+        .await
+        .expect("open archive");
+
+    for index in 0..3 {
+        todos
+            .create(AppTodo::new(format!("Todo {index}"), false))
+            .await
+            .expect("create todo");
+    }
+    archive
+        .create(AppTodo::new("Old".to_string(), true))
+        .await
+        .expect("create archived");
+
+    let metrics = app.metrics_snapshot();
+    assert_eq!(metrics.collection_count, 2);
+    assert_eq!(metrics.total_entities, 4);
+    assert_eq!(metrics.total_ops, 4);
+    assert_eq!(metrics.snapshots_written, 1);
+    assert_eq!(metrics.replication_failures, 0);
+    let todo_stats = metrics
+        .collections
+        .iter()
+        .find(|stats| stats.vec_name == "metrics_todos")
+        .expect("todo stats");
+    assert_eq!(todo_stats.item_count, 3);
+    assert_eq!(todo_stats.total_ops, todos.stats().total_ops);
+
+    drop(archive);
+    let metrics = app.metrics_snapshot();
+    assert_eq!(metrics.collection_count, 1);
+    assert_eq!(metrics.total_entities, 3);
+}
+
+#[tokio::test]
+async fn persist_app_creates_tables_up_front_when_lazy_creation_is_off() {
+    let temp = tempfile::tempdir().expect("temp dir");
+    let app = PersistApp::open(
+        temp.path().join("eager_tables"),
+        PersistAppPolicy::default(),
+    )
+    .await
+    .expect("open app")
+    .with_lazy_table_creation(false);
+
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_eager")
+        .await
+        .expect("open vec creates its table");
+    todos
+        .create(AppTodo::new("Eager".to_string(), false))
+        .await
+        .expect("create into pre-created table");
+    let _dynamic = app
+        .open_vec::<DynamicAppTodoVec>("todo_eager_dynamic")
+        .await
+        .expect("open dynamic vec");
+    assert_eq!(app.create_all_tables().await.expect("create all"), 2);
+
+    let session = PersistSession::new(InMemoryDB::new()).with_lazy_tables(false);
+    let mut todo = AppTodo::new("Orphan".to_string(), false);
+    let err = todo
+        .save(&session)
+        .await
+        .expect_err("missing table must not be created lazily");
+    assert!(
+        err.to_string().contains("lazy table creation is disabled"),
+        "{}",
+        err
+    );
+
+    let snapshot = todos.collection().snapshot(SnapshotMode::WithData);
+    let err = AppTodoVec::new("restored")
+        .restore(snapshot, &session)
+        .await
+        .expect_err("restore must not create the table lazily");
+    assert!(
+        err.to_string().contains("lazy table creation is disabled"),
+        "{}",
+        err
+    );
+
+    PersistTableDefinition::of::<AppTodo>()
+        .create(&session)
+        .await
+        .expect("create table");
+    todo.save(&session).await.expect("save after create");
+}
+
+#[tokio::test]
+async fn managed_put_creates_then_replaces_by_client_id() {
+    let app = PersistApp::open_in_memory();
+    let mut todos = app
+        .open_vec::<AppTodoVec>("todo_put")
+        .await
+        .expect("open vec");
+
+    let with_id = |title: &str, done: bool| {
+        let mut state = AppTodo::new(title.to_string(), done).state();
+        state.persist_id = "todo-42".to_string();
+        AppTodo::from_state(&state).expect("todo from state")
+    };
+
+    let err = todos
+        .put(with_id("Ghost", false), Some(1))
+        .await
+        .expect_err("If-Match on a missing id must conflict");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::VersionConflict)
+    );
+
+    let created = todos.put(with_id("First", false), None).await.expect("put");
+    assert!(created);
+    let stored = todos.get("todo-42").expect("created todo");
+    assert_eq!(stored.metadata().version, 1);
+    let created_at = stored.metadata().created_at;
+
+    let err = todos
+        .put(with_id("Stale", true), Some(7))
+        .await
+        .expect_err("stale If-Match must conflict");
+    assert_eq!(
+        classify_managed_conflict(&err),
+        Some(ManagedConflictKind::VersionConflict)
+    );
+    assert_eq!(todos.get("todo-42").expect("todo").title(), "First");
+
+    let created = todos
+        .put(with_id("Replaced", true), Some(1))
+        .await
+        .expect("put replace");
+    assert!(!created);
+    assert_eq!(todos.list().len(), 1);
+    let stored = todos.get("todo-42").expect("replaced todo");
+    assert_eq!(stored.title(), "Replaced");
+    assert!(*stored.done());
+    assert_eq!(stored.metadata().version, 2);
+    assert_eq!(stored.metadata().created_at, created_at);
+}
+
+#[tokio::test]
+async fn persist_app_reports_and_applies_pending_migrations() {
+    let app = PersistApp::open_in_memory();
+    let _todos = app
+        .open_vec::<AppTodoVec>("todo_migrations")
+        .await
+        .expect("open vec");
+    let mut legacy = PersistTableDefinition::of::<AppTodo>();
+    legacy.type_name = "AppNote".to_string();
+    legacy.table_name = "app_note_migrations".to_string();
+    legacy.create_table_sql =
+        "CREATE TABLE IF NOT EXISTS app_note_migrations (title TEXT)".to_string();
+    app.register_table_definition(legacy.clone());
+    assert_eq!(app.create_all_tables().await.expect("create all"), 2);
+    assert!(app.pending_migrations().await.expect("pending").is_empty());
+
+    let mut current = legacy;
+    current.migration_plan = PersistMigrationPlan::new(2)
+        .with_step(
+            PersistMigrationStep::new(1, 2).with_sql("ALTER TABLE {table} ADD COLUMN body TEXT"),
+        )
+        .expect("plan");
+    app.register_table_definition(current);
+
+    let pending = app.pending_migrations().await.expect("pending");
+    assert_eq!(
+        pending,
+        vec![PersistPendingMigration {
+            type_name: "AppNote".to_string(),
+            table_name: "app_note_migrations".to_string(),
+            from_version: 1,
+            to_version: 2,
+        }]
+    );
+    assert_eq!(app.migrate_all().await.expect("migrate all"), pending);
+    assert!(app.pending_migrations().await.expect("pending").is_empty());
+    assert!(app.migrate_all().await.expect("rerun").is_empty());
+}
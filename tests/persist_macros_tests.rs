@@ -1,8 +1,9 @@
 use chrono::{Duration, Utc};
 use rustmemodb::{
-    InMemoryDB, InvokeStatus, PersistCommandModel, PersistEntity, PersistMigrationPlan,
-    PersistMigrationStep, PersistModel, PersistSession, PersistValue, RestoreConflictPolicy,
-    SnapshotMode, Value, persist_struct, persist_vec,
+    HeteroPersistVec, InMemoryDB, InvokeStatus, PERSIST_REDACTED_VALUE, PersistCommandModel,
+    PersistEntity, PersistIdStrategy, PersistMigrationPlan, PersistMigrationStep, PersistModel,
+    PersistSession, PersistValue, RestoreConflictPolicy, SnapshotMode, Value, persist_struct,
+    persist_vec,
 };
 
 persist_struct! {
@@ -29,6 +30,36 @@ persist_struct! {
     }"#
 }
 
+persist_struct! {
+    pub struct OptionalFlag {
+        name: String,
+        enabled: Option<bool>,
+    }
+}
+
+persist_struct! {
+    pub struct TaggedNote {
+        title: String,
+        tags: Vec<String>,
+    }
+}
+
+persist_struct! {
+    pub struct LeasePolicy {
+        name: String,
+        timeout: std::time::Duration,
+        ttl: Duration,
+    }
+}
+
+persist_struct! {
+    pub struct LedgerTotals {
+        high: i64,
+        low: i64,
+        samples: Vec<i64>,
+    }
+}
+
 persist_vec!(pub PersistUserVec, PersistUser);
 persist_vec!(pub DdlNoteVec, DdlNote);
 persist_vec!(hetero pub MixedPersistVec);
@@ -43,6 +74,164 @@ struct TaskModel {
 
 persist_struct!(pub struct PersistedTask from_struct = TaskModel);
 
+#[derive(Debug, Clone, PartialEq, rustmemodb::PersistEnumInt)]
+enum TicketStatus {
+    Open = 1,
+    InProgress = 5,
+    Closed = 9,
+}
+
+#[derive(PersistModel)]
+#[persist_model(schema_version = 2, migrations = reminder_migration_plan)]
+struct ReminderModel {
+    title: String,
+    priority: i64,
+}
+
+fn reminder_migration_plan() -> PersistMigrationPlan {
+    let mut plan = PersistMigrationPlan::new(2);
+    plan.add_state_step(1, 2, |state| {
+        state
+            .fields_object_mut()?
+            .insert("priority".to_string(), serde_json::json!(3));
+        Ok(())
+    })
+    .unwrap();
+    plan
+}
+
+#[derive(PersistModel)]
+struct SizedCodeModel {
+    #[sql(column_type = "VARCHAR(64)")]
+    code: String,
+    #[sql(column_type = "SMALLINT")]
+    rank: i64,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, rustmemodb::PersistFlatten,
+)]
+struct PostalAddress {
+    street: String,
+    zip: String,
+    unit: Option<i64>,
+}
+
+#[derive(PersistModel)]
+struct CustomerModel {
+    name: String,
+    #[sql(flatten)]
+    address: PostalAddress,
+    #[sql(flatten)]
+    billing: Option<PostalAddress>,
+}
+
+#[derive(PersistModel)]
+struct FeatureFlagModel {
+    key: String,
+    #[sql(bool_as = "int")]
+    enabled: bool,
+    #[sql(bool_as = "int")]
+    beta: Option<bool>,
+    archived: bool,
+}
+
+#[derive(PersistModel)]
+struct PricedItemModel {
+    name: String,
+    #[sql(check = "price_cents >= 0")]
+    price_cents: i64,
+}
+
+#[derive(PersistModel)]
+#[persist_model(
+    strict_fields = true,
+    generated(
+        name = "price_with_tax",
+        expr = "price_cents * 110 / 100",
+        type = "INTEGER"
+    )
+)]
+struct TaxedItemModel {
+    name: String,
+    price_cents: i64,
+}
+
+pub trait Amount: PersistValue + Copy {}
+
+impl Amount for i64 {}
+
+#[derive(PersistModel)]
+pub struct MoneyModel<T: Amount> {
+    label: String,
+    amount: T,
+}
+
+pub type MoneyI64 = MoneyModelPersisted<i64>;
+
+persist_vec!(pub MoneyVec, MoneyI64);
+
+#[derive(PersistModel)]
+struct BoxedValueModel<T> {
+    value: T,
+}
+
+#[derive(PersistModel)]
+struct HandleModel {
+    #[sql(max_len = 8)]
+    handle: String,
+    #[sql(max_len = 4)]
+    country: Option<String>,
+}
+
+#[derive(PersistModel)]
+pub struct PatientModel {
+    name: String,
+    #[sql(redact)]
+    ssn: String,
+    #[sql(redact)]
+    notes: Option<String>,
+}
+
+persist_vec!(pub PatientVec, PatientModelPersisted);
+
+#[derive(PersistModel)]
+struct ProfileModel {
+    name: String,
+    nickname: Option<String>,
+}
+
+#[derive(PersistModel)]
+#[persist_model(strict_fields = true)]
+struct StrictProfileModel {
+    name: String,
+    nickname: Option<String>,
+}
+
+#[derive(PersistModel)]
+#[persist_model(auto_touch = false)]
+struct ReviewModel {
+    title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, rustmemodb::PersistEnumText)]
+enum ShipmentStage {
+    Packed,
+    Shipped,
+}
+
+#[derive(PersistModel)]
+struct ShipmentModel {
+    label: String,
+    stage: ShipmentStage,
+}
+
+#[derive(PersistModel)]
+struct TicketModel {
+    title: String,
+    status: TicketStatus,
+}
+
 #[tokio::test]
 async fn persist_struct_saves_and_updates_only_changed_fields() {
     let session = PersistSession::new(InMemoryDB::new());
@@ -214,6 +403,13 @@ async fn persist_struct_from_ddl_saves_updates_and_reports_functions() {
         .unwrap();
     assert_eq!(rows.rows()[0][0], Value::Integer(20));
 
+    let touches = note.metadata().touch_count;
+    let version = note.metadata().version;
+    note.set_label("tenant", "acme");
+    assert_eq!(note.metadata().touch_count, touches + 1);
+    note.save(&session).await.unwrap();
+    assert_eq!(note.metadata().version, version + 1);
+
     let functions = note.available_functions();
     assert!(functions.iter().any(|f| f.name == "state"));
     assert!(functions.iter().any(|f| f.name == "save"));
@@ -431,6 +627,393 @@ fn persist_value_sql_literals_are_available_for_options() {
     assert_eq!(none_value.to_sql_literal(), "NULL");
 }
 
+#[tokio::test]
+async fn persist_enum_int_stores_discriminant_as_integer() {
+    assert_eq!(TicketStatus::sql_type(), "INTEGER");
+    assert_eq!(TicketStatus::InProgress.to_sql_literal(), "5");
+    assert_eq!(TicketStatus::try_from(9).unwrap(), TicketStatus::Closed);
+    assert!(TicketStatus::try_from(2).is_err());
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut ticket = TicketModel {
+        title: "Broken build".to_string(),
+        status: TicketStatus::Open,
+    }
+    .into_persisted();
+    ticket.save(&session).await.unwrap();
+    ticket.set_status(TicketStatus::InProgress);
+    ticket.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT status FROM {} WHERE status > 1",
+            ticket.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.row_count(), 1);
+    assert_eq!(selected.rows()[0][0], Value::Integer(5));
+
+    let state = ticket.state();
+    assert_eq!(state.fields["status"], serde_json::json!(5));
+    let restored =
+        <TicketModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(restored.status(), &TicketStatus::InProgress);
+
+    let mut bad_state = state.clone();
+    bad_state.fields["status"] = serde_json::json!(3);
+    assert!(
+        <TicketModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&bad_state).is_err()
+    );
+}
+
+#[tokio::test]
+async fn sql_column_type_overrides_emitted_column_type() {
+    let ddl = SizedCodeModelPersisted::create_table_sql_for("sized_codes");
+    assert!(ddl.contains("code VARCHAR(64)"), "unexpected ddl: {ddl}");
+    assert!(ddl.contains("rank SMALLINT"), "unexpected ddl: {ddl}");
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut code = SizedCodeModel {
+        code: "A-1".to_string(),
+        rank: 3,
+    }
+    .into_persisted();
+    code.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!("SELECT code, rank FROM {}", code.table_name()))
+        .await
+        .unwrap();
+    assert_eq!(selected.rows()[0][0], Value::Text("A-1".to_string()));
+    assert_eq!(selected.rows()[0][1], Value::Integer(3));
+}
+
+#[tokio::test]
+async fn sql_flatten_stores_embedded_struct_as_prefixed_columns() {
+    let ddl = CustomerModelPersisted::create_table_sql_for("customers");
+    assert!(ddl.contains("address_street TEXT"), "unexpected ddl: {ddl}");
+    assert!(ddl.contains("address_zip TEXT"), "unexpected ddl: {ddl}");
+    assert!(
+        ddl.contains("address_unit INTEGER"),
+        "unexpected ddl: {ddl}"
+    );
+    assert!(ddl.contains("billing_zip TEXT"), "unexpected ddl: {ddl}");
+    assert!(!ddl.contains("address JSON"), "unexpected ddl: {ddl}");
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut customer = CustomerModel {
+        name: "Ada".to_string(),
+        address: PostalAddress {
+            street: "Main St 1".to_string(),
+            zip: "10115".to_string(),
+            unit: None,
+        },
+        billing: None,
+    }
+    .into_persisted();
+    customer.save(&session).await.unwrap();
+    session
+        .execute(&format!(
+            "CREATE INDEX idx_customer_zip ON {} (address_zip)",
+            customer.table_name()
+        ))
+        .await
+        .unwrap();
+
+    customer.set_billing(Some(PostalAddress {
+        street: "Side St 2".to_string(),
+        zip: "20095".to_string(),
+        unit: Some(4),
+    }));
+    customer.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT address_street, address_unit, billing_zip, billing_unit FROM {} WHERE address_zip = '10115'",
+            customer.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        selected.rows()[0],
+        vec![
+            Value::Text("Main St 1".to_string()),
+            Value::Null,
+            Value::Text("20095".to_string()),
+            Value::Integer(4),
+        ]
+    );
+
+    let state = session
+        .load_persist_state(
+            customer.table_name(),
+            customer.persist_id(),
+            "CustomerModel",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(state.fields.get("address").is_none());
+    let loaded =
+        <CustomerModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(loaded.address(), customer.address());
+    assert_eq!(loaded.billing(), customer.billing());
+
+    customer.set_billing(None);
+    customer.save(&session).await.unwrap();
+    let state = session
+        .load_persist_state(
+            customer.table_name(),
+            customer.persist_id(),
+            "CustomerModel",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let loaded =
+        <CustomerModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(loaded.billing(), &None);
+}
+
+#[tokio::test]
+async fn sql_bool_as_int_stores_booleans_as_integers() {
+    let ddl = FeatureFlagModelPersisted::create_table_sql_for("feature_flags");
+    assert!(ddl.contains("enabled INTEGER"), "unexpected ddl: {ddl}");
+    assert!(ddl.contains("beta INTEGER"), "unexpected ddl: {ddl}");
+    assert!(ddl.contains("archived BOOLEAN"), "unexpected ddl: {ddl}");
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut flag = FeatureFlagModel {
+        key: "dark_mode".to_string(),
+        enabled: true,
+        beta: None,
+        archived: false,
+    }
+    .into_persisted();
+    flag.save(&session).await.unwrap();
+    flag.set_enabled(false);
+    flag.set_beta(Some(true));
+    flag.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT enabled, beta, archived FROM {}",
+            flag.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        selected.rows()[0],
+        vec![Value::Integer(0), Value::Integer(1), Value::Boolean(false)]
+    );
+
+    let state = session
+        .load_persist_state(flag.table_name(), flag.persist_id(), "FeatureFlagModel")
+        .await
+        .unwrap()
+        .unwrap();
+    let loaded =
+        <FeatureFlagModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state)
+            .unwrap();
+    assert!(!*loaded.enabled());
+    assert_eq!(loaded.beta(), &Some(true));
+
+    let native =
+        <FeatureFlagModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&flag.state())
+            .unwrap();
+    assert_eq!(native.beta(), &Some(true));
+}
+
+#[tokio::test]
+async fn sql_check_emits_constraint_enforced_on_insert_and_update() {
+    let ddl = PricedItemModelPersisted::create_table_sql_for("priced_items");
+    assert!(
+        ddl.contains("price_cents INTEGER CHECK (price_cents >= 0)"),
+        "unexpected ddl: {ddl}"
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut invalid = PricedItemModel {
+        name: "refund".to_string(),
+        price_cents: -5,
+    }
+    .into_persisted();
+    let err = invalid.save(&session).await.unwrap_err();
+    assert!(
+        matches!(err, rustmemodb::DbError::ConstraintViolation(_)),
+        "unexpected error: {err:?}"
+    );
+    assert!(err.to_string().contains("CHECK"), "{err}");
+
+    let mut item = PricedItemModel {
+        name: "book".to_string(),
+        price_cents: 1200,
+    }
+    .into_persisted();
+    item.save(&session).await.unwrap();
+    item.set_price_cents(-1);
+    let err = item.save(&session).await.unwrap_err();
+    assert!(err.to_string().contains("CHECK"), "{err}");
+
+    let stored = session
+        .query(&format!("SELECT price_cents FROM {}", item.table_name()))
+        .await
+        .unwrap();
+    assert_eq!(stored.rows()[0][0], Value::Integer(1200));
+}
+
+#[tokio::test]
+async fn persist_model_generated_column_is_stored_and_queryable() {
+    let ddl = TaxedItemModelPersisted::create_table_sql_for("taxed_items");
+    assert!(
+        ddl.contains("price_with_tax INTEGER"),
+        "unexpected ddl: {ddl}"
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut item = TaxedItemModel {
+        name: "book".to_string(),
+        price_cents: 1000,
+    }
+    .into_persisted();
+    item.save(&session).await.unwrap();
+    session
+        .execute(&format!(
+            "CREATE INDEX idx_price_with_tax ON {} (price_with_tax)",
+            item.table_name()
+        ))
+        .await
+        .unwrap();
+    let select = format!(
+        "SELECT price_with_tax FROM {} WHERE price_with_tax > 1000",
+        item.table_name()
+    );
+    let stored = session.query(&select).await.unwrap();
+    assert_eq!(stored.rows()[0][0], Value::Integer(1100));
+
+    item.set_price_cents(2000);
+    item.save(&session).await.unwrap();
+    let stored = session.query(&select).await.unwrap();
+    assert_eq!(stored.rows()[0][0], Value::Integer(2200));
+    assert!(!item.state().fields.to_string().contains("price_with_tax"));
+
+    // Loading the row returns the generated column, which strict_fields accepts.
+    let loaded = session
+        .load_persist_state(item.table_name(), item.persist_id(), item.type_name())
+        .await
+        .unwrap()
+        .expect("stored row");
+    assert_eq!(loaded.metadata.version, 2);
+    let restored =
+        <TaxedItemModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&loaded).unwrap();
+    assert_eq!(restored.price_cents(), &2000);
+}
+
+#[tokio::test]
+async fn persist_model_supports_generic_structs() {
+    assert_eq!(MoneyI64::default_table_name(), "persist_moneymodel_i64");
+    assert_ne!(
+        BoxedValueModelPersisted::<String>::default_table_name(),
+        BoxedValueModelPersisted::<i64>::default_table_name()
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut price = MoneyModel {
+        label: "book".to_string(),
+        amount: 1200_i64,
+    }
+    .into_persisted();
+    price.save(&session).await.unwrap();
+    price
+        .apply(MoneyModelPersistedCommand::SetAmount(1500))
+        .unwrap();
+    price
+        .patch(MoneyModelPersistedPatch {
+            label: Some("hardcover".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    price.save(&session).await.unwrap();
+
+    let restored =
+        <MoneyI64 as rustmemodb::PersistEntityFactory>::from_state(&price.state()).unwrap();
+    assert_eq!(restored.amount(), &1500);
+    assert_eq!(restored.label(), "hardcover");
+    let mut prices = MoneyVec::new("prices");
+    prices.add_one(restored);
+    assert_eq!(prices.len(), 1);
+
+    let mut text = BoxedValueModel {
+        value: "hello".to_string(),
+    }
+    .into_persisted();
+    let mut number = BoxedValueModel { value: 7_i64 }.into_persisted();
+    text.save(&session).await.unwrap();
+    number.save(&session).await.unwrap();
+    let stored = session
+        .query(&format!("SELECT value FROM {}", number.table_name()))
+        .await
+        .unwrap();
+    assert_eq!(stored.rows()[0][0], Value::Integer(7));
+
+    // Both instantiations live side by side in one registry.
+    assert_eq!(text.type_name(), "BoxedValueModel<String>");
+    assert_eq!(number.type_name(), "BoxedValueModel<i64>");
+    let mut mixed = HeteroPersistVec::new("boxed_values");
+    mixed.register_type::<BoxedValueModelPersisted<String>>();
+    mixed.register_type::<BoxedValueModelPersisted<i64>>();
+    assert_eq!(mixed.registered_types().len(), 2);
+    mixed.add_one(text).unwrap();
+    mixed.add_one(number).unwrap();
+    let snapshot = mixed.snapshot(SnapshotMode::WithData);
+
+    let restore_session = PersistSession::new(InMemoryDB::new());
+    let mut restored = HeteroPersistVec::new("boxed_values");
+    restored.register_type::<BoxedValueModelPersisted<String>>();
+    restored.register_type::<BoxedValueModelPersisted<i64>>();
+    restored.restore(snapshot, &restore_session).await.unwrap();
+    let mut type_names = restored
+        .states()
+        .into_iter()
+        .map(|state| state.type_name)
+        .collect::<Vec<_>>();
+    type_names.sort();
+    assert_eq!(
+        type_names,
+        vec!["BoxedValueModel<String>", "BoxedValueModel<i64>"]
+    );
+}
+
+#[tokio::test]
+async fn sql_max_len_emits_varchar_enforced_on_write() {
+    let ddl = HandleModelPersisted::create_table_sql_for("handles");
+    assert!(ddl.contains("handle VARCHAR(8)"), "unexpected ddl: {ddl}");
+    assert!(ddl.contains("country VARCHAR(4)"), "unexpected ddl: {ddl}");
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut handle = HandleModel {
+        handle: "ada".to_string(),
+        country: None,
+    }
+    .into_persisted();
+    handle.save(&session).await.unwrap();
+
+    handle.set_handle("ada_lovelace".to_string());
+    let err = handle.save(&session).await.unwrap_err();
+    assert!(
+        matches!(err, rustmemodb::DbError::ConstraintViolation(_)),
+        "unexpected error: {err:?}"
+    );
+
+    let mut too_long = HandleModel {
+        handle: "grace".to_string(),
+        country: Some("Britain".to_string()),
+    }
+    .into_persisted();
+    assert!(too_long.save(&session).await.is_err());
+}
+
 #[tokio::test]
 async fn persist_vec_restore_conflict_policies_work() {
     let session = PersistSession::new(InMemoryDB::new());
@@ -467,6 +1050,68 @@ async fn persist_vec_restore_conflict_policies_work() {
     assert_eq!(overwrite_restore.len(), 1);
 }
 
+#[tokio::test]
+async fn persist_vec_restore_merge_policy_reconciles_existing_rows() {
+    fn keep_best_score(
+        existing: &rustmemodb::PersistState,
+        incoming: &rustmemodb::PersistState,
+    ) -> rustmemodb::PersistState {
+        let score = |state: &rustmemodb::PersistState| {
+            state
+                .fields
+                .get("score")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0)
+        };
+        let mut merged = incoming.clone();
+        merged.fields["score"] = serde_json::json!(score(existing).max(score(incoming)));
+        merged
+    }
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut users = PersistUserVec::new("users");
+
+    let mut user = PersistUser::new("Alice".to_string(), 10, true);
+    user.save(&session).await.unwrap();
+    let user_id = user.persist_id().to_string();
+    users.add_one(user);
+    let snapshot = users.snapshot(SnapshotMode::WithData);
+
+    session
+        .execute(&format!(
+            "UPDATE {} SET score = 50, name = 'Stored' WHERE __persist_id = '{}'",
+            snapshot.table_name, user_id
+        ))
+        .await
+        .unwrap();
+
+    let mut merged = PersistUserVec::new("merged");
+    merged
+        .restore_with_policy(
+            snapshot,
+            &session,
+            RestoreConflictPolicy::Merge(keep_best_score),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(merged.len(), 1);
+    let restored = &merged.items()[0];
+    assert_eq!(restored.persist_id(), user_id);
+    assert_eq!(*restored.score(), 50);
+    assert_eq!(restored.name(), "Alice");
+
+    let stored = session
+        .query(&format!(
+            "SELECT score FROM {} WHERE __persist_id = '{}'",
+            restored.table_name(),
+            user_id
+        ))
+        .await
+        .unwrap();
+    assert_eq!(stored.rows()[0][0], Value::Integer(50));
+}
+
 #[tokio::test]
 async fn heterogeneous_persist_vec_supports_mixed_types_and_selective_invoke() {
     let session = PersistSession::new(InMemoryDB::new());
@@ -686,3 +1331,611 @@ fn heterogeneous_persist_vec_rejects_unregistered_types() {
     let err = mixed.add_one(user).unwrap_err();
     assert!(err.to_string().contains("not registered"));
 }
+
+#[test]
+fn state_checksum_tracks_field_changes_and_ignores_key_order() {
+    let mut user = PersistUser::new("Alice".to_string(), 10, true);
+    let initial = user.state_checksum();
+    assert_eq!(initial, user.state_checksum());
+
+    let restored =
+        <PersistUser as rustmemodb::PersistEntityFactory>::from_state(&user.state()).unwrap();
+    assert_eq!(restored.state_checksum(), initial);
+
+    user.set_score(11);
+    assert_ne!(user.state_checksum(), initial);
+
+    let a = serde_json::json!({"a": 1, "b": {"x": [1, 2], "y": null}});
+    let b: serde_json::Value =
+        serde_json::from_str(r#"{"b": {"y": null, "x": [1, 2]}, "a": 1}"#).unwrap();
+    assert_eq!(
+        rustmemodb::persist::state_json_checksum(&a),
+        rustmemodb::persist::state_json_checksum(&b)
+    );
+}
+
+#[tokio::test]
+async fn persist_metadata_labels_are_persisted_and_backward_compatible() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let table = "legacy_labeled_users";
+    session
+        .execute(&format!(
+            "CREATE TABLE {} (__persist_id TEXT PRIMARY KEY, __version INTEGER NOT NULL, __schema_version INTEGER NOT NULL, __touch_count INTEGER NOT NULL, __created_at TIMESTAMP NOT NULL, __updated_at TIMESTAMP NOT NULL, __last_touch_at TIMESTAMP NOT NULL, name TEXT, score INTEGER, active BOOLEAN)",
+            table
+        ))
+        .await
+        .unwrap();
+
+    let mut user = PersistUser::with_table_name(table, "Alice".to_string(), 1, true);
+    let touches = user.metadata().touch_count;
+    user.set_label("tenant", "acme");
+    assert_eq!(user.metadata().touch_count, touches + 1);
+    user.save(&session).await.unwrap();
+    assert_eq!(user.get_label("tenant"), Some("acme"));
+    assert_eq!(user.state().metadata.labels["tenant"], "acme");
+
+    user.set_label("source", "import");
+    user.save(&session).await.unwrap();
+    assert_eq!(user.metadata().version, 2);
+
+    let loaded = session
+        .load_persist_state(table, user.persist_id(), "PersistUser")
+        .await
+        .unwrap()
+        .expect("stored row");
+    assert_eq!(loaded.metadata.labels.len(), 2);
+    assert_eq!(loaded.metadata.labels["source"], "import");
+
+    let mut legacy_metadata = serde_json::to_value(&loaded.metadata).unwrap();
+    legacy_metadata.as_object_mut().unwrap().remove("labels");
+    let legacy: rustmemodb::PersistMetadata = serde_json::from_value(legacy_metadata).unwrap();
+    assert!(legacy.labels.is_empty());
+}
+
+#[tokio::test]
+async fn persist_vec_find_by_label_filters_on_metadata_labels() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut users = PersistUserVec::new("labeled_users");
+
+    let mut alice = PersistUser::new("Alice".to_string(), 10, true);
+    alice.set_label("tenant", "acme");
+    let mut bob = PersistUser::new("Bob".to_string(), 20, true);
+    bob.set_label("tenant", "globex");
+    let carol = PersistUser::new("Carol".to_string(), 30, true);
+    users.add_many(vec![alice, bob, carol]);
+    users.save_all(&session).await.unwrap();
+
+    let acme = users.find_by_label("tenant", "acme");
+    assert_eq!(acme.len(), 1);
+    assert_eq!(acme[0].name(), "Alice");
+    assert!(users.find_by_label("tenant", "initech").is_empty());
+    assert!(users.find_by_label("source", "acme").is_empty());
+
+    let restore_session = PersistSession::new(InMemoryDB::new());
+    let mut restored = PersistUserVec::new("labeled_users_restored");
+    restored
+        .restore(users.snapshot(SnapshotMode::WithData), &restore_session)
+        .await
+        .unwrap();
+    let globex = restored.find_by_label("tenant", "globex");
+    assert_eq!(globex.len(), 1);
+    assert_eq!(globex[0].name(), "Bob");
+}
+
+#[tokio::test]
+async fn persist_struct_vec_field_maps_to_sql_array() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut note = TaggedNote::new(
+        "Release".to_string(),
+        vec!["rust".to_string(), "o'clock".to_string()],
+    );
+    note.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT title FROM {} WHERE 'o''clock' = ANY(tags)",
+            note.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.row_count(), 1);
+
+    note.set_tags(vec!["db".to_string()]);
+    note.save(&session).await.unwrap();
+
+    let loaded = session
+        .load_persist_state(note.table_name(), note.persist_id(), "TaggedNote")
+        .await
+        .unwrap()
+        .expect("stored row");
+    let restored = <TaggedNote as rustmemodb::PersistEntityFactory>::from_state(&loaded).unwrap();
+    assert_eq!(restored.tags(), &vec!["db".to_string()]);
+}
+
+#[test]
+fn from_state_upcasts_older_schema_versions_with_registered_migrations() {
+    let reminder = ReminderModel {
+        title: "Call back".to_string(),
+        priority: 1,
+    }
+    .into_persisted();
+
+    let mut legacy_state = reminder.state();
+    legacy_state.metadata.schema_version = 1;
+    legacy_state
+        .fields
+        .as_object_mut()
+        .unwrap()
+        .remove("priority");
+
+    let restored =
+        <ReminderModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&legacy_state)
+            .unwrap();
+    assert_eq!(restored.title(), "Call back");
+    assert_eq!(*restored.priority(), 3);
+    assert_eq!(restored.metadata().schema_version, 2);
+
+    // Without a migration plan the missing field is still a hard error.
+    let mut task_state = TaskModel {
+        title: "Legacy".to_string(),
+        done: false,
+        attempts: 0,
+    }
+    .into_persisted()
+    .state();
+    task_state.metadata.schema_version = 1;
+    task_state
+        .fields
+        .as_object_mut()
+        .unwrap()
+        .remove("attempts");
+    let err = <TaskModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&task_state)
+        .err()
+        .expect("missing field without migration must fail");
+    assert!(err.to_string().contains("attempts"), "{}", err);
+}
+
+#[tokio::test]
+async fn persist_struct_round_trips_extreme_i64_values_exactly() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let samples = vec![i64::MAX, (1_i64 << 53) + 1, i64::MIN];
+    let mut totals = LedgerTotals::new(i64::MAX, i64::MIN, samples.clone());
+    totals.save(&session).await.unwrap();
+
+    let state = session
+        .load_persist_state(totals.table_name(), totals.persist_id(), "LedgerTotals")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.fields["high"], serde_json::json!(i64::MAX));
+    assert_eq!(state.fields["low"], serde_json::json!(i64::MIN));
+
+    let reloaded = <LedgerTotals as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(*reloaded.high(), i64::MAX);
+    assert_eq!(*reloaded.low(), i64::MIN);
+    assert_eq!(reloaded.samples(), &samples);
+}
+
+#[tokio::test]
+async fn persist_struct_save_if_changed_and_force_save() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut user = PersistUser::new("Alice".to_string(), 10, true);
+
+    assert!(user.save_if_changed(&session).await.unwrap());
+    assert!(!user.save_if_changed(&session).await.unwrap());
+    assert_eq!(user.metadata().version, 1);
+
+    user.set_score(11);
+    assert!(user.save_if_changed(&session).await.unwrap());
+    assert_eq!(user.metadata().version, 2);
+
+    session
+        .execute(&format!(
+            "UPDATE {} SET name = 'Mallory' WHERE __persist_id = '{}'",
+            user.table_name(),
+            user.persist_id()
+        ))
+        .await
+        .unwrap();
+    user.force_save(&session).await.unwrap();
+    assert_eq!(user.metadata().version, 3);
+    assert!(!user.has_unsaved_changes());
+
+    let selected = session
+        .query(&format!(
+            "SELECT name, score FROM {} WHERE __persist_id = '{}'",
+            user.table_name(),
+            user.persist_id()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.rows()[0][0], Value::Text("Alice".to_string()));
+    assert_eq!(selected.rows()[0][1], Value::Integer(11));
+}
+
+#[tokio::test]
+async fn persist_struct_records_created_by_and_updated_by_actor() {
+    let base = PersistSession::new(InMemoryDB::new());
+    let alice = base.with_actor("alice");
+    let bob = base.with_actor("bob");
+    assert_eq!(alice.actor(), Some("alice"));
+    assert_eq!(base.actor(), None);
+
+    let mut user = PersistUser::new("Alice".to_string(), 10, true);
+    user.save(&alice).await.unwrap();
+    assert_eq!(user.metadata().created_by.as_deref(), Some("alice"));
+    assert_eq!(user.metadata().updated_by.as_deref(), Some("alice"));
+
+    user.set_score(11);
+    user.save(&bob).await.unwrap();
+    assert_eq!(user.metadata().created_by.as_deref(), Some("alice"));
+    assert_eq!(user.metadata().updated_by.as_deref(), Some("bob"));
+
+    user.set_score(12);
+    user.save(&base).await.unwrap();
+    assert_eq!(user.metadata().updated_by.as_deref(), Some("bob"));
+
+    let selected = base
+        .query(&format!(
+            "SELECT __created_by, __updated_by FROM {} WHERE __persist_id = '{}'",
+            user.table_name(),
+            user.persist_id()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.rows()[0][0], Value::Text("alice".to_string()));
+    assert_eq!(selected.rows()[0][1], Value::Text("bob".to_string()));
+}
+
+#[tokio::test]
+async fn persist_enum_text_maps_to_enum_column() {
+    assert_eq!(
+        <ShipmentStage as PersistValue>::sql_type(),
+        "ENUM('Packed', 'Shipped')"
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut shipment = ShipmentModelPersisted::new(ShipmentModel {
+        label: "box-1".to_string(),
+        stage: ShipmentStage::Packed,
+    });
+    shipment.save(&session).await.unwrap();
+
+    let err = session
+        .execute(&format!(
+            "UPDATE {} SET stage = 'Lost' WHERE __persist_id = '{}'",
+            shipment.table_name(),
+            shipment.persist_id()
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("'Lost'"), "{err}");
+
+    shipment.set_stage(ShipmentStage::Shipped);
+    shipment.save(&session).await.unwrap();
+
+    let state = session
+        .load_persist_state(
+            shipment.table_name(),
+            shipment.persist_id(),
+            "ShipmentModel",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.fields["stage"], serde_json::json!("Shipped"));
+    let reloaded =
+        <ShipmentModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(reloaded.stage(), &ShipmentStage::Shipped);
+}
+
+#[test]
+fn persist_id_strategies_generate_sortable_and_custom_ids() {
+    let uuid7_a = PersistIdStrategy::Uuid7.generate();
+    let ulid_a = PersistIdStrategy::Ulid.generate();
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let uuid7_b = PersistIdStrategy::Uuid7.generate();
+    let ulid_b = PersistIdStrategy::Ulid.generate();
+
+    assert_eq!(
+        uuid::Uuid::parse_str(&uuid7_a).unwrap().get_version_num(),
+        7
+    );
+    assert!(uuid7_a < uuid7_b);
+    assert_eq!(ulid_a.len(), 26);
+    assert!(ulid_a < ulid_b);
+    assert_ne!(PersistIdStrategy::Ulid.generate(), ulid_b);
+
+    let custom = PersistIdStrategy::custom(|| "user-1".to_string());
+    assert_eq!(custom.generate(), "user-1");
+    let user = PersistUser::new("Default".to_string(), 1, true);
+    assert!(uuid::Uuid::parse_str(user.persist_id()).is_ok());
+    assert!(uuid::Uuid::parse_str(&rustmemodb::new_persist_id()).is_ok());
+}
+
+#[tokio::test]
+async fn persist_struct_round_trips_durations_and_stores_milliseconds() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut short = LeasePolicy::new(
+        "short".to_string(),
+        std::time::Duration::new(1, 500_000_123),
+        Duration::milliseconds(-250),
+    );
+    let mut huge = LeasePolicy::new("huge".to_string(), std::time::Duration::MAX, Duration::MAX);
+    short.save(&session).await.unwrap();
+    huge.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT name, timeout, ttl FROM {} ORDER BY timeout",
+            short.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.rows()[0][0], Value::Text("short".to_string()));
+    assert_eq!(selected.rows()[0][1], Value::Integer(1_500));
+    assert_eq!(selected.rows()[0][2], Value::Integer(-250));
+    assert_eq!(selected.rows()[1][1], Value::Integer(i64::MAX));
+    assert_eq!(
+        selected.rows()[1][2],
+        Value::Integer(Duration::MAX.num_milliseconds())
+    );
+
+    let restored =
+        <LeasePolicy as rustmemodb::PersistEntityFactory>::from_state(&huge.state()).unwrap();
+    assert_eq!(*restored.timeout(), std::time::Duration::MAX);
+    assert_eq!(*restored.ttl(), Duration::MAX);
+    let restored =
+        <LeasePolicy as rustmemodb::PersistEntityFactory>::from_state(&short.state()).unwrap();
+    assert_eq!(
+        *restored.timeout(),
+        std::time::Duration::new(1, 500_000_123)
+    );
+    assert_eq!(*restored.ttl(), Duration::milliseconds(-250));
+
+    let loaded = session
+        .load_persist_state(short.table_name(), short.persist_id(), "LeasePolicy")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(loaded.fields["timeout"], serde_json::json!(1_500));
+    let reloaded = <LeasePolicy as rustmemodb::PersistEntityFactory>::from_state(&loaded).unwrap();
+    assert_eq!(*reloaded.timeout(), std::time::Duration::from_millis(1_500));
+    assert_eq!(*reloaded.ttl(), Duration::milliseconds(-250));
+}
+
+#[test]
+fn sql_redact_masks_fields_only_in_redacted_snapshots() {
+    let mut patients = PatientVec::new("patients");
+    patients.add_one(
+        PatientModel {
+            name: "Ann".to_string(),
+            ssn: "123-45-6789".to_string(),
+            notes: None,
+        }
+        .into_persisted(),
+    );
+
+    let full = patients.snapshot(SnapshotMode::WithData);
+    assert_eq!(full.states[0].fields["ssn"], "123-45-6789");
+
+    let redacted = patients.snapshot_redacted(SnapshotMode::WithData);
+    let fields = &redacted.states[0].fields;
+    assert_eq!(fields["name"], "Ann");
+    assert_eq!(fields["ssn"], PERSIST_REDACTED_VALUE);
+    assert!(fields["notes"].is_null());
+
+    let mut mixed = MixedPersistVec::new("mixed_patients");
+    mixed.register_type::<PatientModelPersisted>();
+    mixed
+        .add_one(
+            PatientModel {
+                name: "Bob".to_string(),
+                ssn: "987-65-4321".to_string(),
+                notes: Some("allergic".to_string()),
+            }
+            .into_persisted(),
+        )
+        .unwrap();
+    let redacted = mixed.snapshot_redacted(SnapshotMode::WithData);
+    assert_eq!(redacted.states[0].fields["ssn"], PERSIST_REDACTED_VALUE);
+    assert_eq!(redacted.states[0].fields["notes"], PERSIST_REDACTED_VALUE);
+}
+
+#[test]
+fn from_state_is_lenient_unless_strict_fields_is_set() {
+    let mut state = ProfileModel {
+        name: "Ann".to_string(),
+        nickname: Some("a".to_string()),
+    }
+    .into_persisted()
+    .state();
+    let fields = state.fields.as_object_mut().unwrap();
+    fields.remove("nickname");
+    fields.insert("legacy_rank".to_string(), serde_json::json!(3));
+
+    let lenient =
+        <ProfileModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state).unwrap();
+    assert_eq!(lenient.name(), "Ann");
+    assert_eq!(lenient.nickname(), &None);
+
+    state.fields.as_object_mut().unwrap().remove("name");
+    let err = <ProfileModelPersisted as rustmemodb::PersistEntityFactory>::from_state(&state)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("Field 'name' missing"), "{err}");
+
+    let mut strict_state = StrictProfileModel {
+        name: "Bob".to_string(),
+        nickname: None,
+    }
+    .into_persisted()
+    .state();
+    assert!(
+        <StrictProfileModelPersisted as rustmemodb::PersistEntityFactory>::from_state(
+            &strict_state
+        )
+        .is_ok()
+    );
+    strict_state.fields["legacy_rank"] = serde_json::json!(3);
+    let err = <StrictProfileModelPersisted as rustmemodb::PersistEntityFactory>::from_state(
+        &strict_state,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        err.to_string().contains("Unknown field 'legacy_rank'"),
+        "{err}"
+    );
+
+    strict_state
+        .fields
+        .as_object_mut()
+        .unwrap()
+        .remove("legacy_rank");
+    strict_state
+        .fields
+        .as_object_mut()
+        .unwrap()
+        .remove("nickname");
+    let err = <StrictProfileModelPersisted as rustmemodb::PersistEntityFactory>::from_state(
+        &strict_state,
+    )
+    .err()
+    .unwrap();
+    assert!(
+        err.to_string().contains("Field 'nickname' missing"),
+        "{err}"
+    );
+}
+
+#[test]
+fn persist_state_equality_ignores_volatile_metadata() {
+    let user = PersistUser::new("Ada".to_string(), 1, true);
+    let state = user.state();
+
+    let mut touched = state.clone();
+    touched.metadata.updated_at += Duration::seconds(5);
+    touched.metadata.last_touch_at += Duration::seconds(5);
+    touched.metadata.touch_count += 1;
+    assert_eq!(state, touched);
+    assert!(!state.eq_with_metadata(&touched));
+    assert!(state.eq_with_metadata(&state.clone()));
+
+    let states = std::collections::HashSet::from([state.clone(), touched]);
+    assert_eq!(states.len(), 1);
+
+    let mut bumped = state.clone();
+    bumped.metadata.version += 1;
+    assert_ne!(state, bumped);
+
+    let mut edited = state.clone();
+    edited.fields["score"] = serde_json::json!(2);
+    assert_ne!(state, edited);
+}
+
+#[tokio::test]
+async fn auto_touch_false_only_persists_explicit_touches() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut review = ReviewModel {
+        title: "Draft".to_string(),
+    }
+    .into_persisted();
+    review.save(&session).await.unwrap();
+    let touched_at = review.metadata().last_touch_at;
+    assert_eq!(review.metadata().touch_count, 1);
+
+    let stored_touch_count = |session: PersistSession, table: String, id: String| async move {
+        let result = session
+            .query(&format!(
+                "SELECT __touch_count FROM {} WHERE __persist_id = '{}'",
+                table, id
+            ))
+            .await
+            .unwrap();
+        result.rows()[0][0].clone()
+    };
+    let table = review.table_name().to_string();
+    let id = review.persist_id().to_string();
+
+    review.set_title("Final".to_string());
+    assert_eq!(review.metadata().touch_count, 1);
+    review.save(&session).await.unwrap();
+    assert_eq!(review.metadata().version, 2);
+    assert_eq!(review.metadata().last_touch_at, touched_at);
+    assert_eq!(
+        stored_touch_count(session.clone(), table.clone(), id.clone()).await,
+        Value::Integer(1)
+    );
+
+    review.touch();
+    review.save(&session).await.unwrap();
+    assert_eq!(review.metadata().version, 3);
+    assert_eq!(
+        stored_touch_count(session.clone(), table, id).await,
+        Value::Integer(2)
+    );
+}
+
+#[test]
+fn from_states_keeps_order_and_isolates_bad_rows() {
+    let current = ReminderModel {
+        title: "Current".to_string(),
+        priority: 1,
+    }
+    .into_persisted()
+    .state();
+
+    let mut legacy = ReminderModel {
+        title: "Legacy".to_string(),
+        priority: 1,
+    }
+    .into_persisted()
+    .state();
+    legacy.metadata.schema_version = 1;
+    legacy.fields.as_object_mut().unwrap().remove("priority");
+
+    let mut broken = current.clone();
+    broken.fields = serde_json::json!(["not", "an", "object"]);
+
+    let results = <ReminderModelPersisted as rustmemodb::PersistEntityFactory>::from_states(vec![
+        current, broken, legacy,
+    ]);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().title(), "Current");
+    assert!(results[1].is_err());
+    let upcast = results[2].as_ref().unwrap();
+    assert_eq!(upcast.title(), "Legacy");
+    assert_eq!(*upcast.priority(), 3);
+    assert_eq!(upcast.metadata().schema_version, 2);
+}
+
+#[tokio::test]
+async fn persist_struct_option_bool_keeps_null_distinct_from_false() {
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut unknown = OptionalFlag::new("unknown".to_string(), None);
+    let mut off = OptionalFlag::new("off".to_string(), Some(false));
+    unknown.save(&session).await.unwrap();
+    off.save(&session).await.unwrap();
+
+    let selected = session
+        .query(&format!(
+            "SELECT name FROM {} WHERE enabled = FALSE",
+            off.table_name()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(selected.row_count(), 1);
+    assert_eq!(selected.rows()[0][0], Value::Text("off".to_string()));
+
+    for (flag, expected) in [(&unknown, None), (&off, Some(false))] {
+        let loaded = session
+            .load_persist_state(flag.table_name(), flag.persist_id(), "OptionalFlag")
+            .await
+            .unwrap()
+            .expect("stored row");
+        let restored =
+            <OptionalFlag as rustmemodb::PersistEntityFactory>::from_state(&loaded).unwrap();
+        assert_eq!(restored.enabled(), &expected);
+    }
+}
@@ -844,6 +844,115 @@ async fn runtime_projection_sync_index_lookup_and_rebuild() {
     assert_eq!(restored_rows[0].entity_id, user_id);
 }
 
+#[tokio::test]
+async fn runtime_delete_projection_orphans_keeps_live_rows() {
+    let dir = tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    runtime
+        .register_projection_contract(
+            RuntimeProjectionContract::new("User", "user_projection").with_field(
+                RuntimeProjectionField::new("email", "email", RuntimePayloadType::Text)
+                    .indexed(true),
+            ),
+        )
+        .unwrap();
+
+    let alice = runtime
+        .create_entity(
+            "User",
+            "user_state",
+            json!({ "email": "alice@example.com" }),
+            1,
+        )
+        .await
+        .unwrap();
+    let bob = runtime
+        .create_entity(
+            "User",
+            "user_state",
+            json!({ "email": "bob@example.com" }),
+            1,
+        )
+        .await
+        .unwrap();
+    runtime.delete_entity("User", &bob, "test").await.unwrap();
+
+    assert_eq!(runtime.delete_projection_orphans("User").unwrap(), 0);
+    let rows = runtime.list_projection_rows("User").unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].entity_id, alice);
+    assert!(runtime.delete_projection_orphans("Order").is_err());
+}
+
+#[tokio::test]
+async fn runtime_count_projection_rows_where_filters_without_listing() {
+    let dir = tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    runtime
+        .register_projection_contract(
+            RuntimeProjectionContract::new("User", "user_projection")
+                .with_field(
+                    RuntimeProjectionField::new("email", "email", RuntimePayloadType::Text)
+                        .indexed(true),
+                )
+                .with_field(RuntimeProjectionField::new(
+                    "active",
+                    "active",
+                    RuntimePayloadType::Boolean,
+                )),
+        )
+        .unwrap();
+
+    for (email, active) in [
+        ("alice@example.com", true),
+        ("bob@example.com", false),
+        ("carol@example.com", true),
+    ] {
+        runtime
+            .create_entity(
+                "User",
+                "user_state",
+                json!({ "email": email, "active": active }),
+                1,
+            )
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(runtime.count_projection_rows_where("User", &[]).unwrap(), 3);
+    assert_eq!(
+        runtime
+            .count_projection_rows_where("User", &[("active", json!(true))])
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        runtime
+            .count_projection_rows_where(
+                "User",
+                &[("email", json!("bob@example.com")), ("active", json!(true))]
+            )
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        runtime
+            .count_projection_rows_where("User", &[("email", json!("carol@example.com"))])
+            .unwrap(),
+        1
+    );
+    assert!(
+        runtime
+            .count_projection_rows_where("User", &[("missing", json!(1))])
+            .is_err()
+    );
+    assert!(runtime.count_projection_rows_where("Order", &[]).is_err());
+}
+
 #[tokio::test]
 async fn runtime_consistency_mode_normalizes_operational_policy() {
     let dir = tempdir().unwrap();
@@ -1070,3 +1179,156 @@ async fn runtime_chaos_crash_recovery_with_lifecycle_preserves_state() {
     let stats = recovered.stats();
     assert_eq!(stats.lifecycle_gc_deleted_total, 0);
 }
+
+#[tokio::test]
+async fn runtime_create_entity_idempotent_replays_original_record() {
+    let dir = tempdir().unwrap();
+    let policy = RuntimeOperationalPolicy {
+        durability: RuntimeDurabilityMode::Strict,
+        ..Default::default()
+    };
+
+    let persist_id = {
+        let mut runtime = PersistEntityRuntime::open(dir.path(), policy.clone())
+            .await
+            .unwrap();
+
+        let created = runtime
+            .create_entity_idempotent(
+                "Counter",
+                "runtime_counter",
+                json!({"count": 7}),
+                1,
+                "create-1",
+            )
+            .await
+            .unwrap();
+        assert!(!created.idempotent_replay);
+
+        let retried = runtime
+            .create_entity_idempotent(
+                "Counter",
+                "runtime_counter",
+                json!({"count": 7}),
+                1,
+                "create-1",
+            )
+            .await
+            .unwrap();
+        assert!(retried.idempotent_replay);
+        assert_eq!(retried.envelope_id, created.envelope_id);
+        assert_eq!(retried.state.persist_id, created.state.persist_id);
+        assert_eq!(runtime.list_states().len(), 1);
+
+        let other = runtime
+            .create_entity_idempotent(
+                "Counter",
+                "runtime_counter",
+                json!({"count": 1}),
+                1,
+                "create-2",
+            )
+            .await
+            .unwrap();
+        assert!(!other.idempotent_replay);
+        assert_ne!(other.state.persist_id, created.state.persist_id);
+        assert_eq!(runtime.list_states().len(), 2);
+
+        created.state.persist_id
+    };
+
+    let mut reopened = PersistEntityRuntime::open(dir.path(), policy)
+        .await
+        .unwrap();
+    let replay = reopened
+        .create_entity_idempotent(
+            "Counter",
+            "runtime_counter",
+            json!({"count": 7}),
+            1,
+            "create-1",
+        )
+        .await
+        .unwrap();
+    assert!(replay.idempotent_replay);
+    assert_eq!(replay.state.persist_id, persist_id);
+    assert_eq!(count_from_state(&replay.state), 7);
+    assert_eq!(reopened.list_states().len(), 2);
+}
+
+#[tokio::test]
+async fn runtime_idempotency_key_is_scoped_per_entity_and_command() {
+    let dir = tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    for command in ["increment", "increment:bulk"] {
+        runtime.register_deterministic_envelope_command(
+            "Counter",
+            command,
+            Arc::new(|state, _envelope| {
+                let fields = state.fields_object_mut()?;
+                let current = fields
+                    .get("count")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or_default();
+                fields.insert("count".to_string(), json!(current + 1));
+                state.metadata.version = state.metadata.version.saturating_add(1);
+                Ok(Vec::new())
+            }),
+        );
+    }
+
+    let first_id = runtime
+        .create_entity("Counter", "runtime_counter", json!({"count": 0}), 1)
+        .await
+        .unwrap();
+    let second_id = runtime
+        .create_entity("Counter", "runtime_counter", json!({"count": 0}), 1)
+        .await
+        .unwrap();
+
+    for persist_id in [&first_id, &second_id] {
+        let applied = runtime
+            .apply_command_envelope(
+                RuntimeCommandEnvelope::new("Counter", persist_id, "increment", json!({}))
+                    .with_idempotency_key("client-req-1"),
+            )
+            .await
+            .unwrap();
+        assert!(!applied.idempotent_replay);
+        assert_eq!(count_from_state(&applied.state), 1);
+    }
+
+    // "increment" + "bulk:client-req-1" must not alias "increment:bulk" + "client-req-1".
+    let aliased = runtime
+        .apply_command_envelope(
+            RuntimeCommandEnvelope::new("Counter", &first_id, "increment", json!({}))
+                .with_idempotency_key("bulk:client-req-1"),
+        )
+        .await
+        .unwrap();
+    assert!(!aliased.idempotent_replay);
+    let other_command = runtime
+        .apply_command_envelope(
+            RuntimeCommandEnvelope::new("Counter", &first_id, "increment:bulk", json!({}))
+                .with_idempotency_key("client-req-1"),
+        )
+        .await
+        .unwrap();
+    assert!(!other_command.idempotent_replay);
+    assert_eq!(count_from_state(&other_command.state), 3);
+
+    let replay = runtime
+        .apply_command_envelope(
+            RuntimeCommandEnvelope::new("Counter", &second_id, "increment", json!({}))
+                .with_idempotency_key("client-req-1"),
+        )
+        .await
+        .unwrap();
+    assert!(replay.idempotent_replay);
+    assert_eq!(
+        count_from_state(&runtime.get_state("Counter", &second_id).unwrap()),
+        1
+    );
+}
@@ -1,5 +1,8 @@
 use rustmemodb::{
-    InMemoryDB, PersistEntityRuntime, PersistSession, RuntimeOperationalPolicy, RuntimePayloadType,
+    InMemoryDB, PersistCommandRateLimiter, PersistEntity, PersistEntityFactory,
+    PersistEntityRuntime, PersistSession, PersistValidate, RuntimeCommandEnvelope,
+    RuntimeOperationalPolicy, RuntimePayloadType, is_command_input_error,
+    is_command_rate_limited_error,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -13,8 +16,38 @@ pub struct WalletModel {
     pub balance: i64,
 }
 
+#[rustmemodb::persistent(table = "budget_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[aggregate(count, exists, sum = "balance", avg = "balance")]
+pub struct BudgetModel {
+    #[sql]
+    pub owner: String,
+    #[sql]
+    pub balance: i64,
+}
+
+#[rustmemodb::persistent(table = "savings_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SavingsModel {
+    #[sql(index)]
+    pub owner: String,
+    #[sql(index)]
+    pub balance: i64,
+}
+
+#[rustmemodb::persistent(table = "member_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MemberModel {
+    #[sql(unique)]
+    pub email: String,
+    #[sql(index, unique)]
+    pub handle: String,
+    pub nickname: Option<String>,
+}
+
 #[rustmemodb::persistent_impl]
 impl WalletModel {
+    /// Deposit funds into the wallet
     #[rustmemodb::command]
     pub fn deposit(&mut self, amount: i64) -> rustmemodb::Result<i64> {
         if amount <= 0 {
@@ -30,6 +63,71 @@ impl WalletModel {
     pub fn rename_owner(&mut self, owner: String) {
         self.owner = owner;
     }
+
+    #[rustmemodb::computed]
+    pub fn balance_label(&self) -> String {
+        format!("{}: {}", self.owner, self.balance)
+    }
+}
+
+#[rustmemodb::persistent(table = "report_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReportModel {
+    #[sql]
+    pub runs: i64,
+}
+
+#[rustmemodb::persistent_impl]
+impl ReportModel {
+    #[rustmemodb::command(min_interval_ms = 60000)]
+    pub fn recompute(&mut self) -> i64 {
+        self.runs += 1;
+        self.runs
+    }
+
+    #[rustmemodb::command]
+    pub fn reset(&mut self) {
+        self.runs = 0;
+    }
+
+    #[rustmemodb::command(deprecated = "use reset instead; removed in 2.0")]
+    pub fn clear_runs(&mut self) {
+        self.runs = 0;
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransferRequest {
+    pub to: String,
+    pub amount: i64,
+}
+
+impl PersistValidate for TransferRequest {
+    fn validate(&self) -> Result<(), String> {
+        if self.to.trim().is_empty() {
+            return Err("recipient is required".to_string());
+        }
+        if self.amount <= 0 {
+            return Err(format!("amount must be positive, got {}", self.amount));
+        }
+        Ok(())
+    }
+}
+
+#[rustmemodb::persistent(table = "ledger_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LedgerModel {
+    #[sql]
+    pub sent: i64,
+}
+
+#[rustmemodb::persistent_impl]
+impl LedgerModel {
+    #[rustmemodb::command]
+    pub fn transfer(&mut self, request: TransferRequest, fee: i64) -> i64 {
+        self.sent += request.amount + fee;
+        self.sent
+    }
 }
 
 #[test]
@@ -46,6 +144,8 @@ fn persistent_impl_generates_command_contract_and_names() {
 
     assert_eq!(cmd_a.name(), "deposit");
     assert_eq!(cmd_b.name(), "rename_owner");
+    assert_eq!(cmd_a.describe(), "Deposit funds into the wallet");
+    assert_eq!(cmd_b.describe(), "rename_owner");
 
     let payload = cmd_a.payload_json().unwrap();
     assert_eq!(payload.get("amount").and_then(|v| v.as_i64()), Some(1));
@@ -156,6 +256,126 @@ async fn persistent_impl_registers_domain_commands_in_runtime() {
     assert_eq!(applied.state.metadata.version, 2);
 }
 
+#[tokio::test]
+async fn aggregate_attribute_generates_projection_aggregate_helpers() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    BudgetModelPersisted::register_projection_in_runtime(&mut runtime).unwrap();
+
+    assert!(!BudgetModelPersisted::projection_exists(&runtime).unwrap());
+    assert_eq!(
+        BudgetModelPersisted::projection_avg_balance(&runtime).unwrap(),
+        None
+    );
+
+    for (owner, balance) in [("alice", 10), ("bob", 25), ("carol", 40)] {
+        runtime
+            .create_entity(
+                "BudgetModel",
+                "budget_runtime",
+                json!({ "owner": owner, "balance": balance }),
+                1,
+            )
+            .await
+            .unwrap();
+    }
+
+    assert!(BudgetModelPersisted::projection_exists(&runtime).unwrap());
+    assert_eq!(BudgetModelPersisted::projection_count(&runtime).unwrap(), 3);
+    assert_eq!(
+        BudgetModelPersisted::projection_sum_balance(&runtime).unwrap(),
+        75.0
+    );
+    assert_eq!(
+        BudgetModelPersisted::projection_avg_balance(&runtime).unwrap(),
+        Some(25.0)
+    );
+
+    let err = runtime
+        .sum_projection_column("BudgetModel", "owner")
+        .unwrap_err();
+    assert!(err.to_string().contains("not numeric"), "{}", err);
+}
+
+#[tokio::test]
+async fn projection_range_scans_return_rows_ordered_by_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    SavingsModelPersisted::register_projection_in_runtime(&mut runtime).unwrap();
+
+    let mut ids = Vec::new();
+    for (owner, balance) in [("dave", 55), ("bob", 25), ("alice", 10), ("carol", 40)] {
+        ids.push(
+            runtime
+                .create_entity(
+                    "SavingsModel",
+                    "savings_runtime",
+                    json!({ "owner": owner, "balance": balance }),
+                    1,
+                )
+                .await
+                .unwrap(),
+        );
+    }
+
+    let balances = |rows: Vec<rustmemodb::RuntimeProjectionRow>| {
+        rows.iter()
+            .map(|row| row.values["balance"].as_i64().unwrap())
+            .collect::<Vec<_>>()
+    };
+    let between =
+        SavingsModelPersisted::find_projection_rows_by_balance_between(&runtime, 20, 40).unwrap();
+    assert_eq!(balances(between), vec![25, 40]);
+
+    let exclusive = runtime
+        .find_projection_rows_by_range(
+            "SavingsModel",
+            "balance",
+            Some(&json!(10)),
+            Some(&json!(55)),
+            false,
+        )
+        .unwrap();
+    assert_eq!(balances(exclusive), vec![25, 40]);
+
+    let open_ended = runtime
+        .find_projection_rows_by_range("SavingsModel", "balance", Some(&json!(26)), None, true)
+        .unwrap();
+    assert_eq!(balances(open_ended), vec![40, 55]);
+
+    let owners = runtime
+        .find_projection_rows_by_range(
+            "SavingsModel",
+            "owner",
+            Some(&json!("b")),
+            Some(&json!("d")),
+            true,
+        )
+        .unwrap()
+        .iter()
+        .map(|row| row.values["owner"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(owners, vec!["bob", "carol"]);
+
+    runtime
+        .delete_entity("SavingsModel", &ids[1], "closed")
+        .await
+        .unwrap();
+    let after_delete =
+        SavingsModelPersisted::find_projection_rows_by_balance_between(&runtime, 0, 100).unwrap();
+    assert_eq!(balances(after_delete), vec![10, 40, 55]);
+
+    assert!(
+        runtime
+            .find_projection_rows_by_range("SavingsModel", "balance", Some(&json!([1])), None, true)
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn persistent_impl_registers_projection_contract_and_index_helpers() {
     let dir = tempfile::tempdir().unwrap();
@@ -209,3 +429,510 @@ async fn persistent_impl_registers_projection_contract_and_index_helpers() {
         WalletModelPersisted::find_projection_ids_by_owner(&runtime, "bob".to_string()).unwrap();
     assert_eq!(new, vec![id]);
 }
+
+#[tokio::test]
+async fn persistent_impl_rate_limits_commands_per_entity() {
+    assert_eq!(
+        ReportModelPersisted::domain_command_min_interval_ms("recompute"),
+        Some(60000)
+    );
+    assert_eq!(
+        ReportModelPersisted::domain_command_min_interval_ms("reset"),
+        None
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut first = ReportModel { runs: 0 }.into_persisted();
+    first.bind_session(session.clone());
+    first.save_bound().await.unwrap();
+    let mut second = ReportModel { runs: 0 }.into_persisted();
+    second.bind_session(session);
+    second.save_bound().await.unwrap();
+
+    let result = first
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Recompute)
+        .await
+        .unwrap();
+    assert_eq!(result.as_i64(), Some(1));
+
+    let err = first
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Recompute)
+        .await
+        .expect_err("second recompute must be throttled");
+    assert!(
+        is_command_rate_limited_error(&err),
+        "unexpected error: {err}"
+    );
+    assert_eq!(first.state_json()["runs"].as_i64(), Some(1));
+
+    first
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Reset)
+        .await
+        .unwrap();
+    second
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Recompute)
+        .await
+        .unwrap();
+
+    // Limits belong to the session family, not the process.
+    let other_app = PersistSession::new(InMemoryDB::new());
+    let mut elsewhere = ReportModel { runs: 0 }.into_persisted();
+    elsewhere.bind_session(other_app.clone());
+    elsewhere.save_bound().await.unwrap();
+    let mut copy = ReportModelPersisted::from_state(&elsewhere.state()).unwrap();
+    copy.bind_session(other_app.clone());
+    elsewhere
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Recompute)
+        .await
+        .unwrap();
+    assert_eq!(other_app.command_limits().active_len(), 1);
+    let err = copy
+        .apply_domain_command_persisted(ReportModelPersistentCommand::Recompute)
+        .await
+        .expect_err("a second copy of the entity shares the limit");
+    assert!(is_command_rate_limited_error(&err));
+}
+
+#[tokio::test]
+async fn command_rate_limiter_reserves_atomically_and_evicts_expired_entries() {
+    let limiter = PersistCommandRateLimiter::default();
+    let reservation = limiter
+        .try_acquire("Report", "recompute", "r1", 60_000)
+        .unwrap();
+    assert!(
+        limiter
+            .try_acquire("Report", "recompute", "r1", 60_000)
+            .is_err()
+    );
+
+    // A command that fails gives its slot back.
+    limiter.release(reservation);
+    limiter
+        .try_acquire("Report", "recompute", "r1", 60_000)
+        .unwrap();
+
+    limiter.try_acquire("Report", "recompute", "r2", 1).unwrap();
+    assert_eq!(limiter.active_len(), 2);
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    limiter.try_acquire("Report", "recompute", "r2", 1).unwrap();
+    assert_eq!(limiter.active_len(), 2);
+}
+
+#[tokio::test]
+async fn runtime_enforces_command_min_interval() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    ReportModelPersisted::try_register_domain_commands_in_runtime(&mut runtime).unwrap();
+    let id = runtime
+        .create_entity("ReportModel", "report_runtime", json!({ "runs": 0 }), 1)
+        .await
+        .unwrap();
+
+    runtime
+        .apply_deterministic_command("ReportModel", &id, "recompute", json!({}))
+        .await
+        .unwrap();
+    let err = runtime
+        .apply_deterministic_command("ReportModel", &id, "recompute", json!({}))
+        .await
+        .expect_err("second recompute must be throttled");
+    assert!(
+        is_command_rate_limited_error(&err),
+        "unexpected error: {err}"
+    );
+    let state = runtime
+        .apply_deterministic_command("ReportModel", &id, "reset", json!({}))
+        .await
+        .unwrap();
+    assert_eq!(state.fields["runs"], json!(0));
+}
+
+#[tokio::test]
+async fn persistent_impl_marks_deprecated_commands_in_contract() {
+    assert_eq!(
+        ReportModelPersisted::domain_command_deprecation("clear_runs"),
+        Some("use reset instead; removed in 2.0")
+    );
+    assert_eq!(
+        ReportModelPersisted::domain_command_deprecation("reset"),
+        None
+    );
+
+    let contract = ReportModelPersisted::domain_command_contract();
+    let clear_runs = contract
+        .iter()
+        .find(|command| command.name == "clear_runs")
+        .expect("clear_runs contract");
+    assert_eq!(
+        clear_runs.deprecated.as_deref(),
+        Some("use reset instead; removed in 2.0")
+    );
+    assert!(
+        contract
+            .iter()
+            .filter(|command| command.name != "clear_runs")
+            .all(|command| command.deprecated.is_none())
+    );
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut report = ReportModel { runs: 3 }.into_persisted();
+    report.bind_session(session);
+    report
+        .apply_domain_command_persisted(ReportModelPersistentCommand::ClearRuns)
+        .await
+        .expect("deprecated commands stay functional");
+    assert_eq!(report.data().runs, 0);
+}
+
+#[rustmemodb::persistent(table = "report_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReportCopyModel {
+    #[sql]
+    pub runs: i64,
+}
+
+#[rustmemodb::persistent_impl]
+impl ReportCopyModel {
+    #[rustmemodb::command]
+    pub fn bump(&mut self) -> i64 {
+        self.runs += 1;
+        self.runs
+    }
+}
+
+#[tokio::test]
+async fn register_models_registers_all_and_rejects_table_collisions() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+
+    let err = rustmemodb::register_models!(
+        &mut runtime,
+        WalletModelPersisted,
+        ReportModelPersisted,
+        ReportCopyModelPersisted,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("report_dsl"), "{}", err);
+    assert!(runtime.list_projection_rows("WalletModel").is_err());
+
+    rustmemodb::register_models!(&mut runtime, WalletModelPersisted, ReportModelPersisted).unwrap();
+    assert!(runtime.list_projection_rows("WalletModel").is_ok());
+    assert!(runtime.list_projection_rows("ReportModel").is_ok());
+}
+
+#[tokio::test]
+async fn runtime_registration_rejects_second_model_on_same_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+
+    ReportModelPersisted::try_register_domain_commands_in_runtime(&mut runtime).unwrap();
+    // Re-registering the same model is allowed.
+    ReportModelPersisted::try_register_domain_commands_in_runtime(&mut runtime).unwrap();
+    assert_eq!(runtime.model_table_owner("report_dsl"), Some("ReportModel"));
+
+    let err = ReportCopyModelPersisted::try_register_domain_commands_in_runtime(&mut runtime)
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("'report_dsl'"), "{}", message);
+    assert!(message.contains("ReportModel"), "{}", message);
+    assert!(message.contains("ReportCopyModel"), "{}", message);
+
+    let err = rustmemodb::register_models!(&mut runtime, ReportCopyModelPersisted).unwrap_err();
+    assert!(err.to_string().contains("'report_dsl'"), "{}", err);
+}
+
+#[test]
+fn persistent_impl_computed_fields_are_serialized_but_not_stored() {
+    let wallet = WalletModel {
+        owner: "alice".to_string(),
+        balance: 42,
+    }
+    .into_persisted();
+
+    assert_eq!(
+        WalletModelPersisted::computed_field_names(),
+        &["balance_label"]
+    );
+    let json = wallet.state_json_with_computed().unwrap();
+    assert_eq!(json["balance_label"], json!("alice: 42"));
+    assert_eq!(json["balance"], json!(42));
+
+    assert!(wallet.state_json().get("balance_label").is_none());
+    assert!(
+        rustmemodb::PersistEntity::state(&wallet)
+            .fields
+            .get("balance_label")
+            .is_none()
+    );
+    assert_eq!(WalletModelPersisted::domain_command_contract().len(), 2);
+}
+
+#[tokio::test]
+async fn runtime_replays_entity_commands_from_journal() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    WalletModelPersisted::try_register_domain_commands_in_runtime(&mut runtime).unwrap();
+
+    let id = runtime
+        .create_entity(
+            "WalletModel",
+            "wallet_dsl",
+            json!({"owner": "alice", "balance": 10}),
+            2,
+        )
+        .await
+        .unwrap();
+    for command in [
+        WalletModelPersistentCommand::Deposit { amount: 5 },
+        WalletModelPersistentCommand::RenameOwner {
+            owner: "bob".to_string(),
+        },
+        WalletModelPersistentCommand::Deposit { amount: 7 },
+    ] {
+        runtime
+            .apply_command_envelope(command.to_runtime_envelope(&id).unwrap())
+            .await
+            .unwrap();
+    }
+
+    let live = runtime.get_state("WalletModel", &id).unwrap();
+    let replayed = runtime
+        .replay_entity_from_journal("WalletModel", &id)
+        .await
+        .unwrap();
+    assert_eq!(replayed.fields, json!({"owner": "bob", "balance": 22}));
+    assert_eq!(replayed, live);
+
+    assert!(
+        runtime
+            .replay_entity_from_journal("WalletModel", "missing")
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn persistent_impl_validates_command_inputs_before_mutation() {
+    let mut ledger = LedgerModel { sent: 0 }.into_persisted();
+
+    let err = ledger
+        .apply_domain_command(LedgerModelPersistentCommand::Transfer {
+            request: TransferRequest {
+                to: "bob".to_string(),
+                amount: -5,
+            },
+            fee: 1,
+        })
+        .unwrap_err();
+    assert!(is_command_input_error(&err), "{err}");
+    assert!(err.to_string().contains("field 'request'"), "{err}");
+    assert!(err.to_string().contains("amount must be positive"), "{err}");
+    assert_eq!(ledger.state_json()["sent"], json!(0));
+
+    let sent = ledger
+        .apply_domain_command(LedgerModelPersistentCommand::Transfer {
+            request: TransferRequest {
+                to: "bob".to_string(),
+                amount: 5,
+            },
+            fee: -1,
+        })
+        .unwrap();
+    assert_eq!(sent, json!(4));
+}
+
+#[rustmemodb::persistent(table = "account_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountModel {
+    #[sql]
+    pub active: bool,
+}
+
+#[rustmemodb::persistent_impl]
+impl AccountModel {
+    #[rustmemodb::command(name = ["deactivate", "disable"])]
+    pub fn deactivate(&mut self) {
+        self.active = false;
+    }
+}
+
+#[tokio::test]
+async fn persistent_impl_command_aliases_dispatch_to_same_method() {
+    assert_eq!(
+        AccountModelPersistentCommand::Deactivate.name(),
+        "deactivate"
+    );
+    assert_eq!(
+        AccountModelPersisted::domain_command_deprecation("deactivate"),
+        None
+    );
+    assert_eq!(
+        AccountModelPersisted::domain_command_deprecation("disable"),
+        Some("alias of 'deactivate'")
+    );
+    assert!(AccountModelPersistentCommand::runtime_payload_schema_by_name("disable").is_some());
+
+    let contract = AccountModelPersisted::domain_command_contract();
+    let names = contract
+        .iter()
+        .map(|command| (command.name.as_str(), command.deprecated.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            ("deactivate", None),
+            ("disable", Some("alias of 'deactivate'")),
+        ]
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    AccountModelPersisted::try_register_domain_commands_in_runtime(&mut runtime).unwrap();
+
+    let id = runtime
+        .create_entity("AccountModel", "account_dsl", json!({ "active": true }), 1)
+        .await
+        .unwrap();
+    let envelope = RuntimeCommandEnvelope::new("AccountModel", id, "disable", json!({}));
+    let applied = runtime.apply_command_envelope(envelope).await.unwrap();
+    assert_eq!(applied.state.fields["active"], json!(false));
+}
+
+#[rustmemodb::persistent(table = "counter_dsl")]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CounterModel {
+    #[sql]
+    pub hits: i64,
+}
+
+#[rustmemodb::persistent_impl]
+impl CounterModel {
+    #[rustmemodb::command(serialize_per_entity)]
+    pub fn hit(&mut self) -> i64 {
+        self.hits += 1;
+        self.hits
+    }
+
+    #[rustmemodb::command]
+    pub fn hit_unserialized(&mut self) -> i64 {
+        self.hits += 1;
+        self.hits
+    }
+}
+
+#[tokio::test]
+async fn persistent_impl_serializes_commands_per_entity() {
+    assert!(CounterModelPersisted::domain_command_serialized_per_entity(
+        "hit"
+    ));
+    assert!(!CounterModelPersisted::domain_command_serialized_per_entity("hit_unserialized"));
+
+    let session = PersistSession::new(InMemoryDB::new());
+    let mut first = CounterModel { hits: 0 }.into_persisted();
+    first.bind_session(session.clone());
+    first.save_bound().await.unwrap();
+    let mut second = CounterModelPersisted::from_state(&first.state()).unwrap();
+    second.bind_session(session.clone());
+    let mut stale = CounterModelPersisted::from_state(&first.state()).unwrap();
+    stale.bind_session(session);
+    stale.metadata_mut().persisted = true;
+
+    let (a, b) = tokio::join!(
+        first.apply_domain_command_persisted(CounterModelPersistentCommand::Hit),
+        second.apply_domain_command_persisted(CounterModelPersistentCommand::Hit),
+    );
+    let mut results = vec![a.unwrap(), b.unwrap()];
+    results.sort_by_key(|value| value.as_i64());
+    assert_eq!(results, vec![json!(1), json!(2)]);
+
+    let err = stale
+        .apply_domain_command_persisted(CounterModelPersistentCommand::HitUnserialized)
+        .await
+        .expect_err("stale copy must conflict without the entity lock");
+    assert!(
+        err.to_string().contains("Optimistic lock conflict"),
+        "unexpected error: {err}"
+    );
+
+    let result = stale
+        .apply_domain_command_persisted(CounterModelPersistentCommand::Hit)
+        .await
+        .unwrap();
+    assert_eq!(result, json!(3));
+    assert_eq!(stale.metadata().version, 4);
+}
+
+#[tokio::test]
+async fn sql_unique_projection_column_rejects_duplicate_values() {
+    let contract = MemberModelPersisted::projection_contract().unwrap();
+    assert!(
+        contract
+            .fields
+            .iter()
+            .all(|field| field.unique && field.indexed)
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = PersistEntityRuntime::open(dir.path(), RuntimeOperationalPolicy::default())
+        .await
+        .unwrap();
+    MemberModelPersisted::register_projection_in_runtime(&mut runtime).unwrap();
+
+    let ada = runtime
+        .create_entity(
+            "MemberModel",
+            "member_dsl",
+            json!({ "email": "ada@example.com", "handle": "ada", "nickname": null }),
+            1,
+        )
+        .await
+        .unwrap();
+    let err = runtime
+        .create_entity(
+            "MemberModel",
+            "member_dsl",
+            json!({ "email": "ada@example.com", "handle": "countess", "nickname": null }),
+            1,
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, rustmemodb::DbError::ConstraintViolation(_)),
+        "{err:?}"
+    );
+    assert_eq!(runtime.count_projection_rows("MemberModel").unwrap(), 1);
+
+    assert_eq!(
+        MemberModelPersisted::find_projection_id_by_email(&runtime, "ada@example.com".to_string())
+            .unwrap(),
+        Some(ada.clone())
+    );
+    assert_eq!(
+        MemberModelPersisted::find_projection_id_by_handle(&runtime, "grace".to_string()).unwrap(),
+        None
+    );
+
+    runtime
+        .delete_entity("MemberModel", &ada, "left")
+        .await
+        .unwrap();
+    runtime
+        .create_entity(
+            "MemberModel",
+            "member_dsl",
+            json!({ "email": "ada@example.com", "handle": "ada", "nickname": null }),
+            1,
+        )
+        .await
+        .unwrap();
+}
@@ -362,3 +362,154 @@ async fn test_transaction_error_handling() {
     // ROLLBACK should be no-op
     assert!(conn.rollback().await.is_ok());
 }
+
+#[tokio::test]
+async fn test_savepoint_rollback_to_keeps_earlier_work() {
+    let client = Client::connect("admin", "adminpass").await.unwrap();
+
+    client
+        .execute("CREATE TABLE test_savepoint (id INTEGER, data TEXT)")
+        .await
+        .unwrap();
+    client
+        .execute("INSERT INTO test_savepoint VALUES (1, 'seed')")
+        .await
+        .unwrap();
+
+    let mut conn = client.get_connection().await.unwrap();
+    conn.begin().await.unwrap();
+    conn.execute("INSERT INTO test_savepoint VALUES (2, 'kept')")
+        .await
+        .unwrap();
+
+    conn.savepoint("step").await.unwrap();
+    conn.execute("INSERT INTO test_savepoint VALUES (3, 'undone')")
+        .await
+        .unwrap();
+    conn.execute("UPDATE test_savepoint SET data = 'changed' WHERE id = 1")
+        .await
+        .unwrap();
+    conn.execute("DELETE FROM test_savepoint WHERE id = 2")
+        .await
+        .unwrap();
+
+    conn.rollback_to("step").await.unwrap();
+    let result = conn
+        .execute("SELECT id, data FROM test_savepoint ORDER BY id")
+        .await
+        .unwrap();
+    assert_eq!(result.row_count(), 2);
+    assert_eq!(result.rows()[0][1].to_string(), "seed");
+    assert_eq!(result.rows()[1][1].to_string(), "kept");
+
+    // The savepoint survives rollback_to and can be reused
+    conn.execute("INSERT INTO test_savepoint VALUES (4, 'retry')")
+        .await
+        .unwrap();
+    conn.execute("ROLLBACK TO SAVEPOINT step").await.unwrap();
+    conn.execute("INSERT INTO test_savepoint VALUES (5, 'final')")
+        .await
+        .unwrap();
+    conn.execute("RELEASE SAVEPOINT step").await.unwrap();
+    assert!(conn.rollback_to("step").await.is_err());
+
+    conn.commit().await.unwrap();
+
+    let result = client
+        .query("SELECT id FROM test_savepoint ORDER BY id")
+        .await
+        .unwrap();
+    let ids: Vec<String> = result.rows().iter().map(|row| row[0].to_string()).collect();
+    assert_eq!(ids, vec!["1", "2", "5"]);
+}
+
+#[tokio::test]
+async fn test_failed_statement_rolls_back_transaction_with_or_without_savepoint() {
+    let client = Client::connect("admin", "adminpass").await.unwrap();
+
+    client
+        .execute("CREATE TABLE test_statement_failure (id INTEGER PRIMARY KEY)")
+        .await
+        .unwrap();
+    client
+        .execute("INSERT INTO test_statement_failure VALUES (1)")
+        .await
+        .unwrap();
+
+    for use_savepoint in [false, true] {
+        let mut conn = client.get_connection().await.unwrap();
+        conn.begin().await.unwrap();
+        conn.execute("INSERT INTO test_statement_failure VALUES (2)")
+            .await
+            .unwrap();
+        if use_savepoint {
+            conn.savepoint("step").await.unwrap();
+        }
+
+        // Row 3 is written before the duplicate key fails the statement
+        assert!(
+            conn.execute("INSERT INTO test_statement_failure VALUES (3), (1)")
+                .await
+                .is_err()
+        );
+        assert!(
+            !conn.connection().is_in_transaction(),
+            "savepoint: {use_savepoint}"
+        );
+        assert!(conn.rollback_to("step").await.is_err());
+
+        let result = client
+            .query("SELECT id FROM test_statement_failure ORDER BY id")
+            .await
+            .unwrap();
+        let ids: Vec<String> = result.rows().iter().map(|row| row[0].to_string()).collect();
+        assert_eq!(ids, vec!["1"], "savepoint: {use_savepoint}");
+    }
+}
+
+#[tokio::test]
+async fn test_savepoint_requires_active_transaction() {
+    let client = Client::connect("admin", "adminpass").await.unwrap();
+    let mut conn = client.get_connection().await.unwrap();
+
+    let err = conn.savepoint("outside").await.unwrap_err();
+    assert!(err.to_string().contains("active transaction"));
+    assert!(conn.rollback_to("outside").await.is_err());
+    assert!(conn.release("outside").is_err());
+    assert!(conn.execute("SAVEPOINT outside").await.is_err());
+
+    conn.begin().await.unwrap();
+    let err = conn.rollback_to("unknown").await.unwrap_err();
+    assert!(err.to_string().contains("does not exist"));
+    conn.rollback().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_transaction_reinserts_unique_key_it_deleted() {
+    let client = Client::connect("admin", "adminpass").await.unwrap();
+    client
+        .execute("CREATE TABLE test_tx_reuse_key (id INTEGER PRIMARY KEY, data TEXT)")
+        .await
+        .unwrap();
+    client
+        .execute("INSERT INTO test_tx_reuse_key VALUES (1, 'old')")
+        .await
+        .unwrap();
+
+    let mut conn = client.get_connection().await.unwrap();
+    conn.begin().await.unwrap();
+    conn.execute("DELETE FROM test_tx_reuse_key WHERE id = 1")
+        .await
+        .unwrap();
+    conn.execute("INSERT INTO test_tx_reuse_key VALUES (1, 'new')")
+        .await
+        .unwrap();
+    conn.commit().await.unwrap();
+
+    let result = client
+        .query("SELECT data FROM test_tx_reuse_key")
+        .await
+        .unwrap();
+    assert_eq!(result.row_count(), 1);
+    assert_eq!(result.rows()[0][0].to_string(), "new");
+}